@@ -23,9 +23,11 @@ use std::collections::VecDeque;
 use std::error::Error as StdError;
 use std::future::Future;
 use std::hash::Hash;
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 use salvo_core::handler::Skipper;
+use salvo_core::http::header::{HeaderName, HeaderValue, CACHE_CONTROL, VARY};
 use salvo_core::http::{HeaderMap, ResBody, StatusCode};
 use salvo_core::{async_trait, Depot, Error, FlowCtrl, Handler, Request, Response};
 
@@ -157,6 +159,12 @@ pub trait CacheStore: Send + Sync + 'static {
         Q: Hash + Eq + Sync;
     /// Save the cache item from the store.
     fn save_entry(&self, key: Self::Key, data: CachedEntry) -> impl Future<Output = Result<(), Self::Error>> + Send;
+    /// Remove all cache items whose key matches the predicate.
+    ///
+    /// Stores which can not enumerate their keys should return an error instead of ignoring the call.
+    fn invalidate_matching<F>(&self, predicate: F) -> impl Future<Output = Result<(), Self::Error>> + Send
+    where
+        F: Fn(&Self::Key) -> bool + Send + Sync + 'static;
 }
 
 /// `CachedBody` is used to save response body to `CachedStore`.
@@ -206,11 +214,45 @@ pub struct CachedEntry {
     ///
     /// *Notice: If the response's body is streaming, it will be ignored an not cached.
     pub body: CachedBody,
+    /// The instant when this entry is expired, it is computed from response's `Cache-Control: max-age`.
+    pub expires_at: Option<Instant>,
+    /// Request header values listed in response's `Vary` header when this entry is cached.
+    pub vary: Vec<(HeaderName, Option<HeaderValue>)>,
+    /// Other cached variants of the same key, which are cached for different values of the request
+    /// headers listed in `Vary`.
+    pub variants: Vec<CachedEntry>,
 }
 impl CachedEntry {
     /// Create a new `CachedEntry`.
     pub fn new(status: Option<StatusCode>, headers: HeaderMap, body: CachedBody) -> Self {
-        Self { status, headers, body }
+        Self {
+            status,
+            headers,
+            body,
+            expires_at: None,
+            vary: Vec::new(),
+            variants: Vec::new(),
+        }
+    }
+
+    /// Check is this entry expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map(|at| at <= Instant::now()).unwrap_or(false)
+    }
+
+    /// Check is this entry matches the request's headers listed in response's `Vary` header.
+    pub fn is_vary_matched(&self, req: &Request) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| req.headers().get(name) == value.as_ref())
+    }
+
+    /// Find the entry or the variant which is not expired and matches the request's headers listed in
+    /// response's `Vary` header.
+    pub fn matched_variant(&self, req: &Request) -> Option<&CachedEntry> {
+        std::iter::once(self)
+            .chain(self.variants.iter())
+            .find(|entry| !entry.is_expired() && entry.is_vary_matched(req))
     }
 
     /// Get the response status.
@@ -231,7 +273,72 @@ impl CachedEntry {
     }
 }
 
+/// Cache directives parsed from response's `Cache-Control` header.
+#[derive(Default)]
+struct CacheDirectives {
+    no_store: bool,
+    private: bool,
+    max_age: Option<Duration>,
+}
+impl CacheDirectives {
+    fn parse(headers: &HeaderMap) -> Self {
+        let mut directives = Self::default();
+        for value in headers.get_all(CACHE_CONTROL).iter().filter_map(|v| v.to_str().ok()) {
+            for item in value.split(',') {
+                let item = item.trim().to_ascii_lowercase();
+                match item.split_once('=') {
+                    Some(("max-age", secs)) => {
+                        directives.max_age = secs.trim_matches('"').parse().ok().map(Duration::from_secs);
+                    }
+                    None if item == "no-store" => directives.no_store = true,
+                    None if item == "private" => directives.private = true,
+                    _ => {}
+                }
+            }
+        }
+        directives
+    }
+    fn is_cacheable(&self) -> bool {
+        !self.no_store && !self.private && self.max_age != Some(Duration::ZERO)
+    }
+}
+
+/// Max count of variants cached for one key.
+const MAX_VARIANTS: usize = 16;
+
+/// Collect request header values listed in response's `Vary` header, returns `None` if `Vary` is `*`.
+fn vary_values(req: &Request, res: &Response) -> Option<Vec<(HeaderName, Option<HeaderValue>)>> {
+    let mut names = Vec::new();
+    for value in res.headers().get_all(VARY).iter().filter_map(|v| v.to_str().ok()) {
+        for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if name == "*" {
+                return None;
+            }
+            if let Ok(name) = HeaderName::from_bytes(name.as_bytes()) {
+                names.push(name);
+            }
+        }
+    }
+    names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    names.dedup();
+    Some(
+        names
+            .into_iter()
+            .map(|name| {
+                let value = req.headers().get(&name).cloned();
+                (name, value)
+            })
+            .collect(),
+    )
+}
+
 /// A constructed via `salvo_cache::Cache::builder()`.
+///
+/// Only successful (2xx) responses are cached. Responses with `Cache-Control: no-store`, `private`
+/// or `max-age=0` are not cached, and `max-age` is used as the entry's time to live if presents.
+/// If the response has a `Vary` header, the cached entry is only used for requests with the same
+/// values of the listed headers, responses with `Vary: *` are not cached. Variants for different
+/// values are cached together under the same key.
 #[non_exhaustive]
 pub struct Cache<S, I> {
     /// Cache store.
@@ -260,6 +367,19 @@ impl<S, I> Cache<S, I> {
         self
     }
 }
+impl<S, I> Cache<S, I>
+where
+    S: CacheStore,
+{
+    /// Remove all cached entries whose key matches the predicate.
+    #[inline]
+    pub async fn invalidate_matching<F>(&self, predicate: F) -> Result<(), S::Error>
+    where
+        F: Fn(&S::Key) -> bool + Send + Sync + 'static,
+    {
+        self.store.invalidate_matching(predicate).await
+    }
+}
 
 #[async_trait]
 impl<S, I> Handler for Cache<S, I>
@@ -277,27 +397,48 @@ where
                 return;
             }
         };
-        let cache = match self.store.load_entry(&key).await {
-            Some(cache) => cache,
+        let stored = self.store.load_entry(&key).await;
+        let cache = match stored.as_ref().and_then(|entry| entry.matched_variant(req)) {
+            Some(cache) => cache.clone(),
             None => {
                 ctrl.call_next(req, depot, res).await;
-                if !res.body.is_stream() && !res.body.is_error() {
-                    let headers = res.headers().clone();
-                    let body = TryInto::<CachedBody>::try_into(&res.body);
-                    match body {
-                        Ok(body) => {
-                            let cached_data = CachedEntry::new(res.status_code, headers, body);
-                            if let Err(e) = self.store.save_entry(key, cached_data).await {
-                                tracing::error!(error = ?e, "cache failed");
-                            }
+                let is_success = res.status_code.map(|s| s.is_success()).unwrap_or(true);
+                if !is_success || res.body.is_stream() || res.body.is_error() {
+                    return;
+                }
+                let directives = CacheDirectives::parse(res.headers());
+                if !directives.is_cacheable() {
+                    return;
+                }
+                let Some(vary) = vary_values(req, res) else {
+                    return;
+                };
+                let headers = res.headers().clone();
+                let body = TryInto::<CachedBody>::try_into(&res.body);
+                match body {
+                    Ok(body) => {
+                        let mut cached_data = CachedEntry::new(res.status_code, headers, body);
+                        cached_data.expires_at = directives.max_age.map(|max_age| Instant::now() + max_age);
+                        if let Some(mut stored) = stored {
+                            let mut variants = std::mem::take(&mut stored.variants);
+                            variants.insert(0, stored);
+                            variants.retain(|v| !v.is_expired() && v.vary != vary);
+                            variants.truncate(MAX_VARIANTS - 1);
+                            cached_data.variants = variants;
+                        }
+                        cached_data.vary = vary;
+                        if let Err(e) = self.store.save_entry(key, cached_data).await {
+                            tracing::error!(error = ?e, "cache failed");
                         }
-                        Err(e) => tracing::error!(error = ?e, "cache failed"),
                     }
+                    Err(e) => tracing::error!(error = ?e, "cache failed"),
                 }
                 return;
             }
         };
-        let CachedEntry { status, headers, body } = cache;
+        let CachedEntry {
+            status, headers, body, ..
+        } = cache;
         if let Some(status) = status {
             res.status_code(status);
         }
@@ -347,4 +488,104 @@ mod tests {
 
         assert_ne!(content0, content2);
     }
+
+    #[handler]
+    async fn with_headers(req: &mut Request, res: &mut Response) {
+        if let Some(cache_control) = req.query::<String>("cc") {
+            res.add_header(CACHE_CONTROL, cache_control, true).unwrap();
+        }
+        if let Some(vary) = req.query::<String>("vary") {
+            res.add_header(VARY, vary, true).unwrap();
+        }
+        if req.query::<bool>("fail").unwrap_or(false) {
+            res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        res.render(format!("{:?}", OffsetDateTime::now_utc()));
+    }
+
+    async fn access(service: &Service, url: &str, lang: &str) -> String {
+        TestClient::get(url)
+            .add_header("accept-language", lang, true)
+            .send(service)
+            .await
+            .take_string()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cache_control() {
+        let cache = Cache::new(MokaStore::new(100), RequestIssuer::default());
+        let service = Service::new(Router::new().hoop(cache).goal(with_headers));
+
+        for url in [
+            "http://127.0.0.1:5801/?cc=no-store",
+            "http://127.0.0.1:5801/?cc=private,%20max-age=60",
+            "http://127.0.0.1:5801/?cc=max-age=0",
+            "http://127.0.0.1:5801/?fail=true",
+            "http://127.0.0.1:5801/?vary=*",
+        ] {
+            let content0 = access(&service, url, "en").await;
+            let content1 = access(&service, url, "en").await;
+            assert_ne!(content0, content1, "{url} should not be cached");
+        }
+
+        let url = "http://127.0.0.1:5801/?cc=public,%20max-age=60";
+        let content0 = access(&service, url, "en").await;
+        let content1 = access(&service, url, "en").await;
+        assert_eq!(content0, content1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_vary() {
+        let cache = Cache::new(MokaStore::new(100), RequestIssuer::default());
+        let service = Service::new(Router::new().hoop(cache).goal(with_headers));
+
+        let url = "http://127.0.0.1:5801/?vary=Accept-Language";
+        let content0 = access(&service, url, "en").await;
+        assert_eq!(content0, access(&service, url, "en").await);
+        let content1 = access(&service, url, "zh").await;
+        assert_ne!(content0, content1);
+        assert_eq!(content1, access(&service, url, "zh").await);
+        assert_eq!(content0, access(&service, url, "en").await);
+        assert_eq!(content1, access(&service, url, "zh").await);
+    }
+
+    #[tokio::test]
+    async fn test_cache_max_age_expired() {
+        let cache = Cache::new(MokaStore::new(100), RequestIssuer::default());
+        let service = Service::new(Router::new().hoop(cache).goal(with_headers));
+
+        let url = "http://127.0.0.1:5801/?cc=max-age=1";
+        let content0 = access(&service, url, "en").await;
+        assert_eq!(content0, access(&service, url, "en").await);
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        assert_ne!(content0, access(&service, url, "en").await);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_matching() {
+        let store = MokaStore::new(100);
+        store
+            .save_entry(
+                "a|GET".to_owned(),
+                CachedEntry::new(None, HeaderMap::new(), CachedBody::None),
+            )
+            .await
+            .unwrap();
+        store
+            .save_entry(
+                "b|GET".to_owned(),
+                CachedEntry::new(None, HeaderMap::new(), CachedBody::None),
+            )
+            .await
+            .unwrap();
+        let cache = Cache::new(store, RequestIssuer::default());
+        cache
+            .invalidate_matching(|key: &String| key.starts_with('a'))
+            .await
+            .unwrap();
+        assert!(cache.store.load_entry(&"a|GET".to_owned()).await.is_none());
+        assert!(cache.store.load_entry(&"b|GET".to_owned()).await.is_some());
+    }
 }
@@ -132,4 +132,20 @@ where
         self.inner.insert(key, entry).await;
         Ok(())
     }
+
+    async fn invalidate_matching<F>(&self, predicate: F) -> Result<(), Self::Error>
+    where
+        F: Fn(&Self::Key) -> bool + Send + Sync + 'static,
+    {
+        let keys = self
+            .inner
+            .iter()
+            .filter(|(key, _)| predicate(key))
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        for key in keys {
+            self.inner.invalidate(&*key).await;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,150 @@
+//! Decompress the body of a request.
+use std::fmt::{self, Display, Formatter};
+#[allow(unused_imports)]
+use std::io::{Error as IoError, ErrorKind, Result as IoResult, Write};
+
+#[cfg(feature = "brotli")]
+use brotli::DecompressorWriter as BrotliDecoder;
+use bytes::{Bytes, BytesMut};
+#[cfg(feature = "gzip")]
+use flate2::write::GzDecoder;
+#[cfg(feature = "deflate")]
+use flate2::write::ZlibDecoder;
+#[cfg(feature = "zstd")]
+use zstd::stream::write::Decoder as ZstdDecoder;
+
+use super::CompressionAlgo;
+
+/// Error returned when the decoded data is larger than the limit.
+#[derive(Debug)]
+pub(super) struct SizeLimitExceeded;
+impl Display for SizeLimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("decoded data is too large")
+    }
+}
+impl std::error::Error for SizeLimitExceeded {}
+
+impl SizeLimitExceeded {
+    /// Whether the io error is caused by exceeding the limit.
+    pub(super) fn is(e: &IoError) -> bool {
+        e.get_ref().is_some_and(|e| e.is::<SizeLimitExceeded>())
+    }
+}
+
+/// Writer keeps the decoded data, it fails as soon as the total size of decoded data exceeds `max_size`,
+/// so a small but highly compressed body can not exhaust memory.
+pub(super) struct LimitedWriter {
+    buf: BytesMut,
+    written: usize,
+    max_size: usize,
+    exceeded: bool,
+}
+
+impl LimitedWriter {
+    fn new(max_size: usize) -> Self {
+        Self {
+            buf: BytesMut::with_capacity(8192.min(max_size)),
+            written: 0,
+            max_size,
+            exceeded: false,
+        }
+    }
+
+    fn take(&mut self) -> Bytes {
+        self.buf.split().freeze()
+    }
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        if self.written + buf.len() > self.max_size {
+            self.exceeded = true;
+            return Err(IoError::other(SizeLimitExceeded));
+        }
+        self.written += buf.len();
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+pub(super) enum Decoder {
+    #[cfg(feature = "brotli")]
+    Brotli(Box<BrotliDecoder<LimitedWriter>>),
+    #[cfg(feature = "deflate")]
+    Deflate(ZlibDecoder<LimitedWriter>),
+    #[cfg(feature = "gzip")]
+    Gzip(GzDecoder<LimitedWriter>),
+    #[cfg(feature = "zstd")]
+    Zstd(ZstdDecoder<'static, LimitedWriter>),
+}
+
+impl Decoder {
+    /// Creates a decoder, decoding fails with [`SizeLimitExceeded`] if the decoded data is larger than `max_size`.
+    pub(super) fn new(algo: CompressionAlgo, max_size: usize) -> IoResult<Self> {
+        let writer = LimitedWriter::new(max_size);
+        match algo {
+            #[cfg(feature = "brotli")]
+            CompressionAlgo::Brotli => Ok(Self::Brotli(Box::new(BrotliDecoder::new(writer, 8 * 1024)))),
+            #[cfg(feature = "deflate")]
+            CompressionAlgo::Deflate => Ok(Self::Deflate(ZlibDecoder::new(writer))),
+            #[cfg(feature = "gzip")]
+            CompressionAlgo::Gzip => Ok(Self::Gzip(GzDecoder::new(writer))),
+            #[cfg(feature = "zstd")]
+            CompressionAlgo::Zstd => ZstdDecoder::new(writer).map(Self::Zstd),
+        }
+    }
+
+    #[allow(unused_variables)]
+    pub(super) fn write(&mut self, data: &[u8]) -> IoResult<()> {
+        match *self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(ref mut decoder) => decoder.write_all(data),
+            #[cfg(feature = "deflate")]
+            Self::Deflate(ref mut decoder) => decoder.write_all(data),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut decoder) => decoder.write_all(data),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut decoder) => decoder.write_all(data),
+        }
+    }
+
+    /// Takes the data decoded so far.
+    pub(super) fn take(&mut self) -> Bytes {
+        match *self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(ref mut decoder) => decoder.get_mut().take(),
+            #[cfg(feature = "deflate")]
+            Self::Deflate(ref mut decoder) => decoder.get_mut().take(),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut decoder) => decoder.get_mut().take(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut decoder) => decoder.get_mut().take(),
+        }
+    }
+
+    /// Finishes decoding and returns the remaining data, fails if the compressed data is incomplete.
+    pub(super) fn finish(self) -> IoResult<Bytes> {
+        match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(decoder) => match decoder.into_inner() {
+                Ok(mut writer) => Ok(writer.take()),
+                Err(writer) if writer.exceeded => Err(IoError::other(SizeLimitExceeded)),
+                Err(_) => Err(IoError::new(ErrorKind::UnexpectedEof, "brotli data is incomplete")),
+            },
+            #[cfg(feature = "deflate")]
+            Self::Deflate(decoder) => decoder.finish().map(|mut writer| writer.take()),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(decoder) => decoder.finish().map(|mut writer| writer.take()),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(mut decoder) => {
+                decoder.flush()?;
+                Ok(decoder.into_inner().take())
+            }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Request decompression middleware.
+use bytes::{Bytes, BytesMut};
+use futures_util::StreamExt;
+use salvo_core::http::body::ReqBody;
+use salvo_core::http::header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH};
+use salvo_core::http::request::secure_max_size;
+use salvo_core::http::StatusError;
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Request, Response};
+
+use super::decoder::{Decoder, SizeLimitExceeded};
+use super::CompressionAlgo;
+
+/// Middleware decompresses request body according to `Content-Encoding` header.
+///
+/// Supported encodings are the same as [`CompressionAlgo`]. After decompression, the `Content-Encoding`
+/// header is removed and `Content-Length` is set to the decompressed size, so the following handlers
+/// see a plain body.
+///
+/// Returns `415 Unsupported Media Type` if the encoding is not supported, `400 Bad Request` if the
+/// body can not be decompressed and `413 Payload Too Large` if the decompressed body is larger than
+/// [`max_size`](Decompression::max_size).
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_compression::Decompression;
+///
+/// #[handler]
+/// async fn upload(req: &mut Request) -> String {
+///     let payload = req.payload().await.map(|b| b.len()).unwrap_or_default();
+///     format!("received {payload} bytes")
+/// }
+///
+/// let router = Router::with_hoop(Decompression::new()).post(upload);
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Decompression {
+    /// Max size of both compressed and decompressed body, default is [`secure_max_size`].
+    pub max_size: usize,
+}
+
+impl Default for Decompression {
+    fn default() -> Self {
+        Self {
+            max_size: secure_max_size(),
+        }
+    }
+}
+
+impl Decompression {
+    /// Create a new `Decompression`.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets max size of both compressed and decompressed body and returns new `Decompression`.
+    #[inline]
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    fn decode(&self, algo: CompressionAlgo, data: &[u8]) -> Result<Bytes, StatusError> {
+        let map_err = |e: std::io::Error| {
+            if SizeLimitExceeded::is(&e) {
+                StatusError::payload_too_large().brief("Decompressed request body is too large.")
+            } else {
+                StatusError::bad_request()
+                    .brief("Request body can not be decompressed.")
+                    .detail(e.to_string())
+            }
+        };
+        // The decoder fails as soon as the decompressed data exceeds `max_size`.
+        let mut decoder = Decoder::new(algo, self.max_size).map_err(map_err)?;
+        let mut decoded = BytesMut::new();
+        for chunk in data.chunks(8 * 1024) {
+            decoder.write(chunk).map_err(map_err)?;
+            decoded.extend_from_slice(&decoder.take());
+        }
+        decoded.extend_from_slice(&decoder.finish().map_err(map_err)?);
+        Ok(decoded.freeze())
+    }
+
+    async fn decompress(&self, req: &mut Request) -> Result<(), StatusError> {
+        let Some(encoding) = req.headers().get(CONTENT_ENCODING) else {
+            return Ok(());
+        };
+        let encoding = encoding
+            .to_str()
+            .map_err(|_| StatusError::bad_request().brief("Invalid `Content-Encoding` header."))?;
+        // Encodings are listed in the order they were applied, so decode in reverse order.
+        let mut algos = Vec::new();
+        for name in encoding.split(',').map(str::trim).rev() {
+            if name.is_empty() || name.eq_ignore_ascii_case("identity") {
+                continue;
+            }
+            match name.to_ascii_lowercase().parse::<CompressionAlgo>() {
+                Ok(algo) => algos.push(algo),
+                Err(_) => {
+                    return Err(
+                        StatusError::unsupported_media_type().brief(format!("Unsupported content encoding: `{name}`."))
+                    );
+                }
+            }
+        }
+
+        let mut body = req.take_body();
+        let mut data = BytesMut::new();
+        while let Some(frame) = body.next().await {
+            let frame = frame.map_err(|e| {
+                StatusError::bad_request()
+                    .brief("Failed to read request body.")
+                    .detail(e.to_string())
+            })?;
+            if let Some(chunk) = frame.data_ref() {
+                if data.len() + chunk.len() > self.max_size {
+                    return Err(StatusError::payload_too_large());
+                }
+                data.extend_from_slice(chunk);
+            }
+        }
+        let mut data = data.freeze();
+        for algo in algos {
+            data = self.decode(algo, &data)?;
+        }
+
+        let headers = req.headers_mut();
+        headers.remove(CONTENT_ENCODING);
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(data.len()));
+        req.replace_body(ReqBody::Once(data));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Decompression {
+    async fn handle(&self, req: &mut Request, _depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if let Err(e) = self.decompress(req).await {
+            res.render(e);
+            ctrl.skip_rest();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn echo(req: &mut Request) -> String {
+        let encoding = req.headers().contains_key(CONTENT_ENCODING);
+        let body = req.payload().await.map(|b| String::from_utf8_lossy(b).into_owned());
+        format!("{encoding}|{}", body.unwrap_or_default())
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_decompression() {
+        let service = Service::new(Router::with_hoop(Decompression::new()).post(echo));
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header(CONTENT_ENCODING, "gzip", true)
+            .body(gzip(br#"{"name":"salvo"}"#))
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), r#"false|{"name":"salvo"}"#);
+
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(b"hello").unwrap();
+        let mut res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header(CONTENT_ENCODING, "br", true)
+            .body(brotli.into_inner())
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "false|hello");
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/")
+            .body("plain")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "false|plain");
+    }
+
+    #[tokio::test]
+    async fn test_decompression_errors() {
+        let service = Service::new(Router::with_hoop(Decompression::new().max_size(16)).post(echo));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header(CONTENT_ENCODING, "compress", true)
+            .body("hello")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header(CONTENT_ENCODING, "gzip", true)
+            .body("not gzip data")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header(CONTENT_ENCODING, "gzip", true)
+            .body(gzip(&[b'a'; 1024]))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::PAYLOAD_TOO_LARGE));
+    }
+
+    #[test]
+    fn test_decode_bomb() {
+        // 16 MiB of zeros is compressed to a few KiB, decoding must stop as soon as it exceeds `max_size`.
+        let data = vec![0u8; 16 * 1024 * 1024];
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(&data).unwrap();
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+        zlib.write_all(&data).unwrap();
+        let bombs = [
+            (CompressionAlgo::Gzip, gzip(&data)),
+            (CompressionAlgo::Deflate, zlib.finish().unwrap()),
+            (CompressionAlgo::Brotli, brotli.into_inner()),
+            (CompressionAlgo::Zstd, zstd::encode_all(&data[..], 3).unwrap()),
+        ];
+        let decompression = Decompression::new().max_size(64 * 1024);
+        for (algo, bomb) in bombs {
+            assert!(bomb.len() < 64 * 1024);
+            let err = decompression.decode(algo, &bomb).unwrap_err();
+            assert_eq!(err.code, StatusCode::PAYLOAD_TOO_LARGE, "{algo:?}");
+        }
+        assert_eq!(
+            Decompression::new()
+                .decode(CompressionAlgo::Gzip, &gzip(b"hello"))
+                .unwrap(),
+            Bytes::from_static(b"hello")
+        );
+    }
+}
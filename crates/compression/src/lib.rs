@@ -1,5 +1,7 @@
 //! Compression middleware for for Savlo web server framework.
 //!
+//! [`Compression`] compresses response bodies and [`Decompression`] decompresses request bodies.
+//!
 //! Read more: <https://salvo.rs>
 use std::fmt::{self, Display};
 use std::str::FromStr;
@@ -7,12 +9,15 @@ use std::str::FromStr;
 use indexmap::IndexMap;
 
 use salvo_core::http::body::ResBody;
-use salvo_core::http::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE};
+use salvo_core::http::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, VARY};
 use salvo_core::http::{self, Mime, StatusCode};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Request, Response};
 
+mod decoder;
+mod decompression;
 mod encoder;
 mod stream;
+pub use decompression::Decompression;
 use encoder::Encoder;
 use stream::EncodeStream;
 
@@ -257,9 +262,9 @@ impl Compression {
         self
     }
 
-    fn negotiate(&self, req: &Request, res: &Response) -> Option<(CompressionAlgo, CompressionLevel)> {
+    fn is_negotiable(&self, req: &Request, res: &Response) -> bool {
         if req.headers().contains_key(&CONTENT_ENCODING) {
-            return None;
+            return false;
         }
 
         if !self.content_types.is_empty() {
@@ -269,19 +274,23 @@ impl Compression {
                 .and_then(|v| v.to_str().ok())
                 .unwrap_or_default();
             if content_type.is_empty() {
-                return None;
+                return false;
             }
             if let Ok(content_type) = content_type.parse::<Mime>() {
                 if !self.content_types.iter().any(|citem| {
                     citem.type_() == content_type.type_()
                         && (citem.subtype() == "*" || citem.subtype() == content_type.subtype())
                 }) {
-                    return None;
+                    return false;
                 }
             } else {
-                return None;
+                return false;
             }
         }
+        true
+    }
+
+    fn negotiate(&self, req: &Request) -> Option<(CompressionAlgo, CompressionLevel)> {
         let header = req.headers().get(ACCEPT_ENCODING).and_then(|v| v.to_str().ok())?;
 
         let accept_algos = http::parse_accept_encoding(header)
@@ -322,70 +331,41 @@ impl Handler for Compression {
             }
         }
 
-        match res.take_body() {
-            ResBody::None => {
-                return;
-            }
-            ResBody::Once(bytes) => {
-                if self.min_length > 0 && bytes.len() < self.min_length {
-                    res.body(ResBody::Once(bytes));
-                    return;
-                }
-                match self.negotiate(req, res) {
-                    Some((algo, level)) => {
-                        res.stream(EncodeStream::new(algo, level, Some(bytes)));
-                        res.headers_mut().append(CONTENT_ENCODING, algo.into());
-                    }
-                    None => {
-                        res.body(ResBody::Once(bytes));
-                        return;
-                    }
-                }
-            }
-            ResBody::Chunks(chunks) => {
-                if self.min_length > 0 {
-                    let len: usize = chunks.iter().map(|c| c.len()).sum();
-                    if len < self.min_length {
-                        res.body(ResBody::Chunks(chunks));
-                        return;
-                    }
-                }
-                match self.negotiate(req, res) {
-                    Some((algo, level)) => {
-                        res.stream(EncodeStream::new(algo, level, chunks));
-                        res.headers_mut().append(CONTENT_ENCODING, algo.into());
-                    }
-                    None => {
-                        res.body(ResBody::Chunks(chunks));
-                        return;
-                    }
-                }
-            }
-            ResBody::Hyper(body) => match self.negotiate(req, res) {
-                Some((algo, level)) => {
-                    res.stream(EncodeStream::new(algo, level, body));
-                    res.headers_mut().append(CONTENT_ENCODING, algo.into());
-                }
-                None => {
-                    res.body(ResBody::Hyper(body));
-                    return;
-                }
-            },
-            ResBody::Stream(body) => {
-                let body = body.into_inner();
-                match self.negotiate(req, res) {
-                    Some((algo, level)) => {
-                        res.stream(EncodeStream::new(algo, level, body));
-                        res.headers_mut().append(CONTENT_ENCODING, algo.into());
-                    }
-                    None => {
-                        res.body(ResBody::stream(body));
-                        return;
-                    }
-                }
-            }
-            _ => {}
+        let body = res.take_body();
+        let too_small = self.min_length > 0
+            && match &body {
+                ResBody::Once(bytes) => bytes.len() < self.min_length,
+                ResBody::Chunks(chunks) => chunks.iter().map(|c| c.len()).sum::<usize>() < self.min_length,
+                _ => false,
+            };
+        let encodable = matches!(
+            body,
+            ResBody::Once(_)
+                | ResBody::Chunks(_)
+                | ResBody::Hyper(_)
+                | ResBody::Stream(_)
+                | ResBody::Boxed(_)
+                | ResBody::Channel(_)
+        );
+        if too_small || !encodable || !self.is_negotiable(req, res) {
+            res.body(body);
+            return;
         }
+        // The response now depends on `Accept-Encoding`, even if it ends up not compressed.
+        res.headers_mut()
+            .append(VARY, HeaderValue::from_static("accept-encoding"));
+        let Some((algo, level)) = self.negotiate(req) else {
+            res.body(body);
+            return;
+        };
+        match body {
+            ResBody::Once(bytes) => res.stream(EncodeStream::new(algo, level, Some(bytes))),
+            ResBody::Chunks(chunks) => res.stream(EncodeStream::new(algo, level, chunks)),
+            ResBody::Hyper(body) => res.stream(EncodeStream::new(algo, level, body)),
+            ResBody::Stream(body) => res.stream(EncodeStream::new(algo, level, body.into_inner())),
+            body => res.stream(EncodeStream::new(algo, level, body.into_boxed_stream())),
+        };
+        res.headers_mut().append(CONTENT_ENCODING, algo.into());
         res.headers_mut().remove(CONTENT_LENGTH);
     }
 }
@@ -444,4 +424,40 @@ mod tests {
         let content = res.take_string().await.unwrap();
         assert_eq!(content, "hello");
     }
+
+    #[tokio::test]
+    async fn test_vary_without_compression() {
+        let comp_handler = Compression::new().min_length(1);
+        let router = Router::with_hoop(comp_handler).push(Router::with_path("hello").get(hello));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "identity", true)
+            .send(router)
+            .await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.headers().get(VARY).unwrap(), "accept-encoding");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_compress_channel_body() {
+        #[handler]
+        async fn channel(res: &mut Response) {
+            res.add_header(CONTENT_TYPE, "text/plain", true).unwrap();
+            let mut sender = res.channel();
+            tokio::spawn(async move {
+                sender.send_data("hello ").await.unwrap();
+                sender.send_data("world").await.unwrap();
+            });
+        }
+        let router = Router::with_hoop(Compression::new()).push(Router::with_path("channel").get(channel));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/channel")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(router)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(res.headers().get(VARY).unwrap(), "accept-encoding");
+        assert_eq!(res.take_string().await.unwrap(), "hello world");
+    }
 }
@@ -437,14 +437,16 @@ where
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: _,
+            tls_client_cert,
         } = self.inner.accept().await?;
         Ok(Accepted {
             conn: HandshakeStream::new(self.tls_acceptor.accept(conn)),
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: Scheme::HTTPS,
+            tls_client_cert,
         })
     }
 }
@@ -0,0 +1,331 @@
+//! Support of the `Upgrade: h2c` mechanism.
+//!
+//! An upgrade request is answered with `101 Switching Protocols`, then the request is replayed as stream 1
+//! of an HTTP/2 connection on the same socket, see [RFC 7540](https://www.rfc-editor.org/rfc/rfc7540#section-3.2).
+use std::future::Future;
+use std::io::{Error as IoError, Result as IoResult};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use http::request::Parts;
+use http::{Method, StatusCode, Version};
+use http_body_util::{BodyExt, Limited};
+use hyper::body::Incoming;
+use hyper::service::Service as HyperService;
+use hyper::upgrade::{OnUpgrade, Upgraded};
+use hyper::{Request as HyperRequest, Response as HyperResponse};
+use parking_lot::Mutex;
+use tokio::io::AsyncReadExt;
+
+use crate::http::body::ResBody;
+use crate::rt::tokio::TokioIo;
+use crate::service::HyperHandler;
+
+use super::proto::{Rewind, H2_PREFACE};
+
+/// Default `SETTINGS_MAX_FRAME_SIZE`, the largest frame the server accepts before its settings are acknowledged.
+const MAX_FRAME_SIZE: usize = 16_384;
+/// Default initial flow control window, the largest body the client may send on stream 1.
+const INITIAL_WINDOW_SIZE: usize = 65_535;
+
+const FRAME_DATA: u8 = 0x0;
+const FRAME_HEADERS: u8 = 0x1;
+const FRAME_SETTINGS: u8 = 0x4;
+const FLAG_END_STREAM: u8 = 0x1;
+const FLAG_END_HEADERS: u8 = 0x4;
+
+/// Headers which are connection-specific and must not be sent in HTTP/2.
+const CONNECTION_HEADERS: [&str; 8] = [
+    "connection",
+    "upgrade",
+    "http2-settings",
+    "host",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "te",
+];
+
+/// An accepted upgrade, waiting for the HTTP/1.1 connection to hand over the socket.
+pub(crate) struct H2cUpgrade {
+    on_upgrade: OnUpgrade,
+    frames: Bytes,
+}
+impl H2cUpgrade {
+    /// Wait for the upgraded socket and read the client connection preface.
+    ///
+    /// The returned socket reads the preface, the first `SETTINGS` frame and the replayed request again.
+    pub(crate) async fn into_io(self) -> IoResult<Rewind<TokioIo<Upgraded>>> {
+        let upgraded = self.on_upgrade.await.map_err(IoError::other)?;
+        let mut io = TokioIo::new(upgraded);
+        let mut buf = BytesMut::with_capacity(H2_PREFACE.len() + 9 + self.frames.len());
+        buf.resize(H2_PREFACE.len() + 9, 0);
+        io.read_exact(&mut buf).await?;
+        if &buf[..H2_PREFACE.len()] != H2_PREFACE {
+            return Err(IoError::other("invalid http2 connection preface"));
+        }
+        let head = &buf[H2_PREFACE.len()..];
+        let len = usize::from(head[0]) << 16 | usize::from(head[1]) << 8 | usize::from(head[2]);
+        if head[3] != FRAME_SETTINGS || len > MAX_FRAME_SIZE {
+            return Err(IoError::other("http2 connection preface is not followed by settings"));
+        }
+        let start = buf.len();
+        buf.resize(start + len, 0);
+        io.read_exact(&mut buf[start..]).await?;
+        buf.extend_from_slice(&self.frames);
+        Ok(Rewind::new_buffered(buf.freeze(), io))
+    }
+}
+
+/// Service answers `Upgrade: h2c` requests with `101 Switching Protocols`, other requests are passed to the handler.
+pub(crate) struct H2cService {
+    pub(crate) handler: HyperHandler,
+    /// Slot of the accepted upgrade, upgrades are disabled if it is `None`.
+    pub(crate) upgrade: Option<Arc<Mutex<Option<H2cUpgrade>>>>,
+}
+
+impl HyperService<HyperRequest<Incoming>> for H2cService {
+    type Response = HyperResponse<ResBody>;
+    type Error = hyper::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn call(&self, mut req: HyperRequest<Incoming>) -> Self::Future {
+        let Some(upgrade) = self.upgrade.clone().filter(|_| is_upgrade_request(&req)) else {
+            return self.handler.call(req);
+        };
+        let on_upgrade = hyper::upgrade::on(&mut req);
+        let handler = self.handler.clone();
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            // The whole body must be read before switching protocols, its length is checked by
+            // `is_upgrade_request`, the limit guards against a body longer than `Content-Length`.
+            let body = match Limited::new(body, INITIAL_WINDOW_SIZE).collect().await {
+                Ok(body) => body.to_bytes(),
+                Err(e) => {
+                    return match e.downcast::<hyper::Error>() {
+                        Ok(e) => Err(*e),
+                        Err(_) => {
+                            let mut res = HyperResponse::new(ResBody::None);
+                            *res.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+                            Ok(res)
+                        }
+                    };
+                }
+            };
+            match encode_request(&parts, &body) {
+                Some(frames) => {
+                    *upgrade.lock() = Some(H2cUpgrade { on_upgrade, frames });
+                    let mut res = HyperResponse::new(ResBody::None);
+                    *res.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+                    res.headers_mut()
+                        .insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+                    res.headers_mut()
+                        .insert(header::UPGRADE, HeaderValue::from_static("h2c"));
+                    Ok(res)
+                }
+                // Too large to be replayed before the settings are acknowledged, so stay on HTTP/1.1.
+                None => handler.call(HyperRequest::from_parts(parts, body)).await,
+            }
+        })
+    }
+}
+
+fn header_tokens(headers: &HeaderMap, name: HeaderName) -> impl Iterator<Item = &str> {
+    headers
+        .get_all(name)
+        .into_iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+}
+
+/// Check whether the request asks to upgrade to h2c.
+///
+/// The body is read into memory before switching protocols, so requests with `Transfer-Encoding`, or with
+/// `Content-Length` larger than the initial window, are not upgraded and are served as HTTP/1.1.
+pub(crate) fn is_upgrade_request<B>(req: &HyperRequest<B>) -> bool {
+    if req.version() != Version::HTTP_11 || req.method() == Method::CONNECT {
+        return false;
+    }
+    let headers = req.headers();
+    if headers.contains_key(header::TRANSFER_ENCODING) {
+        return false;
+    }
+    if let Some(len) = headers.get(header::CONTENT_LENGTH) {
+        match len.to_str().ok().and_then(|len| len.parse::<usize>().ok()) {
+            Some(len) if len <= INITIAL_WINDOW_SIZE => {}
+            _ => return false,
+        }
+    }
+    if headers.get_all(HeaderName::from_static("http2-settings")).iter().count() != 1 {
+        return false;
+    }
+    if !header_tokens(headers, header::UPGRADE).any(|token| token.eq_ignore_ascii_case("h2c")) {
+        return false;
+    }
+    let mut has_upgrade = false;
+    let mut has_settings = false;
+    for token in header_tokens(headers, header::CONNECTION) {
+        has_upgrade |= token.eq_ignore_ascii_case("upgrade");
+        has_settings |= token.eq_ignore_ascii_case("http2-settings");
+    }
+    has_upgrade && has_settings
+}
+
+/// Encode the request as the `HEADERS` and `DATA` frames of stream 1.
+///
+/// Returns `None` if the header block does not fit in one frame, or the body exceeds the initial window.
+fn encode_request(parts: &Parts, body: &Bytes) -> Option<Bytes> {
+    let authority = parts
+        .uri
+        .authority()
+        .map(|authority| authority.as_str())
+        .or_else(|| parts.headers.get(header::HOST).and_then(|host| host.to_str().ok()));
+    let path = parts.uri.path_and_query().map(|path| path.as_str()).unwrap_or("/");
+
+    let mut block = BytesMut::new();
+    encode_header(&mut block, b":method", parts.method.as_str().as_bytes());
+    encode_header(&mut block, b":scheme", b"http");
+    encode_header(&mut block, b":path", path.as_bytes());
+    if let Some(authority) = authority {
+        encode_header(&mut block, b":authority", authority.as_bytes());
+    }
+    for (name, value) in &parts.headers {
+        if !CONNECTION_HEADERS.contains(&name.as_str()) {
+            encode_header(&mut block, name.as_str().as_bytes(), value.as_bytes());
+        }
+    }
+    if block.len() > MAX_FRAME_SIZE || body.len() > INITIAL_WINDOW_SIZE {
+        return None;
+    }
+
+    let mut frames = BytesMut::with_capacity(9 + block.len() + body.len() + 9 * (body.len() / MAX_FRAME_SIZE + 1));
+    let flags = if body.is_empty() {
+        FLAG_END_HEADERS | FLAG_END_STREAM
+    } else {
+        FLAG_END_HEADERS
+    };
+    encode_frame(&mut frames, FRAME_HEADERS, flags, &block);
+    let mut chunks = body.chunks(MAX_FRAME_SIZE).peekable();
+    while let Some(chunk) = chunks.next() {
+        let flags = if chunks.peek().is_none() { FLAG_END_STREAM } else { 0 };
+        encode_frame(&mut frames, FRAME_DATA, flags, chunk);
+    }
+    Some(frames.freeze())
+}
+
+fn encode_frame(buf: &mut BytesMut, kind: u8, flags: u8, payload: &[u8]) {
+    let len = payload.len() as u32;
+    buf.put_slice(&len.to_be_bytes()[1..]);
+    buf.put_u8(kind);
+    buf.put_u8(flags);
+    // Stream 1 is the upgraded request.
+    buf.put_u32(1);
+    buf.put_slice(payload);
+}
+
+/// Encode a header as HPACK literal without indexing, with a new name and no Huffman coding.
+fn encode_header(buf: &mut BytesMut, name: &[u8], value: &[u8]) {
+    buf.put_u8(0);
+    encode_string(buf, name);
+    encode_string(buf, value);
+}
+
+fn encode_string(buf: &mut BytesMut, value: &[u8]) {
+    const PREFIX_MAX: usize = 0x7f;
+    let mut len = value.len();
+    if len < PREFIX_MAX {
+        buf.put_u8(len as u8);
+    } else {
+        buf.put_u8(PREFIX_MAX as u8);
+        len -= PREFIX_MAX;
+        while len >= 0x80 {
+            buf.put_u8((len & 0x7f) as u8 | 0x80);
+            len >>= 7;
+        }
+        buf.put_u8(len as u8);
+    }
+    buf.put_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_upgrade_request() {
+        let req = HyperRequest::get("/")
+            .header("connection", "keep-alive, Upgrade, HTTP2-Settings")
+            .header("upgrade", "websocket, H2C")
+            .header("http2-settings", "")
+            .body(())
+            .unwrap();
+        assert!(is_upgrade_request(&req));
+
+        let req = HyperRequest::get("/")
+            .header("connection", "Upgrade")
+            .header("upgrade", "h2c")
+            .header("http2-settings", "")
+            .body(())
+            .unwrap();
+        assert!(!is_upgrade_request(&req));
+
+        let req = HyperRequest::get("/")
+            .header("connection", "Upgrade, HTTP2-Settings")
+            .header("upgrade", "h2c")
+            .body(())
+            .unwrap();
+        assert!(!is_upgrade_request(&req));
+
+        let upgrade = || {
+            HyperRequest::post("/")
+                .header("connection", "Upgrade, HTTP2-Settings")
+                .header("upgrade", "h2c")
+                .header("http2-settings", "")
+        };
+        let req = upgrade().header("content-length", "1024").body(()).unwrap();
+        assert!(is_upgrade_request(&req));
+        let req = upgrade()
+            .header("content-length", (INITIAL_WINDOW_SIZE + 1).to_string())
+            .body(())
+            .unwrap();
+        assert!(!is_upgrade_request(&req));
+        let req = upgrade().header("transfer-encoding", "chunked").body(()).unwrap();
+        assert!(!is_upgrade_request(&req));
+    }
+
+    #[test]
+    fn test_encode_string() {
+        let mut buf = BytesMut::new();
+        encode_string(&mut buf, &[b'a'; 300]);
+        // 300 - 127 = 173 = 0b1_0101101
+        assert_eq!(&buf[..3], &[0x7f, 0xad, 0x01]);
+        assert_eq!(buf.len(), 303);
+    }
+
+    #[test]
+    fn test_encode_request() {
+        let (parts, _) = HyperRequest::post("/upload?a=1")
+            .header("host", "example.com")
+            .header("connection", "Upgrade, HTTP2-Settings")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let frames = encode_request(&parts, &Bytes::from(vec![0; MAX_FRAME_SIZE + 1])).unwrap();
+        // HEADERS frame, not ending the stream.
+        assert_eq!(&frames[3..9], &[FRAME_HEADERS, FLAG_END_HEADERS, 0, 0, 0, 1]);
+        let len = usize::from(frames[2]);
+        let block = &frames[9..9 + len];
+        assert!(block.windows(11).any(|w| w == b"example.com"));
+        assert!(!block.windows(10).any(|w| w == b"connection"));
+        // Two DATA frames, the last ends the stream.
+        let data = &frames[9 + len..];
+        assert_eq!(&data[..9], &[0x00, 0x40, 0x00, FRAME_DATA, 0, 0, 0, 0, 1]);
+        let last = &data[9 + MAX_FRAME_SIZE..];
+        assert_eq!(&last[..9], &[0, 0, 1, FRAME_DATA, FLAG_END_STREAM, 0, 0, 0, 1]);
+
+        assert!(encode_request(&parts, &Bytes::from(vec![0; INITIAL_WINDOW_SIZE + 1])).is_none());
+    }
+}
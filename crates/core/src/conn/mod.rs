@@ -6,8 +6,10 @@
 use std::fmt::{self, Display, Formatter};
 use std::future::Future;
 use std::io::Result as IoResult;
+use std::sync::Arc;
 
 use http::uri::Scheme;
+use once_cell::sync::OnceCell;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 use crate::http::{HttpConnection, Version};
@@ -15,6 +17,12 @@ use crate::http::{HttpConnection, Version};
 mod proto;
 pub use proto::HttpBuilder;
 
+cfg_feature! {
+    #![all(feature = "http1", feature = "http2")]
+    mod h2c;
+    pub use proto::ProtocolPriority;
+}
+
 cfg_feature! {
     #![any(feature = "native-tls", feature = "rustls", feature = "openssl", feature = "acme")]
     mod handshake_stream;
@@ -128,6 +136,26 @@ pub struct Accepted<C> {
     pub http_scheme: Scheme,
     /// Http version.
     pub http_version: Version,
+    pub(crate) tls_client_cert: TlsClientCertSlot,
+}
+
+/// Slot of the DER encoded certificate presented by the TLS client.
+///
+/// TLS handshake is done lazily after the connection is accepted, so the certificate is filled in when
+/// the handshake is finished, which is before the first request is read.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TlsClientCertSlot(Arc<OnceCell<Vec<u8>>>);
+impl TlsClientCertSlot {
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn set(&self, der: Vec<u8>) {
+        let _ = self.0.set(der);
+    }
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn get(&self) -> Option<Vec<u8>> {
+        self.0.get().cloned()
+    }
 }
 
 impl<C> Accepted<C>
@@ -143,6 +171,7 @@ where
             remote_addr,
             http_version,
             http_scheme,
+            tls_client_cert,
         } = self;
         Accepted {
             conn: wrap_fn(conn),
@@ -150,6 +179,7 @@ where
             remote_addr,
             http_version,
             http_scheme,
+            tls_client_cert,
         }
     }
 }
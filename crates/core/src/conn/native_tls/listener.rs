@@ -176,20 +176,35 @@ where
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: _,
+            tls_client_cert,
         } = self.inner.accept().await?;
-        let conn = async move {
-            tls_acceptor
-                .accept(conn)
-                .await
-                .map_err(|e| IoError::new(ErrorKind::Other, e.to_string()))
+        let conn = {
+            let tls_client_cert = tls_client_cert.clone();
+            async move {
+                let tls_stream = tls_acceptor
+                    .accept(conn)
+                    .await
+                    .map_err(|e| IoError::new(ErrorKind::Other, e.to_string()))?;
+                if let Some(der) = tls_stream
+                    .get_ref()
+                    .peer_certificate()
+                    .ok()
+                    .flatten()
+                    .and_then(|cert| cert.to_der().ok())
+                {
+                    tls_client_cert.set(der);
+                }
+                Ok(tls_stream)
+            }
         };
         Ok(Accepted {
             conn: HandshakeStream::new(conn),
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: Scheme::HTTPS,
+            tls_client_cert,
         })
     }
 }
@@ -175,8 +175,10 @@ where
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: _,
+            tls_client_cert,
         } = self.inner.accept().await?;
+        let tls_client_cert_slot = tls_client_cert.clone();
         let conn = async move {
             let ssl =
                 Ssl::new(tls_acceptor.context()).map_err(|err| IoError::new(ErrorKind::Other, err.to_string()))?;
@@ -186,6 +188,9 @@ where
                 .accept()
                 .await
                 .map_err(|err| IoError::new(ErrorKind::Other, err.to_string()))?;
+            if let Some(der) = tls_stream.ssl().peer_certificate().and_then(|cert| cert.to_der().ok()) {
+                tls_client_cert_slot.set(der);
+            }
             Ok(tls_stream)
         };
 
@@ -194,7 +199,8 @@ where
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: Scheme::HTTPS,
+            tls_client_cert,
         })
     }
 }
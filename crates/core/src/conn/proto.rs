@@ -1,5 +1,4 @@
 use std::cmp;
-use std::error::Error as StdError;
 use std::future::Future;
 use std::io::{Error as IoError, ErrorKind, IoSlice, Result as IoResult};
 use std::marker::{PhantomPinned, Unpin};
@@ -10,17 +9,23 @@ use std::time::Duration;
 
 use bytes::{Buf, Bytes};
 
-use http::{Request, Response, Version};
-use hyper::service::Service;
+use http::Version;
 use pin_project::pin_project;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::sync::Notify;
 use tokio_util::either::Either;
 use tokio_util::sync::CancellationToken;
 
-use crate::http::body::{Body, HyperBody};
+#[cfg(all(feature = "http1", feature = "http2"))]
+use http::uri::Scheme;
+#[cfg(all(feature = "http1", feature = "http2"))]
+use parking_lot::Mutex;
+
+#[cfg(all(feature = "http1", feature = "http2"))]
+use crate::conn::h2c::{H2cService, H2cUpgrade};
 #[cfg(any(feature = "http1", feature = "http2"))]
 use crate::rt::tokio::TokioIo;
+use crate::service::HyperHandler;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
@@ -34,7 +39,21 @@ use hyper::server::conn::http2;
 #[cfg(feature = "quinn")]
 use crate::conn::quinn;
 
-const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+pub(crate) const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// Which protocol is preferred on plain-text connections, when both `http1` and `http2` features are enabled.
+#[cfg(all(feature = "http1", feature = "http2"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProtocolPriority {
+    /// Prefer HTTP/2: connections starting with the HTTP/2 connection preface are served as HTTP/2 (h2c with
+    /// prior knowledge), other connections fall back to HTTP/1.1.
+    #[default]
+    Http2,
+    /// Prefer HTTP/1.1: connections are served as HTTP/1.1 without looking for the HTTP/2 connection preface,
+    /// they can only switch to HTTP/2 by the `Upgrade: h2c` mechanism if it is enabled.
+    Http1,
+}
 
 #[doc(hidden)]
 pub struct HttpBuilder {
@@ -44,27 +63,30 @@ pub struct HttpBuilder {
     pub(crate) http2: http2::Builder<TokioExecutor>,
     #[cfg(feature = "quinn")]
     pub(crate) quinn: quinn::Builder,
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    pub(crate) h2c: bool,
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    pub(crate) protocol_priority: ProtocolPriority,
 }
 impl HttpBuilder {
-    /// Bind a connection together with a [`Service`].
-    pub async fn serve_connection<I, S, B>(
+    /// Bind a connection together with a [`HyperHandler`].
+    pub async fn serve_connection<I>(
         &self,
         socket: I,
-        #[allow(unused_variables)] service: S,
+        #[allow(unused_variables)] handler: HyperHandler,
         idle_timeout: Option<Duration>,
     ) -> Result<()>
     where
-        S: Service<Request<HyperBody>, Response = Response<B>> + Send,
-        S::Future: Send + 'static,
-        S::Error: Into<Box<dyn StdError + Send + Sync>>,
-        B: Body + Send + 'static,
-        B::Data: Send,
-        B::Error: Into<Box<dyn StdError + Send + Sync>>,
         I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
     {
         let conn_shutdown_token = CancellationToken::new();
         #[cfg(all(feature = "http1", feature = "http2"))]
-        let (version, socket) = read_version(socket).await?;
+        let (version, socket) =
+            if self.protocol_priority == ProtocolPriority::Http1 && handler.http_scheme != Scheme::HTTPS {
+                (Version::HTTP_11, Rewind::new(socket))
+            } else {
+                read_version(socket).await?
+            };
         #[cfg(all(not(feature = "http1"), not(feature = "http2")))]
         let version = Version::HTTP_11; // Just make the compiler happy.
         #[cfg(all(feature = "http1", not(feature = "http2")))]
@@ -92,6 +114,16 @@ impl HttpBuilder {
                 return Err(std::io::Error::new(std::io::ErrorKind::Other, "http1 feature not enabled").into());
                 #[cfg(feature = "http1")]
                 {
+                    #[cfg(not(feature = "http2"))]
+                    let service = handler;
+                    #[cfg(feature = "http2")]
+                    let upgrade =
+                        (self.h2c && handler.http_scheme != Scheme::HTTPS).then(|| Arc::new(Mutex::new(None)));
+                    #[cfg(feature = "http2")]
+                    let service = H2cService {
+                        handler: handler.clone(),
+                        upgrade: upgrade.clone(),
+                    };
                     let mut conn = self
                         .http1
                         .serve_connection(TokioIo::new(socket), service)
@@ -99,6 +131,10 @@ impl HttpBuilder {
 
                     tokio::select! {
                         _ = &mut conn => {
+                            #[cfg(feature = "http2")]
+                            if let Some(upgrade) = upgrade.as_ref().and_then(|upgrade| upgrade.lock().take()) {
+                                return self.serve_h2c(upgrade, handler, conn_shutdown_token).await;
+                            }
                             // Connection completed successfully.
                             return Ok(());
                         },
@@ -117,7 +153,7 @@ impl HttpBuilder {
                 return Err(std::io::Error::new(std::io::ErrorKind::Other, "http2 feature not enabled").into());
                 #[cfg(feature = "http2")]
                 {
-                    let mut conn = self.http2.serve_connection(TokioIo::new(socket), service);
+                    let mut conn = self.http2.serve_connection(TokioIo::new(socket), handler);
                     tokio::select! {
                         _ = &mut conn => {
                             // Connection completed successfully.
@@ -140,6 +176,37 @@ impl HttpBuilder {
 
         Ok(())
     }
+
+    /// Serve the HTTP/2 connection after the `Upgrade: h2c` request is answered.
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    async fn serve_h2c(
+        &self,
+        upgrade: H2cUpgrade,
+        handler: HyperHandler,
+        conn_shutdown_token: CancellationToken,
+    ) -> Result<()> {
+        let socket = tokio::select! {
+            socket = upgrade.into_io() => socket?,
+            _ = conn_shutdown_token.cancelled() => {
+                tracing::info!("closing connection due to inactivity");
+                return Ok(());
+            }
+        };
+        let mut conn = self.http2.serve_connection(TokioIo::new(socket), handler);
+        tokio::select! {
+            _ = &mut conn => {
+                // Connection completed successfully.
+                return Ok(());
+            },
+            _ = conn_shutdown_token.cancelled() => {
+                tracing::info!("closing connection due to inactivity");
+            }
+        }
+
+        Pin::new(&mut conn).graceful_shutdown();
+        conn.await.ok();
+        Ok(())
+    }
 }
 
 #[pin_project]
@@ -289,7 +356,10 @@ pub(crate) struct Rewind<T> {
 }
 #[allow(dead_code)]
 impl<T> Rewind<T> {
-    fn new_buffered(buf: Bytes, io: T) -> Self {
+    pub(crate) fn new(io: T) -> Self {
+        Rewind { pre: None, inner: io }
+    }
+    pub(crate) fn new_buffered(buf: Bytes, io: T) -> Self {
         Rewind {
             pre: Some(buf),
             inner: io,
@@ -148,6 +148,7 @@ where
                         remote_addr: remote_addr.into(),
                         http_scheme: self.holdings[0].http_scheme.clone(),
                         http_version: Version::HTTP_3,
+                        tls_client_cert: Default::default(),
                     });
                 }
                 Err(e) => return Err(IoError::new(ErrorKind::Other, e.to_string())),
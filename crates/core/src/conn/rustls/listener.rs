@@ -154,14 +154,27 @@ where
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: _,
+            tls_client_cert,
         } = self.inner.accept().await?;
+        let conn = {
+            let tls_acceptor = tls_acceptor.clone();
+            let tls_client_cert = tls_client_cert.clone();
+            async move {
+                let tls_stream = tls_acceptor.accept(conn).await?;
+                if let Some(cert) = tls_stream.get_ref().1.peer_certificates().and_then(|certs| certs.first()) {
+                    tls_client_cert.set(cert.to_vec());
+                }
+                Ok(tls_stream)
+            }
+        };
         Ok(Accepted {
-            conn: HandshakeStream::new(tls_acceptor.accept(conn)),
+            conn: HandshakeStream::new(conn),
             local_addr,
             remote_addr,
             http_version,
-            http_scheme,
+            http_scheme: Scheme::HTTPS,
+            tls_client_cert,
         })
     }
 }
@@ -180,6 +180,7 @@ impl Acceptor for TcpAcceptor {
             remote_addr: remote_addr.into(),
             http_version: Version::HTTP_11,
             http_scheme: Scheme::HTTP,
+            tls_client_cert: Default::default(),
         })
     }
 }
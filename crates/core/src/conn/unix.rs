@@ -1,7 +1,7 @@
 //! UnixListener module
 use std::fs::{set_permissions, Permissions};
 use std::io::{Error as IoError, ErrorKind, Result as IoResult};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -17,6 +17,8 @@ use crate::Error;
 use super::{Accepted, Acceptor, Listener};
 
 /// `UnixListener` is used to create a Unix socket connection listener.
+///
+/// The socket file is removed when the [`UnixAcceptor`] is dropped, for example after the server is stopped.
 #[cfg(unix)]
 pub struct UnixListener<T> {
     path: T,
@@ -57,6 +59,7 @@ where
     type Acceptor = UnixAcceptor;
 
     async fn try_bind(self) -> crate::Result<Self::Acceptor> {
+        let path = self.path.as_ref().to_owned();
         let inner = match (self.permissions, self.owner) {
             (Some(permissions), Some((uid, gid))) => {
                 let inner = TokioUnixListener::bind(self.path.clone())?;
@@ -84,6 +87,7 @@ where
         };
         Ok(UnixAcceptor {
             inner,
+            path,
             holdings: vec![holding],
         })
     }
@@ -92,9 +96,20 @@ where
 /// `UnixAcceptor` is used to accept a Unix socket connection.
 pub struct UnixAcceptor {
     inner: TokioUnixListener,
+    path: PathBuf,
     holdings: Vec<Holding>,
 }
 
+impl Drop for UnixAcceptor {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            if e.kind() != ErrorKind::NotFound {
+                tracing::warn!(error = ?e, path = ?self.path, "failed to remove unix socket file");
+            }
+        }
+    }
+}
+
 #[cfg(unix)]
 impl Acceptor for UnixAcceptor {
     type Conn = UnixStream;
@@ -112,6 +127,7 @@ impl Acceptor for UnixAcceptor {
             remote_addr: remote_addr.into(),
             http_version: Version::HTTP_11,
             http_scheme: Scheme::HTTP,
+            tls_client_cert: Default::default(),
         })
     }
 }
@@ -151,4 +167,39 @@ mod tests {
         assert_eq!(conn.read_i32().await.unwrap(), 518);
         std::fs::remove_file(sock_file).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_unix_listener_serve() {
+        use std::os::unix::fs::PermissionsExt;
+
+        use crate::prelude::*;
+
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let sock_file = "/tmp/test-salvo-serve.sock";
+        let acceptor = UnixListener::new(sock_file)
+            .permissions(Permissions::from_mode(0o600))
+            .bind()
+            .await;
+        assert_eq!(std::fs::metadata(sock_file).unwrap().permissions().mode() & 0o777, 0o600);
+        let server = Server::new(acceptor);
+        let handle = server.handle();
+        let serving = tokio::spawn(server.serve(Router::new().get(hello)));
+
+        let mut stream = tokio::net::UnixStream::connect(sock_file).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf).ends_with("hello"));
+
+        handle.graceful_stop(Duration::from_secs(1)).await;
+        serving.await.unwrap();
+        assert!(!Path::new(sock_file).exists());
+    }
 }
@@ -1,6 +1,7 @@
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::fmt::{self, Formatter};
+use std::future::Future;
 
 /// `Depot` is for store temp data of current request.
 ///
@@ -95,6 +96,69 @@ impl Depot {
         self.get_mut(&type_key::<T>())
     }
 
+    /// Obtain a reference to a value previous inject to the depot, inject the value returned by `f` first
+    /// if it is not present.
+    ///
+    /// **Note: A value of type `T` stored with [`insert`](Depot::insert) is not found by this method.**
+    #[inline]
+    pub fn get_or_insert_with<T, F>(&mut self, f: F) -> &T
+    where
+        T: Any + Send + Sync,
+        F: FnOnce() -> T,
+    {
+        let key = type_key::<T>();
+        if !self.map.get(&key).map(|value| value.is::<T>()).unwrap_or(false) {
+            self.map.insert(key.clone(), Box::new(f()));
+        }
+        self.map
+            .get(&key)
+            .and_then(|value| value.downcast_ref::<T>())
+            .expect("value should be injected")
+    }
+
+    /// Obtain a reference to a value previous inject to the depot, await the future returned by `f` and
+    /// inject its output first if it is not present.
+    ///
+    /// This is useful for deferring expensive initialization, such as establishing a database connection or
+    /// validating a token, until a handler actually needs the result. If `f` fails, nothing is injected and
+    /// the error is returned, so the next call will try again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// struct CurrentUser(String);
+    ///
+    /// #[handler]
+    /// async fn hello(depot: &mut Depot) -> Result<String, StatusError> {
+    ///     let user = depot
+    ///         .get_or_try_insert_with(|| async {
+    ///             // Query the database here.
+    ///             Ok::<_, StatusError>(CurrentUser("salvo".into()))
+    ///         })
+    ///         .await?;
+    ///     Ok(format!("Hello {}", user.0))
+    /// }
+    /// ```
+    pub async fn get_or_try_insert_with<T, E, F, Fut>(&mut self, f: F) -> Result<&T, E>
+    where
+        T: Any + Send + Sync,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<T, E>> + Send,
+    {
+        let key = type_key::<T>();
+        if !self.map.get(&key).map(|value| value.is::<T>()).unwrap_or(false) {
+            let value = f().await?;
+            self.map.insert(key.clone(), Box::new(value));
+        }
+        Ok(self
+            .map
+            .get(&key)
+            .and_then(|value| value.downcast_ref::<T>())
+            .expect("value should be injected"))
+    }
+
     /// Inserts a key-value pair into the depot.
     #[inline]
     pub fn insert<K, V>(&mut self, key: K, value: V) -> &mut Self
@@ -167,6 +231,63 @@ impl Depot {
         self.map.remove(key).is_some()
     }
 
+    /// Returns a view of the depot whose keys are prefixed with `name`.
+    ///
+    /// Middlewares can use their own namespace to store values under simple keys like `"user"` without
+    /// colliding with each other. Values can also be read with the plain API using `"{name}::{key}"` as key.
+    /// Use [`Depot::namespace_ref`] to read values through a shared reference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains `::`, because `"a::b"` namespace and `"a"` namespace with `"b::{key}"` keys
+    /// would be combined to the same key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// let mut depot = Depot::new();
+    /// depot.insert("user", "root");
+    /// depot.namespace("jwt_auth").insert("user", "client");
+    ///
+    /// assert_eq!(depot.get::<&str>("user").unwrap(), &"root");
+    /// assert_eq!(depot.namespace("jwt_auth").get::<&str>("user").unwrap(), &"client");
+    /// assert_eq!(depot.get::<&str>("jwt_auth::user").unwrap(), &"client");
+    /// ```
+    #[inline]
+    pub fn namespace(&mut self, name: impl Into<String>) -> DepotNamespace<'_> {
+        DepotNamespace {
+            depot: self,
+            name: namespace_name(name.into()),
+        }
+    }
+
+    /// Returns a read-only view of the depot whose keys are prefixed with `name`, see [`Depot::namespace`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains `::`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// let mut depot = Depot::new();
+    /// depot.namespace("jwt_auth").insert("user", "client");
+    ///
+    /// let depot = &depot;
+    /// assert_eq!(depot.namespace_ref("jwt_auth").get::<&str>("user").unwrap(), &"client");
+    /// ```
+    #[inline]
+    pub fn namespace_ref(&self, name: impl Into<String>) -> DepotNamespaceRef<'_> {
+        DepotNamespaceRef {
+            depot: self,
+            name: namespace_name(name.into()),
+        }
+    }
+
     /// Remove value from depot and returning the value if the type was previously in the depot.
     #[inline]
     pub fn scrape<T: Any + Send + Sync>(&mut self) -> Result<T, Option<Box<dyn Any + Send + Sync>>> {
@@ -174,6 +295,149 @@ impl Depot {
     }
 }
 
+/// A view of [`Depot`] whose keys are prefixed with a namespace, created by [`Depot::namespace`].
+///
+/// Values stored through a namespace are kept in the same depot, the namespace and the key are combined as
+/// `"{namespace}::{key}"`, so middlewares using different namespaces never overwrite each other.
+pub struct DepotNamespace<'a> {
+    depot: &'a mut Depot,
+    name: String,
+}
+
+#[inline]
+fn namespace_name(name: String) -> String {
+    assert!(!name.contains("::"), "depot namespace `{name}` must not contain `::`");
+    name
+}
+
+#[inline]
+fn namespaced_key(name: &str, key: &str) -> String {
+    format!("{name}::{key}")
+}
+
+fn fmt_namespace(f: &mut Formatter<'_>, type_name: &str, depot: &Depot, name: &str) -> fmt::Result {
+    let prefix = namespaced_key(name, "");
+    f.debug_struct(type_name)
+        .field("name", &name)
+        .field(
+            "keys",
+            &depot
+                .map
+                .keys()
+                .filter_map(|key| key.strip_prefix(&prefix))
+                .collect::<Vec<_>>(),
+        )
+        .finish()
+}
+
+impl DepotNamespace<'_> {
+    #[inline]
+    fn key(&self, key: &str) -> String {
+        namespaced_key(&self.name, key)
+    }
+
+    /// Returns the name of this namespace.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Inserts a key-value pair into this namespace.
+    #[inline]
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+        K: AsRef<str>,
+        V: Any + Send + Sync,
+    {
+        let key = self.key(key.as_ref());
+        self.depot.insert(key, value);
+        self
+    }
+
+    /// Check is there a value stored in this namespace with this key.
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.depot.contains_key(&self.key(key))
+    }
+
+    /// Immutably borrows value from this namespace.
+    ///
+    /// Returns `Err(None)` if value is not present in this namespace.
+    /// Returns `Err(Some(Box<dyn Any + Send + Sync>))` if value is present but downcast failed.
+    #[inline]
+    pub fn get<V: Any + Send + Sync>(&self, key: &str) -> Result<&V, Option<&Box<dyn Any + Send + Sync>>> {
+        self.depot.get(&self.key(key))
+    }
+
+    /// Mutably borrows value from this namespace.
+    ///
+    /// Returns `Err(None)` if value is not present in this namespace.
+    /// Returns `Err(Some(Box<dyn Any + Send + Sync>))` if value is present but downcast failed.
+    #[inline]
+    pub fn get_mut<V: Any + Send + Sync>(
+        &mut self,
+        key: &str,
+    ) -> Result<&mut V, Option<&mut Box<dyn Any + Send + Sync>>> {
+        let key = self.key(key);
+        self.depot.get_mut(&key)
+    }
+
+    /// Remove value from this namespace and returning the value at the key if the key was previously in it.
+    #[inline]
+    pub fn remove<V: Any + Send + Sync>(&mut self, key: &str) -> Result<V, Option<Box<dyn Any + Send + Sync>>> {
+        let key = self.key(key);
+        self.depot.remove(&key)
+    }
+
+    /// Delete the key from this namespace, if the key is not present, return `false`.
+    #[inline]
+    pub fn delete(&mut self, key: &str) -> bool {
+        let key = self.key(key);
+        self.depot.delete(&key)
+    }
+}
+
+impl fmt::Debug for DepotNamespace<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt_namespace(f, "DepotNamespace", self.depot, &self.name)
+    }
+}
+
+/// A read-only view of [`Depot`] whose keys are prefixed with a namespace, created by [`Depot::namespace_ref`].
+pub struct DepotNamespaceRef<'a> {
+    depot: &'a Depot,
+    name: String,
+}
+
+impl<'a> DepotNamespaceRef<'a> {
+    /// Returns the name of this namespace.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check is there a value stored in this namespace with this key.
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.depot.contains_key(&namespaced_key(&self.name, key))
+    }
+
+    /// Immutably borrows value from this namespace.
+    ///
+    /// Returns `Err(None)` if value is not present in this namespace.
+    /// Returns `Err(Some(Box<dyn Any + Send + Sync>))` if value is present but downcast failed.
+    #[inline]
+    pub fn get<V: Any + Send + Sync>(&self, key: &str) -> Result<&'a V, Option<&'a Box<dyn Any + Send + Sync>>> {
+        self.depot.get(&namespaced_key(&self.name, key))
+    }
+}
+
+impl fmt::Debug for DepotNamespaceRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt_namespace(f, "DepotNamespaceRef", self.depot, &self.name)
+    }
+}
+
 impl fmt::Debug for Depot {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Depot").field("keys", &self.map.keys()).finish()
@@ -199,6 +463,66 @@ mod test {
         assert_eq!(depot.get_mut::<String>("one").unwrap(), &mut "ONE".to_owned());
     }
 
+    #[test]
+    fn test_depot_get_or_insert_with() {
+        let mut depot = Depot::new();
+        assert_eq!(depot.get_or_insert_with(|| 1u8), &1);
+        assert_eq!(depot.get_or_insert_with(|| 2u8), &1);
+        assert_eq!(depot.obtain::<u8>().unwrap(), &1);
+    }
+
+    #[tokio::test]
+    async fn test_depot_get_or_try_insert_with() {
+        let mut depot = Depot::new();
+        let result = depot.get_or_try_insert_with(|| async { Err::<u32, _>("failed") }).await;
+        assert_eq!(result, Err("failed"));
+        assert!(!depot.contains::<u32>());
+
+        let mut calls = 0;
+        for _ in 0..2 {
+            let value = depot
+                .get_or_try_insert_with(|| {
+                    calls += 1;
+                    async { Ok::<_, ()>(10u32) }
+                })
+                .await;
+            assert_eq!(value, Ok(&10));
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_depot_namespace() {
+        let mut depot = Depot::new();
+        depot.insert("claims", 1u8);
+        depot.namespace("jwt_auth").insert("claims", 2u8);
+        depot.namespace("session").insert("claims", 3u8);
+
+        assert_eq!(depot.get::<u8>("claims").unwrap(), &1);
+        assert_eq!(depot.namespace("jwt_auth").get::<u8>("claims").unwrap(), &2);
+        assert_eq!(depot.namespace("session").get::<u8>("claims").unwrap(), &3);
+
+        let mut jwt = depot.namespace("jwt_auth");
+        *jwt.get_mut::<u8>("claims").unwrap() = 4;
+        assert!(jwt.contains_key("claims"));
+        assert_eq!(jwt.remove::<u8>("claims").unwrap(), 4);
+        assert!(!jwt.delete("claims"));
+        assert!(depot.namespace("session").contains_key("claims"));
+
+        let depot = &depot;
+        let session = depot.namespace_ref("session");
+        assert_eq!(session.name(), "session");
+        assert!(session.contains_key("claims"));
+        assert_eq!(session.get::<u8>("claims").unwrap(), &3);
+        assert!(depot.namespace_ref("jwt_auth").get::<u8>("claims").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "must not contain `::`")]
+    fn test_depot_namespace_reject_separator() {
+        Depot::new().namespace("jwt::auth");
+    }
+
     #[tokio::test]
     async fn test_middleware_use_depot() {
         #[handler]
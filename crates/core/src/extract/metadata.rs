@@ -155,6 +155,8 @@ pub struct Field {
     pub serde_rename: Option<&'static str>,
     /// Field metadata, this is used for nested extractible types.
     pub metadata: Option<&'static Metadata>,
+    /// Field default value, it is used when the field is missing in request.
+    pub default: Option<fn() -> Result<serde_json::Value, serde_json::Error>>,
 }
 impl Field {
     /// Create a new field with the given name and kind.
@@ -172,6 +174,7 @@ impl Field {
             rename: None,
             serde_rename: None,
             metadata: None,
+            default: None,
         }
     }
 
@@ -217,6 +220,12 @@ impl Field {
         self
     }
 
+    /// Sets the function which returns default value when the field is missing in request.
+    pub fn default(mut self, default: fn() -> Result<serde_json::Value, serde_json::Error>) -> Self {
+        self.default = Some(default);
+        self
+    }
+
     /// Check is this field has body required.
     pub(crate) fn has_body_required(&self) -> bool {
         self.sources.iter().any(|s| s.from == SourceFrom::Body)
@@ -7,8 +7,8 @@
 //! # use salvo_core::prelude::*;
 //! # use serde::{Deserialize, Serialize};
 //! #[derive(Serialize, Deserialize, Extractible, Debug)]
-//! // Get the data field value from the body by default.
-//! #[salvo(extract(default_source(from = "body")))]
+//! // Get the data field value from the body by default, all fields are required.
+//! #[salvo(extract(default_source(from = "body"), compile_check = "required"))]
 //! struct GoodMan<'a> {
 //!     /// The id number is obtained from the request path parameter, and the data is automatically parsed as i64 type.
 //!     #[salvo(extract(source(from = "param")))]
@@ -31,7 +31,7 @@
 //! # use salvo_core::prelude::*;
 //! # use serde::{Deserialize, Serialize};
 //! #[derive(Serialize, Deserialize, Extractible, Debug)]
-//! #[salvo(extract(default_source(from = "body")))]
+//! #[salvo(extract(default_source(from = "body"), compile_check = "required"))]
 //! struct GoodMan<'a> {
 //!     #[salvo(extract(source(from = "param")))]
 //!     id: i64,
@@ -46,7 +46,7 @@
 //! }
 //!
 //! #[derive(Serialize, Deserialize, Extractible, Debug)]
-//! #[salvo(extract(default_source(from = "body")))]
+//! #[salvo(extract(default_source(from = "body"), compile_check = "required"))]
 //! struct Nested<'a> {
 //!     #[salvo(extract(source(from = "param")))]
 //!     id: i64,
@@ -61,18 +61,178 @@
 //! ```
 //!
 //! View [full source code](https://github.com/salvo-rs/salvo/blob/main/examples/extract-nested/src/main.rs)
+//!
+//! `#[salvo(extract(flatten))]` does the same without `#[serde(flatten)]`, errors of the nested type are
+//! prefixed with the field name, like ``pagination: missing field `page` ``.
+//!
+//! A flatten field with sources collects all key-value pairs of these sources into a map, keys used by
+//! other fields are skipped:
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use salvo_core::prelude::*;
+//! # use serde::Deserialize;
+//! #[derive(Deserialize, Extractible)]
+//! #[salvo(extract(default_source(from = "query")))]
+//! struct Search {
+//!     #[salvo(extract(compile_check = "required"))]
+//!     keyword: String,
+//!     #[salvo(extract(source(from = "query"), flatten))]
+//!     filters: HashMap<String, String>,
+//! }
+//! ```
+//!
+//! Values are deserialized by the type's `Deserialize` implementation, so custom parsing is done with serde's
+//! `#[serde(deserialize_with = "path")]`, for example to parse `?ids=1,2,3` into `Vec<i64>`. The same function
+//! can be set with `#[salvo(extract(deserialize_with = "path"))]` when the type only derives `Extractible`,
+//! then it is used when the type is extracted as handler argument or by [`Extractible::extract`].
+//!
+//! Use `alias` to accept other names for a field, it can be set multiple times or as a list like
+//! `#[salvo(extract(alias = ["userId", "uid"]))]`. Aliases are tried in order before the field name, the first present one wins.
+//!
+//! Struct-level `#[salvo(extract(debug))]` makes `Extractible` implement `Debug` for the type, fields marked with
+//! `#[salvo(extract(secret))]` are printed as `"***"` in it, so passwords and tokens will not appear in logs.
+//! `secret` fields require `debug`, and `Debug` must be removed from `#[derive(...)]`, for example
+//! `#[derive(Debug, Deserialize, Extractible)]` becomes `#[derive(Deserialize, Extractible)]`, otherwise the two
+//! implementations conflict. In debug builds, a warning is logged through `tracing` the first time such value is
+//! formatted:
+//!
+//! ```
+//! # use salvo_core::prelude::*;
+//! # use serde::Deserialize;
+//! #[derive(Deserialize, Extractible)]
+//! #[salvo(extract(default_source(from = "body"), compile_check = "required", debug))]
+//! struct Login {
+//!     username: String,
+//!     #[salvo(extract(secret))]
+//!     password: String,
+//! }
+//! ```
+//!
+//! If the only default source is `default_source(from = "body", parse = "json")` and no field sets its own
+//! source, alias, or rename, the generated `extract` passes the body straight to the type's `Deserialize`
+//! implementation through [`Request::parse_json`], so every `#[serde(...)]` attribute
+//! (like `deny_unknown_fields`) is honored.
+//!
+//! With `serde-qs` feature enabled, `default_source(from = "query", parse = "qs")` deserializes the whole query
+//! string with [`serde_qs`](https://docs.rs/serde_qs), so nested objects (`filter[name]=foo&filter[age]=30`)
+//! and arrays (`ids[]=1&ids[]=2`) are supported.
+//!
+//! Missing fields must be handled on purpose: a field which is not `Option`, and has no `default`,
+//! `#[serde(default)]` or `flatten`, is a compile error unless it is marked with `compile_check = "required"`.
+//! It makes the extraction fail when the field is missing in request. Setting `compile_check = "required"`
+//! on the struct marks all its fields, which helps to migrate existing types:
+//!
+//! ```
+//! # use salvo_core::prelude::*;
+//! # use serde::Deserialize;
+//! #[derive(Deserialize, Extractible)]
+//! #[salvo(extract(default_source(from = "query")))]
+//! struct Search {
+//!     #[salvo(extract(compile_check = "required"))]
+//!     keyword: String,
+//!     page: Option<u32>,
+//! }
+//! ```
+//!
+//! Use `default` to fill the field with `Default::default()` when it is missing in request, or
+//! `default = "path::to::function"` to call the given function. The default value is converted through
+//! `serde_json::Value`, so the field type must implement `Serialize`, which is checked at compile time, and a
+//! default value failed to convert is reported as extraction error. `Option` fields are `None` when missing,
+//! so they should not set `default`:
+//!
+//! ```
+//! # use salvo_core::prelude::*;
+//! # use serde::Deserialize;
+//! fn default_size() -> u32 {
+//!     20
+//! }
+//!
+//! #[derive(Deserialize, Extractible)]
+//! #[salvo(extract(default_source(from = "query")))]
+//! struct Pagination {
+//!     #[salvo(extract(default))]
+//!     page: u32,
+//!     #[salvo(extract(default = "default_size"))]
+//!     size: u32,
+//! }
+//! ```
+//!
+//! Tuple structs are supported too, every field should set its name with `rename`. Missing `Option` fields
+//! are set to `None`:
+//!
+//! ```
+//! # use salvo_core::prelude::*;
+//! # use serde::Deserialize;
+//! #[derive(Deserialize, Extractible)]
+//! struct UserId(#[salvo(extract(source(from = "param"), rename = "id", compile_check = "required"))] u64);
+//!
+//! #[derive(Deserialize, Extractible)]
+//! #[salvo(extract(default_source(from = "query")))]
+//! struct Page(
+//!     #[salvo(extract(rename = "page", compile_check = "required"))] u32,
+//!     #[salvo(extract(rename = "size"))] Option<u32>,
+//! );
+//! ```
+//!
+//! By default, extraction failure is rendered as `400 Bad Request`. Use `on_error` to map the
+//! [`ParseError`](crate::http::ParseError) to a custom [`StatusError`](crate::http::StatusError),
+//! the function should have signature `fn(ParseError) -> StatusError`:
+//!
+//! ```
+//! # use salvo_core::prelude::*;
+//! # use salvo_core::http::ParseError;
+//! # use serde::Deserialize;
+//! fn unprocessable(e: ParseError) -> StatusError {
+//!     StatusError::unprocessable_entity().brief("Invalid user data.").cause(e)
+//! }
+//!
+//! #[derive(Deserialize, Extractible)]
+//! #[salvo(extract(default_source(from = "body"), on_error = "unprocessable"))]
+//! struct User {
+//!     #[salvo(extract(compile_check = "required"))]
+//!     name: String,
+//! }
+//! ```
+//!
+//! Fields with `source(from = "state")` are obtained from [`Depot`] by their type with `depot.obtain::<T>()`
+//! and cloned, so both `Arc<T>` and `T: Clone` work, missing `Option` fields are set to `None`. These fields
+//! need not to be `Deserialize`, the struct is only derived from `Extractible`, and it can only be extracted
+//! as handler argument. The depot is not available to [`Extractible::extract`], so calling it on such type
+//! compiles but always returns an error at runtime, and [`Request::extract`] does not compile because the
+//! struct is not `Deserialize`:
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use salvo_core::prelude::*;
+//! struct Pool;
+//!
+//! #[derive(Extractible)]
+//! #[salvo(extract(default_source(from = "body")))]
+//! struct CreateUser {
+//!     #[salvo(extract(compile_check = "required"))]
+//!     name: String,
+//!     #[salvo(extract(source(from = "state")))]
+//!     db: Arc<Pool>,
+//!     #[salvo(extract(source(from = "header"), compile_check = "required"))]
+//!     #[serde(rename = "x-request-id")]
+//!     request_id: String,
+//! }
+//! ```
 
 /// Metadata types.
 pub mod metadata;
 pub use metadata::Metadata;
 mod case;
 pub use case::RenameRule;
+pub mod multipart;
+pub use multipart::FromMultipart;
 
 use std::fmt::Debug;
 use std::future::Future;
 
 use crate::http::Request;
-use crate::Writer;
+use crate::{Depot, Writer};
 
 /// If a type implements this trait, it will give a metadata, this will help request to extracts data to this type.
 pub trait Extractible<'ex> {
@@ -82,6 +242,9 @@ pub trait Extractible<'ex> {
     /// Extract data from request.
     ///
     /// **NOTE:** Set status code to 400 if extract failed and status code is not error.
+    ///
+    /// Types derived with fields of `source(from = "state")` always return an error here, they can only be
+    /// extracted as handler argument.
     fn extract(
         req: &'ex mut Request,
     ) -> impl Future<Output = Result<Self, impl Writer + Send + Debug + 'static>> + Send
@@ -98,4 +261,16 @@ pub trait Extractible<'ex> {
     {
         Self::extract(req)
     }
+
+    /// Extract data from request and depot with a argument. This function used in macros internal.
+    fn extract_with_depot<'d>(
+        req: &'ex mut Request,
+        _depot: &'d Depot,
+        arg: &str,
+    ) -> impl Future<Output = Result<Self, impl Writer + Send + Debug + 'static>> + Send
+    where
+        Self: Sized,
+    {
+        Self::extract_with_arg(req, arg)
+    }
 }
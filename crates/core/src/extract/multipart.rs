@@ -0,0 +1,188 @@
+//! Multipart extraction.
+//!
+//! Derive [`FromMultipart`] to map fields of `multipart/form-data` request to struct fields:
+//!
+//! ```
+//! use salvo_core::extract::multipart::{FromMultipart, UploadedFile};
+//! use salvo_core::prelude::*;
+//!
+//! #[derive(FromMultipart, Debug)]
+//! struct Upload {
+//!     title: String,
+//!     description: Option<String>,
+//!     #[salvo(multipart(rename = "file"))]
+//!     avatar: UploadedFile,
+//! }
+//!
+//! #[handler]
+//! async fn upload(req: &mut Request) -> Result<String, StatusError> {
+//!     let data: Upload = req.parse_multipart().await.map_err(|_| StatusError::bad_request())?;
+//!     Ok(format!("{} uploaded", data.title))
+//! }
+//! ```
+use std::future::Future;
+
+use bytes::Bytes;
+use mime::Mime;
+
+use crate::http::form::{FilePart, FormData};
+use crate::http::header::CONTENT_TYPE;
+use crate::http::ParseError;
+
+/// Construct a type from [`FormData`], it is usually implemented by `#[derive(FromMultipart)]`.
+pub trait FromMultipart: Sized {
+    /// Create `Self` from form data.
+    fn from_multipart(form_data: &FormData) -> impl Future<Output = Result<Self, ParseError>> + Send;
+}
+
+/// A type which can be extracted from a named part of [`FormData`].
+///
+/// Returns `Ok(None)` if the part is missing.
+pub trait MultipartField: Sized {
+    /// Extract value from the part named `name`.
+    fn from_form_data(
+        form_data: &FormData,
+        name: &str,
+    ) -> impl Future<Output = Result<Option<Self>, ParseError>> + Send;
+}
+
+/// A file uploaded in a `multipart/form-data` request, the content is loaded into memory.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct UploadedFile {
+    /// The file name provided by client.
+    pub filename: Option<String>,
+    /// The content type of this file.
+    pub content_type: Option<Mime>,
+    /// The file content.
+    pub bytes: Bytes,
+}
+impl UploadedFile {
+    /// Read `FilePart` content into memory and create a new `UploadedFile`.
+    pub async fn from_file_part(part: &FilePart) -> Result<Self, ParseError> {
+        let bytes = tokio::fs::read(part.path()).await?;
+        Ok(Self {
+            filename: part.name().map(ToOwned::to_owned),
+            content_type: part
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok()),
+            bytes: bytes.into(),
+        })
+    }
+}
+
+impl MultipartField for String {
+    async fn from_form_data(form_data: &FormData, name: &str) -> Result<Option<Self>, ParseError> {
+        Ok(form_data.fields.get(name).cloned())
+    }
+}
+impl MultipartField for FilePart {
+    async fn from_form_data(form_data: &FormData, name: &str) -> Result<Option<Self>, ParseError> {
+        Ok(form_data.files.get(name).cloned())
+    }
+}
+impl MultipartField for UploadedFile {
+    async fn from_form_data(form_data: &FormData, name: &str) -> Result<Option<Self>, ParseError> {
+        match form_data.files.get(name) {
+            Some(part) => Ok(Some(UploadedFile::from_file_part(part).await?)),
+            None => Ok(None),
+        }
+    }
+}
+impl<T> MultipartField for Option<T>
+where
+    T: MultipartField + Send,
+{
+    async fn from_form_data(form_data: &FormData, name: &str) -> Result<Option<Self>, ParseError> {
+        Ok(Some(T::from_form_data(form_data, name).await?))
+    }
+}
+
+/// Extract a required part, returns error if the part is missing. This function used in macros internal.
+#[doc(hidden)]
+pub async fn required<T: MultipartField>(form_data: &FormData, name: &str) -> Result<T, ParseError> {
+    T::from_form_data(form_data, name)
+        .await?
+        .ok_or_else(|| ParseError::other(format!("multipart field `{name}` is missing")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(FromMultipart, Debug)]
+    struct Upload {
+        title: String,
+        note: Option<String>,
+        #[salvo(multipart(rename = "file"))]
+        attachment: UploadedFile,
+    }
+
+    #[handler]
+    async fn upload(req: &mut Request) -> String {
+        match req.parse_multipart::<Upload>().await {
+            Ok(data) => format!(
+                "{}|{:?}|{:?}|{:?}|{}",
+                data.title,
+                data.note,
+                data.attachment.filename,
+                data.attachment.content_type.map(|m| m.to_string()),
+                String::from_utf8_lossy(&data.attachment.bytes)
+            ),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn multipart_body(parts: &[(&str, Option<&str>, &str)]) -> String {
+        let mut body = String::new();
+        for (name, filename, value) in parts {
+            body.push_str("--boundary\r\n");
+            match filename {
+                Some(filename) => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{name}\"; filename=\"{filename}\"\r\nContent-Type: text/plain\r\n\r\n"
+                )),
+                None => body.push_str(&format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n")),
+            }
+            body.push_str(value);
+            body.push_str("\r\n");
+        }
+        body.push_str("--boundary--\r\n");
+        body
+    }
+
+    #[tokio::test]
+    async fn test_from_multipart() {
+        let router = Router::with_path("upload").post(upload);
+        let service = Service::new(router);
+
+        let content = TestClient::post("http://127.0.0.1:5801/upload")
+            .add_header("content-type", "multipart/form-data; boundary=boundary", true)
+            .body(multipart_body(&[
+                ("title", None, "hello"),
+                ("file", Some("a.txt"), "content"),
+            ]))
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, r#"hello|None|Some("a.txt")|Some("text/plain")|content"#);
+
+        let content = TestClient::post("http://127.0.0.1:5801/upload")
+            .add_header("content-type", "multipart/form-data; boundary=boundary", true)
+            .body(multipart_body(&[
+                ("note", None, "note"),
+                ("file", Some("a.txt"), "content"),
+            ]))
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(content.contains("multipart field `title` is missing"));
+    }
+}
@@ -38,6 +38,23 @@
 //! - No need to manually add `#[async_trait]`.
 //! - The parameters that are not needed in the function have been omitted, and the required parameters can be arranged in any order.
 //! - For objects that implement `Writer` or `Scribe` abstraction, it can be directly used as the return value of the function. Here `&'static str` implements `Scribe`, so it can be returned directly as the return value of the function.
+//! - If the return type is `Vec<T>`, it will be rendered as json array, so `T` must implement `serde::Serialize`.
+//! - Opaque types like `impl Scribe` or `impl Writer` can also be used as the return type.
+//!
+//! The struct generated for a function implements `Clone` and `Copy`, so it can be used in multiple routers.
+//!
+//! Generic parameters of the function are moved to the generated struct, create it with `new()`:
+//!
+//! ```
+//! use salvo_core::prelude::*;
+//!
+//! #[handler]
+//! async fn type_name<T>() -> &'static str {
+//!     std::any::type_name::<T>()
+//! }
+//!
+//! let router = Router::new().get(type_name::<String>::new());
+//! ```
 //!
 //! `#[handler]` can not only be added to the function, but also can be added to the `impl` of `struct` to let `struct` implement `Handler`. At this time, the `handle` function in the `impl` code block will be Identified as the specific implementation of `handle` in `Handler`:
 //!
@@ -54,6 +71,27 @@
 //! }
 //! ````
 //!
+//! Synchronous function, such as a CPU-bound one, is called in `tokio::task::spawn_blocking` if all of its
+//! arguments are extracted types, so it does not block the async runtime. The arguments are moved into the
+//! blocking task, so they and the return type must be `Send + 'static`. Each call is handed off to tokio's
+//! blocking thread pool, cheap synchronous functions should be `async fn` to avoid this cost. If the task panics,
+//! `500 Internal Server Error` is responded. Synchronous function takes references like `&mut Response` is
+//! called inline. `#[handler(blocking)]` makes sure the function is run in `spawn_blocking`, it is an error if
+//! the function is `async` or takes references:
+//!
+//! ```
+//! use salvo_core::prelude::*;
+//!
+//! #[handler]
+//! fn fibonacci() -> String {
+//!     let (mut a, mut b) = (0u64, 1u64);
+//!     for _ in 0..50 {
+//!         (a, b) = (b, a + b);
+//!     }
+//!     a.to_string()
+//! }
+//! ```
+//!
 //! ## Handle errors
 //!
 //! `Handler` in Salvo can return `Result`, only the types of `Ok` and `Err` in `Result` are implemented `Writer` trait.
@@ -295,3 +333,254 @@ macro_rules! __for_each_tuple {
 
 __for_each_tuple!(handler_tuple_impls);
 __for_each_tuple!(skipper_tuple_impls);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    // Omitted arguments must not produce warnings in generated code.
+    #[deny(unused_variables, unused_mut)]
+    mod omitted_args {
+        use crate::prelude::*;
+
+        #[handler]
+        pub(super) async fn hello() -> &'static str {
+            "hi"
+        }
+        #[handler]
+        pub(super) async fn hello_res(res: &mut Response) {
+            res.render("hi");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_omitted_args() {
+        let router = Router::new()
+            .push(Router::with_path("hello").get(omitted_args::hello))
+            .push(Router::with_path("hello_res").get(omitted_args::hello_res));
+        let service = Service::new(router);
+        for path in ["hello", "hello_res"] {
+            let content = TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, "hi");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_state() {
+        use std::sync::Arc;
+
+        struct Pool(&'static str);
+
+        #[derive(Extractible)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Input {
+            #[salvo(extract(compile_check = "required"))]
+            name: String,
+            #[salvo(extract(source(from = "state")))]
+            pool: Arc<Pool>,
+            #[salvo(extract(source(from = "state")))]
+            count: u32,
+            #[salvo(extract(source(from = "state")))]
+            missing: Option<String>,
+            #[salvo(extract(source(from = "header"), compile_check = "required"))]
+            #[serde(rename = "x-request-id")]
+            request_id: String,
+        }
+        #[handler]
+        async fn inject(depot: &mut Depot) {
+            depot.inject(Arc::new(Pool("db"))).inject(7u32);
+        }
+        #[handler]
+        async fn hello(input: Input) -> String {
+            format!(
+                "{} {} {} {:?} {}",
+                input.name, input.pool.0, input.count, input.missing, input.request_id
+            )
+        }
+        let router = Router::new()
+            .push(Router::with_path("inject").hoop(inject).get(hello))
+            .push(Router::with_path("plain").get(hello));
+        let service = Service::new(router);
+
+        let content = TestClient::get("http://127.0.0.1:5801/inject?name=salvo")
+            .add_header("x-request-id", "abc", true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "salvo db 7 None abc");
+
+        let res = TestClient::get("http://127.0.0.1:5801/plain?name=salvo")
+            .add_header("x-request-id", "abc", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_handler() {
+        #[derive(serde::Deserialize, Extractible)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Input {
+            #[salvo(extract(compile_check = "required"))]
+            name: String,
+        }
+        #[handler]
+        fn hello(input: Input) -> Result<String, StatusError> {
+            match input.name.as_str() {
+                "panic" => panic!("blocking panic"),
+                "" => Err(StatusError::bad_request()),
+                name => Ok(format!("hello {name}")),
+            }
+        }
+
+        let service = Service::new(Router::new().get(hello));
+        let mut res = TestClient::get("http://127.0.0.1:5801/?name=jobs").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), "hello jobs");
+        let res = TestClient::get("http://127.0.0.1:5801/?name=").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+        let res = TestClient::get("http://127.0.0.1:5801/?name=panic")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[tokio::test]
+    async fn test_sync_handler_with_references() {
+        #[handler]
+        fn hello(req: &mut Request, res: &mut Response) {
+            res.render(format!("hello {}", req.uri().path()));
+        }
+
+        let service = Service::new(Router::with_path("<name>").get(hello));
+        let content = TestClient::get("http://127.0.0.1:5801/jobs")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "hello /jobs");
+    }
+
+    #[tokio::test]
+    async fn test_handler_with_name() {
+        #[handler(name = "GreetingHandler")]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let service = Service::new(Router::new().get(GreetingHandler));
+        let content = TestClient::get("http://127.0.0.1:5801/")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_handler_copy() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let handler = hello;
+        let router = Router::new()
+            .push(Router::with_path("a").get(handler))
+            .push(Router::with_path("b").get(handler));
+        let service = Service::new(router);
+        for path in ["a", "b"] {
+            let content = TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, "hello");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generic_handler() {
+        trait Greeting {
+            fn greeting() -> &'static str;
+        }
+        struct English;
+        impl Greeting for English {
+            fn greeting() -> &'static str {
+                "hello"
+            }
+        }
+        struct Chinese;
+        impl Greeting for Chinese {
+            fn greeting() -> &'static str {
+                "ni hao"
+            }
+        }
+
+        #[handler]
+        async fn greet<T>() -> &'static str
+        where
+            T: Greeting,
+        {
+            T::greeting()
+        }
+
+        #[derive(serde::Deserialize, Extractible)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Input<'a> {
+            #[salvo(extract(compile_check = "required"))]
+            name: &'a str,
+        }
+        #[handler]
+        async fn greet_name<'a, T: Greeting>(input: Input<'a>) -> String {
+            format!("{} {}", T::greeting(), input.name)
+        }
+
+        let router = Router::new()
+            .push(Router::with_path("en").get(greet::<English>::new()))
+            .push(Router::with_path("zh").get(greet::<Chinese>::new()))
+            .push(Router::with_path("name").get(greet_name::<English>::new()));
+        let service = Service::new(router);
+        for (path, expected) in [("en", "hello"), ("zh", "ni hao"), ("name?name=jobs", "hello jobs")] {
+            let content = TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_with_lifetime() {
+        #[derive(serde::Deserialize, Extractible)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Input<'a> {
+            #[salvo(extract(compile_check = "required"))]
+            name: &'a str,
+        }
+        #[handler]
+        async fn hello<'a>(input: Input<'a>, res: &mut Response) {
+            res.render(format!("hello {}", input.name));
+        }
+
+        let content = TestClient::get("http://127.0.0.1:5801/?name=jobs")
+            .send(Router::new().get(hello))
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "hello jobs");
+    }
+}
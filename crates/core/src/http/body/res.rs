@@ -9,11 +9,11 @@ use std::pin::Pin;
 use std::task::{self, ready, Context, Poll};
 
 use futures_channel::{mpsc, oneshot};
-use futures_util::stream::{BoxStream, FusedStream, Stream, TryStreamExt};
+use futures_util::stream::{self, BoxStream, FusedStream, Stream, StreamExt, TryStreamExt};
 use hyper::body::{Body, Frame, Incoming, SizeHint};
 use sync_wrapper::SyncWrapper;
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 
 use crate::error::BoxedError;
 use crate::http::body::{BodyReceiver, BodySender, BytesFrame};
@@ -98,7 +98,15 @@ impl ResBody {
     ///
     /// Useful when wanting to stream chunks from another thread.
     pub fn channel() -> (BodySender, Self) {
-        let (data_tx, data_rx) = mpsc::channel(0);
+        Self::channel_with_capacity(0)
+    }
+
+    /// Create a `Body` stream with an associated sender half, the channel buffers at most `capacity` chunks
+    /// besides the one held by sender.
+    ///
+    /// [`BodySender::send_data`] waits when the buffer is full, so a slow client slows down the producer.
+    pub fn channel_with_capacity(capacity: usize) -> (BodySender, Self) {
+        let (data_tx, data_rx) = mpsc::channel(capacity);
         let (trailers_tx, trailers_rx) = oneshot::channel();
 
         let tx = BodySender {
@@ -130,6 +138,64 @@ impl ResBody {
     pub fn take(&mut self) -> Self {
         std::mem::replace(self, Self::None)
     }
+
+    /// Consume body and convert it into a boxed stream of data bytes.
+    ///
+    /// Trailers frames are ignored, this is useful when body need to be forwarded to another http client.
+    pub fn into_boxed_stream(self) -> BoxStream<'static, Result<Bytes, BoxedError>> {
+        match self {
+            Self::None | Self::Error(_) => stream::empty().boxed(),
+            Self::Once(bytes) => stream::once(async move { Ok(bytes) }).boxed(),
+            Self::Chunks(chunks) => stream::iter(chunks.into_iter().map(Ok)).boxed(),
+            Self::Stream(stream) => stream
+                .into_inner()
+                .try_filter_map(|frame| async move { Ok(frame.into_data().ok()) })
+                .boxed(),
+            body => body
+                .map_err(BoxedError::from)
+                .try_filter_map(|frame| async move { Ok(frame.into_data().ok()) })
+                .boxed(),
+        }
+    }
+}
+
+impl ResBody {
+    /// Consume body and collect all data into `Bytes`, trailers are ignored. Returns the [`StatusError`] if
+    /// the body is [`ResBody::Error`].
+    ///
+    /// **NOTE:** The whole body is loaded into memory, use [`ResBody::into_bytes_with_max_size`]
+    /// if the body size is unknown.
+    pub async fn into_bytes(self) -> Result<Bytes, BoxedError> {
+        self.into_bytes_with_max_size(usize::MAX).await
+    }
+
+    /// Consume body and collect all data into `Bytes`, returns error if body size exceeds `max_size`.
+    pub async fn into_bytes_with_max_size(self, max_size: usize) -> Result<Bytes, BoxedError> {
+        let too_large = || IoError::new(ErrorKind::Other, "response body is too large");
+        match self {
+            Self::None => Ok(Bytes::new()),
+            Self::Error(e) => Err(e.into()),
+            Self::Once(bytes) => {
+                if bytes.len() > max_size {
+                    Err(too_large().into())
+                } else {
+                    Ok(bytes)
+                }
+            }
+            body => {
+                let mut stream = body.into_boxed_stream();
+                let mut data = BytesMut::new();
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk?;
+                    if data.len() + chunk.len() > max_size {
+                        return Err(too_large().into());
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+                Ok(data.freeze())
+            }
+        }
+    }
 }
 
 impl Body for ResBody {
@@ -0,0 +1,172 @@
+//! Content Security Policy.
+use std::fmt::{self, Display, Formatter};
+
+/// Directives deprecated by CSP Level 3.
+const DEPRECATED_DIRECTIVES: [&str; 5] = [
+    "report-uri",
+    "block-all-mixed-content",
+    "plugin-types",
+    "prefetch-src",
+    "referrer",
+];
+
+/// Typed builder for `Content-Security-Policy` header value.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::http::CspDirectives;
+///
+/// let policy = CspDirectives::new()
+///     .default_src(&["'self'"])
+///     .script_src(&["'self'", "cdn.example.com"])
+///     .report_uri("/csp-report");
+/// assert_eq!(
+///     policy.build(),
+///     "default-src 'self'; script-src 'self' cdn.example.com; report-uri /csp-report"
+/// );
+/// ```
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct CspDirectives {
+    directives: Vec<(String, Vec<String>)>,
+}
+
+macro_rules! source_directives {
+    ($($(#[$meta:meta])* $fn_name:ident => $name:literal,)+) => {
+        $(
+            $(#[$meta])*
+            #[inline]
+            pub fn $fn_name(self, sources: &[&str]) -> Self {
+                self.directive($name, sources)
+            }
+        )+
+    };
+}
+
+impl CspDirectives {
+    /// Create a new empty `CspDirectives`.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets a directive with the given values, an existing directive with the same name will be replaced.
+    ///
+    /// A warning will be logged if the directive is deprecated.
+    pub fn directive(mut self, name: &str, values: &[&str]) -> Self {
+        let name = name.trim().to_ascii_lowercase();
+        if DEPRECATED_DIRECTIVES.contains(&name.as_str()) {
+            tracing::warn!(directive = name, "content security policy directive is deprecated");
+        }
+        let values = values.iter().map(|v| v.to_string()).collect();
+        if let Some(item) = self.directives.iter_mut().find(|(n, _)| *n == name) {
+            item.1 = values;
+        } else {
+            self.directives.push((name, values));
+        }
+        self
+    }
+
+    source_directives! {
+        /// Sets `default-src` directive.
+        default_src => "default-src",
+        /// Sets `script-src` directive.
+        script_src => "script-src",
+        /// Sets `style-src` directive.
+        style_src => "style-src",
+        /// Sets `img-src` directive.
+        img_src => "img-src",
+        /// Sets `connect-src` directive.
+        connect_src => "connect-src",
+        /// Sets `font-src` directive.
+        font_src => "font-src",
+        /// Sets `object-src` directive.
+        object_src => "object-src",
+        /// Sets `media-src` directive.
+        media_src => "media-src",
+        /// Sets `frame-src` directive.
+        frame_src => "frame-src",
+        /// Sets `child-src` directive.
+        child_src => "child-src",
+        /// Sets `worker-src` directive.
+        worker_src => "worker-src",
+        /// Sets `manifest-src` directive.
+        manifest_src => "manifest-src",
+        /// Sets `frame-ancestors` directive.
+        frame_ancestors => "frame-ancestors",
+        /// Sets `base-uri` directive.
+        base_uri => "base-uri",
+        /// Sets `form-action` directive.
+        form_action => "form-action",
+        /// Sets `sandbox` directive.
+        sandbox => "sandbox",
+    }
+
+    /// Sets `upgrade-insecure-requests` directive.
+    #[inline]
+    pub fn upgrade_insecure_requests(self) -> Self {
+        self.directive("upgrade-insecure-requests", &[])
+    }
+
+    /// Sets `report-uri` directive.
+    ///
+    /// **NOTE:** This directive is deprecated, use [`CspDirectives::report_to`] instead.
+    #[inline]
+    pub fn report_uri(self, uri: &str) -> Self {
+        self.directive("report-uri", &[uri])
+    }
+
+    /// Sets `report-to` directive.
+    #[inline]
+    pub fn report_to(self, group: &str) -> Self {
+        self.directive("report-to", &[group])
+    }
+
+    /// Check is there any directive defined.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.directives.is_empty()
+    }
+
+    /// Build the header value.
+    pub fn build(&self) -> String {
+        self.directives
+            .iter()
+            .map(|(name, values)| {
+                if values.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{} {}", name, values.join(" "))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+impl Display for CspDirectives {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_csp() {
+        let policy = CspDirectives::new()
+            .default_src(&["'none'"])
+            .img_src(&["'self'", "data:"])
+            .upgrade_insecure_requests()
+            .default_src(&["'self'"]);
+        assert_eq!(
+            policy.build(),
+            "default-src 'self'; img-src 'self' data:; upgrade-insecure-requests"
+        );
+        assert!(CspDirectives::new().is_empty());
+        assert_eq!(CspDirectives::new().build(), "");
+    }
+}
@@ -71,6 +71,11 @@ pub enum ParseError {
     #[error("Serde json error: {0}")]
     SerdeJson(#[from] serde_json::error::Error),
 
+    /// Serde qs error.
+    #[cfg(feature = "serde-qs")]
+    #[error("Serde qs error: {0}")]
+    SerdeQs(#[from] serde_qs::Error),
+
     /// Custom error that does not fall under any other error kind.
     #[error("Other error: {0}")]
     Other(BoxedError),
@@ -1,7 +1,9 @@
 //! The http related types and functions.
 
+mod csp;
 pub mod errors;
 pub mod form;
+mod problem;
 mod range;
 pub mod request;
 pub mod response;
@@ -9,16 +11,22 @@ cfg_feature! {
     #![feature = "cookie"]
     pub use cookie;
 }
+pub use csp::CspDirectives;
 pub use errors::{ParseError, StatusError};
 pub use headers;
 pub use http::method::Method;
 pub use http::{header, method, uri, HeaderMap, HeaderValue, StatusCode};
 pub use mime::{self, Mime};
+pub use problem::ProblemDetail;
 pub use range::HttpRange;
 pub use request::Request;
 pub mod body;
 pub use body::{Body, ReqBody, ResBody};
-pub use response::Response;
+pub use response::{Response, ResponseBuilder};
+cfg_feature! {
+    #![feature = "link-hints"]
+    pub use response::LinkHint;
+}
 
 pub use http::version::Version;
 
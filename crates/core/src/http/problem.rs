@@ -0,0 +1,188 @@
+//! RFC 7807 problem details.
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{Map, Value};
+
+use crate::http::{StatusCode, StatusError};
+use crate::{Response, Scribe};
+
+/// Problem details for HTTP APIs, see [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807).
+///
+/// Use [`Response::problem_detail`](crate::http::Response::problem_detail) or [`Response::render`] to write
+/// it to response.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::http::{ProblemDetail, Response, StatusCode};
+///
+/// let mut res = Response::new();
+/// res.problem_detail(
+///     ProblemDetail::new(StatusCode::FORBIDDEN, "You do not have enough credit.")
+///         .detail("Your current balance is 30, but that costs 50.")
+///         .instance("/account/12345/msgs/abc")
+///         .extension("balance", 30),
+/// );
+/// assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ProblemDetail {
+    /// A URI reference that identifies the problem type, default is `about:blank`.
+    pub type_uri: String,
+    /// A short, human-readable summary of the problem type.
+    pub title: String,
+    /// The HTTP status code.
+    pub status: StatusCode,
+    /// A human-readable explanation specific to this occurrence of the problem.
+    pub detail: Option<String>,
+    /// A URI reference that identifies the specific occurrence of the problem.
+    pub instance: Option<String>,
+    /// Extension members, serialized as top-level fields.
+    pub extensions: Map<String, Value>,
+}
+
+impl ProblemDetail {
+    /// Create new `ProblemDetail` with status code and title.
+    #[inline]
+    pub fn new(status: StatusCode, title: impl Into<String>) -> Self {
+        Self {
+            type_uri: "about:blank".to_owned(),
+            title: title.into(),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+    /// Create new `ProblemDetail` from [`StatusError`].
+    ///
+    /// The error's name is used as title, its detail, or brief if there is no detail, is used as detail.
+    pub fn from_status_error(err: StatusError) -> Self {
+        let detail = err.detail.or_else(|| (!err.brief.is_empty()).then_some(err.brief));
+        Self {
+            detail,
+            ..Self::new(err.code, err.name)
+        }
+    }
+    /// Sets problem type uri and returns new `ProblemDetail`.
+    #[inline]
+    pub fn type_uri(mut self, type_uri: &str) -> Self {
+        self.type_uri = type_uri.to_owned();
+        self
+    }
+    /// Sets detail and returns new `ProblemDetail`.
+    #[inline]
+    pub fn detail(mut self, detail: &str) -> Self {
+        self.detail = Some(detail.to_owned());
+        self
+    }
+    /// Sets instance and returns new `ProblemDetail`.
+    #[inline]
+    pub fn instance(mut self, instance: &str) -> Self {
+        self.instance = Some(instance.to_owned());
+        self
+    }
+    /// Adds an extension member and returns new `ProblemDetail`.
+    ///
+    /// Members whose names conflict with standard members are ignored when serializing.
+    pub fn extension(mut self, key: &str, value: impl Serialize) -> Self {
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                self.extensions.insert(key.to_owned(), value);
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, key, "problem detail extension serialize failed");
+            }
+        }
+        self
+    }
+}
+
+impl From<StatusError> for ProblemDetail {
+    #[inline]
+    fn from(err: StatusError) -> Self {
+        Self::from_status_error(err)
+    }
+}
+
+impl Scribe for ProblemDetail {
+    #[inline]
+    fn render(self, res: &mut Response) {
+        res.problem_detail(self);
+    }
+}
+
+impl Serialize for ProblemDetail {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", &self.type_uri)?;
+        map.serialize_entry("title", &self.title)?;
+        map.serialize_entry("status", &self.status.as_u16())?;
+        if let Some(detail) = &self.detail {
+            map.serialize_entry("detail", detail)?;
+        }
+        if let Some(instance) = &self.instance {
+            map.serialize_entry("instance", instance)?;
+        }
+        for (key, value) in &self.extensions {
+            if !["type", "title", "status", "detail", "instance"].contains(&key.as_str()) {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::header::CONTENT_TYPE;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[test]
+    fn test_serialize_problem_detail() {
+        let problem = ProblemDetail::new(StatusCode::NOT_FOUND, "Not Found")
+            .detail("user not found")
+            .extension("user_id", 42)
+            .extension("status", 200);
+        assert_eq!(
+            serde_json::to_string(&problem).unwrap(),
+            r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"user not found","user_id":42}"#
+        );
+    }
+
+    #[test]
+    fn test_from_status_error() {
+        let problem = ProblemDetail::from_status_error(StatusError::not_found());
+        assert_eq!(problem.status, StatusCode::NOT_FOUND);
+        assert_eq!(problem.title, "Not Found");
+        assert_eq!(
+            problem.detail.as_deref(),
+            Some("The requested resource could not be found.")
+        );
+
+        let problem: ProblemDetail = StatusError::bad_request().detail("name is required").into();
+        assert_eq!(problem.detail.as_deref(), Some("name is required"));
+    }
+
+    #[tokio::test]
+    async fn test_render_problem_detail() {
+        #[handler]
+        async fn create() -> Result<&'static str, ProblemDetail> {
+            Err(ProblemDetail::new(StatusCode::CONFLICT, "Conflict").extension("user_id", 42))
+        }
+        let service = Service::new(Router::new().post(create));
+        let mut res = TestClient::post("http://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::CONFLICT));
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/problem+json");
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            r#"{"type":"about:blank","title":"Conflict","status":409,"user_id":42}"#
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use crate::http::ParseError;
 
+/// Largest gap between two ranges which are still merged by [`HttpRange::coalesce`].
+const MAX_COALESCE_GAP: u64 = 80;
+
 /// HTTP Range header representation.
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct HttpRange {
     /// Start position.
@@ -97,6 +100,30 @@ impl HttpRange {
 
         Ok(ranges)
     }
+
+    /// Combines ranges into one range, if they overlap or are separated by small gaps only.
+    ///
+    /// Multiple ranges are served as a single part response covering all ranges, which is allowed by
+    /// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-14.2). Returns `None` if there is no
+    /// non-empty range, or the ranges are too far apart to be merged, in which case the whole content
+    /// should be sent.
+    pub fn coalesce(ranges: &[HttpRange]) -> Option<HttpRange> {
+        let mut ranges = ranges.iter().filter(|range| range.length > 0).collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+        let (first, rest) = ranges.split_first()?;
+        let start = first.start;
+        let mut end = first.start + first.length;
+        for range in rest {
+            if range.start > end.saturating_add(MAX_COALESCE_GAP) {
+                return None;
+            }
+            end = end.max(range.start + range.length);
+        }
+        Some(HttpRange {
+            start,
+            length: end - start,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -267,4 +294,14 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_coalesce() {
+        let ranges = HttpRange::parse("bytes=150-299, 0-99, 50-119", 1000).unwrap();
+        assert_eq!(HttpRange::coalesce(&ranges), Some(HttpRange { start: 0, length: 300 }));
+        let ranges = HttpRange::parse("bytes=0-9, 900-999", 1000).unwrap();
+        assert_eq!(HttpRange::coalesce(&ranges), None);
+        assert_eq!(HttpRange::coalesce(&[]), None);
+        assert_eq!(HttpRange::coalesce(&HttpRange::parse("bytes=-5", 0).unwrap()), None);
+    }
 }
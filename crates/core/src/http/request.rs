@@ -8,7 +8,7 @@ use std::sync::Arc;
 use bytes::Bytes;
 #[cfg(feature = "cookie")]
 use cookie::{Cookie, CookieJar};
-use http::header::{AsHeaderName, HeaderMap, HeaderValue, IntoHeaderName, CONTENT_TYPE};
+use http::header::{AsHeaderName, HeaderMap, HeaderValue, IntoHeaderName, CONNECTION, CONTENT_TYPE, UPGRADE};
 use http::method::Method;
 pub use http::request::Parts;
 use http::uri::{Scheme, Uri};
@@ -22,7 +22,7 @@ use parking_lot::RwLock;
 use serde::de::Deserialize;
 
 use crate::conn::SocketAddr;
-use crate::extract::{Extractible, Metadata};
+use crate::extract::{Extractible, FromMultipart, Metadata};
 use crate::http::body::ReqBody;
 use crate::http::form::{FilePart, FormData};
 use crate::http::{Mime, ParseError, Version};
@@ -524,6 +524,30 @@ impl Request {
             .and_then(|v| v.parse().ok())
     }
 
+    /// Check whether the request asks for protocol upgrade, which means `Connection` header contains `upgrade`
+    /// and `Upgrade` header is present.
+    #[inline]
+    pub fn is_http_upgrade(&self) -> bool {
+        let has_connection_upgrade = self
+            .headers
+            .get_all(CONNECTION)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .any(|v| v.trim().eq_ignore_ascii_case("upgrade"));
+        has_connection_upgrade && self.headers.contains_key(UPGRADE)
+    }
+
+    /// Get the protocol requested by `Upgrade` header, for example: `websocket`, `h2c`.
+    #[inline]
+    pub fn upgrade_protocol(&self) -> Option<&str> {
+        self.headers
+            .get(UPGRADE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+
     cfg_feature! {
         #![feature = "cookie"]
         /// Get `CookieJar` reference.
@@ -708,6 +732,9 @@ impl Request {
     }
 
     /// Extract request as type `T` from request's different parts.
+    ///
+    /// Fields of `source(from = "state")` are read from [`Depot`](crate::Depot) which is not available here,
+    /// types with such fields can only be extracted as handler argument.
     #[inline]
     pub async fn extract<'de, T>(&'de mut self) -> Result<T, ParseError>
     where
@@ -745,6 +772,19 @@ impl Request {
         from_str_multi_map(queries).map_err(ParseError::Deserialize)
     }
 
+    /// Parse query string as type `T` from request with [`serde_qs`], it supports nested objects
+    /// (`filter[name]=foo&filter[age]=30`) and arrays (`ids[]=1&ids[]=2`).
+    #[cfg(feature = "serde-qs")]
+    #[inline]
+    pub fn parse_qs<'de, T>(&'de mut self) -> Result<T, ParseError>
+    where
+        T: Deserialize<'de>,
+    {
+        serde_qs::Config::new(5, false)
+            .deserialize_str(self.uri.query().unwrap_or_default())
+            .map_err(ParseError::SerdeQs)
+    }
+
     /// Parse headers as type `T` from request.
     #[inline]
     pub fn parse_headers<'de, T>(&'de mut self) -> Result<T, ParseError>
@@ -819,6 +859,17 @@ impl Request {
         Err(ParseError::InvalidContentType)
     }
 
+    /// Parse `multipart/form-data` body as type `T` from request.
+    ///
+    /// *Notice: This method takes body and body's size is not limited.
+    #[inline]
+    pub async fn parse_multipart<T>(&mut self) -> Result<T, ParseError>
+    where
+        T: FromMultipart,
+    {
+        T::from_multipart(self.form_data().await?).await
+    }
+
     /// Parse json body or form body as type `T` from request with default max size.
     #[inline]
     pub async fn parse_body<'de, T>(&'de mut self) -> Result<T, ParseError>
@@ -897,6 +948,25 @@ mod tests {
             .build();
         assert_eq!(req.parse_json::<User>().await.unwrap(), User { name: "jobs".into() });
     }
+    #[test]
+    fn test_http_upgrade() {
+        let req = TestClient::get("http://127.0.0.1:5801/ws")
+            .add_header("connection", "keep-alive, Upgrade", true)
+            .add_header("upgrade", "websocket", true)
+            .build();
+        assert!(req.is_http_upgrade());
+        assert_eq!(req.upgrade_protocol(), Some("websocket"));
+
+        let req = TestClient::get("http://127.0.0.1:5801/ws")
+            .add_header("upgrade", "h2c", true)
+            .build();
+        assert!(!req.is_http_upgrade());
+        assert_eq!(req.upgrade_protocol(), Some("h2c"));
+
+        let req = TestClient::get("http://127.0.0.1:5801/ws").build();
+        assert!(!req.is_http_upgrade());
+        assert_eq!(req.upgrade_protocol(), None);
+    }
     #[tokio::test]
     async fn test_query() {
         let req = TestClient::get("http://127.0.0.1:5801/hello?name=rust&name=25&name=a&name=2&weapons=98&weapons=gun")
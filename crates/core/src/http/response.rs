@@ -12,7 +12,7 @@ use http::{version::Version, Extensions};
 use mime::Mime;
 
 use crate::fs::NamedFile;
-use crate::http::{StatusCode, StatusError};
+use crate::http::{CspDirectives, ProblemDetail, StatusCode, StatusError};
 use crate::{BoxedError, Error, Scribe};
 use bytes::Bytes;
 
@@ -41,6 +41,23 @@ impl Default for Response {
         Self::new()
     }
 }
+macro_rules! impl_from_body_for_response {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Response {
+                /// Create a new `Response` with the given body, `Content-Type` header is not set.
+                #[inline]
+                fn from(body: $ty) -> Self {
+                    let mut res = Self::new();
+                    res.body = body.into();
+                    res
+                }
+            }
+        )*
+    };
+}
+impl_from_body_for_response!(String, &'static str, Vec<u8>, Bytes);
+
 impl<B> From<hyper::Response<B>> for Response
 where
     B: Into<ResBody>,
@@ -124,6 +141,25 @@ impl Response {
     pub fn headers_mut(&mut self) -> &mut HeaderMap {
         &mut self.headers
     }
+    /// Get an owned copy of current headers.
+    ///
+    /// This is useful for comparing headers before and after handlers are executed in middleware.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// let snapshot = res.headers_snapshot();
+    /// res.headers_mut().insert("x-custom", "value".parse().unwrap());
+    /// assert!(snapshot.get("x-custom").is_none());
+    /// assert!(res.headers().get("x-custom").is_some());
+    /// ```
+    #[inline]
+    pub fn headers_snapshot(&self) -> HeaderMap {
+        self.headers.clone()
+    }
     /// Sets headers.
     #[inline]
     pub fn set_headers(&mut self, headers: HeaderMap) {
@@ -150,6 +186,95 @@ impl Response {
         Ok(self)
     }
 
+    /// Sets `Content-Security-Policy` header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::{CspDirectives, Response};
+    ///
+    /// let mut res = Response::new();
+    /// res.content_security_policy(CspDirectives::new().default_src(&["'self'"])).unwrap();
+    /// assert_eq!(res.headers().get("content-security-policy").unwrap(), "default-src 'self'");
+    /// ```
+    #[inline]
+    pub fn content_security_policy(&mut self, policy: CspDirectives) -> crate::Result<&mut Self> {
+        self.add_header(http::header::CONTENT_SECURITY_POLICY, policy.build(), true)
+    }
+    /// Sets `Content-Security-Policy-Report-Only` header.
+    #[inline]
+    pub fn csp_report_only(&mut self, policy: CspDirectives) -> crate::Result<&mut Self> {
+        self.add_header(http::header::CONTENT_SECURITY_POLICY_REPORT_ONLY, policy.build(), true)
+    }
+
+    /// Sets `Location` header, returns an error if `uri` is not a valid uri.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.location("/users/42").unwrap();
+    /// assert_eq!(res.headers().get("location").unwrap(), "/users/42");
+    /// ```
+    #[inline]
+    pub fn location(&mut self, uri: &str) -> crate::Result<&mut Self> {
+        let uri = uri
+            .parse::<http::Uri>()
+            .map_err(|_| Error::Other("invalid location uri".into()))?;
+        self.add_header(http::header::LOCATION, uri.to_string(), true)
+    }
+    /// Sets status code to `307 Temporary Redirect` and `Location` header, returns an error if `uri` is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::{Response, StatusCode};
+    ///
+    /// let mut res = Response::new();
+    /// res.redirect_temporary("/login").unwrap();
+    /// assert_eq!(res.status_code, Some(StatusCode::TEMPORARY_REDIRECT));
+    /// assert_eq!(res.headers().get("location").unwrap(), "/login");
+    /// ```
+    #[inline]
+    pub fn redirect_temporary(&mut self, uri: impl AsRef<str>) -> crate::Result<&mut Self> {
+        self.redirect(StatusCode::TEMPORARY_REDIRECT, uri.as_ref())
+    }
+    /// Sets status code to `308 Permanent Redirect` and `Location` header, returns an error if `uri` is invalid.
+    #[inline]
+    pub fn redirect_permanent(&mut self, uri: impl AsRef<str>) -> crate::Result<&mut Self> {
+        self.redirect(StatusCode::PERMANENT_REDIRECT, uri.as_ref())
+    }
+    /// Sets status code to `303 See Other` and `Location` header, returns an error if `uri` is invalid.
+    #[inline]
+    pub fn redirect_see_other(&mut self, uri: impl AsRef<str>) -> crate::Result<&mut Self> {
+        self.redirect(StatusCode::SEE_OTHER, uri.as_ref())
+    }
+    fn redirect(&mut self, status_code: StatusCode, uri: &str) -> crate::Result<&mut Self> {
+        let redirect = crate::writing::Redirect::with_status_code(status_code, uri)?;
+        self.render(redirect);
+        Ok(self)
+    }
+    /// Sets `Allow` header, existing value is always overwritten.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::{Method, Response};
+    ///
+    /// let mut res = Response::new();
+    /// res.allow(&[Method::GET, Method::POST]);
+    /// assert_eq!(res.headers().get("allow").unwrap(), "GET, POST");
+    /// ```
+    #[inline]
+    pub fn allow(&mut self, methods: &[http::Method]) -> &mut Self {
+        let value = methods.iter().map(|m| m.as_str()).collect::<Vec<_>>().join(", ");
+        let value = HeaderValue::from_str(&value).expect("method names should be valid header value");
+        self.headers.insert(http::header::ALLOW, value);
+        self
+    }
+
     /// Get version.
     #[inline]
     pub fn version(&self) -> Version {
@@ -185,6 +310,14 @@ impl Response {
         self.replace_body(ResBody::None)
     }
 
+    /// Take body from response and collect all data into `Bytes`.
+    ///
+    /// See [`ResBody::into_bytes`].
+    #[inline]
+    pub async fn take_body_bytes(&mut self) -> Result<Bytes, BoxedError> {
+        self.take_body().into_bytes().await
+    }
+
     /// If returns `true`, it means this response is ready for write back and the reset handlers should be skipped.
     #[inline]
     pub fn is_stamped(&mut self) -> bool {
@@ -202,15 +335,12 @@ impl Response {
     pub fn into_hyper(self) -> hyper::Response<ResBody> {
         let Self {
             status_code,
-            #[cfg(feature = "cookie")]
             mut headers,
             #[cfg(feature = "cookie")]
             cookies,
-            #[cfg(not(feature = "cookie"))]
-            headers,
+            version,
             body,
             extensions,
-            ..
         } = self;
 
         #[cfg(feature = "cookie")]
@@ -225,6 +355,19 @@ impl Response {
             ResBody::Error(e) => e.code,
             _ => StatusCode::OK,
         });
+
+        // Do not rely on hyper to add chunked encoding for stream body, it is not applicable for HTTP/2.
+        // `Transfer-Encoding` is not allowed for 1xx, 204 and 304 responses, and header set by user is kept.
+        if version == Version::HTTP_11
+            && body.is_stream()
+            && !status_code.is_informational()
+            && status_code != StatusCode::NO_CONTENT
+            && status_code != StatusCode::NOT_MODIFIED
+            && !headers.contains_key(http::header::CONTENT_LENGTH)
+            && !headers.contains_key(http::header::TRANSFER_ENCODING)
+        {
+            headers.insert(http::header::TRANSFER_ENCODING, HeaderValue::from_static("chunked"));
+        }
         let mut res = hyper::Response::new(body);
         *res.extensions_mut() = extensions;
         *res.headers_mut() = headers;
@@ -342,6 +485,36 @@ impl Response {
             .and_then(|h| h.to_str().ok())
             .and_then(|v| v.parse().ok())
     }
+    /// Sets `Content-Type` header, existing value is always overwritten.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.set_content_type(mime::TEXT_HTML_UTF_8);
+    /// assert_eq!(res.content_type(), Some(mime::TEXT_HTML_UTF_8));
+    /// ```
+    #[inline]
+    pub fn set_content_type(&mut self, mime: Mime) -> &mut Self {
+        let value = HeaderValue::from_str(mime.as_ref()).expect("mime should be valid header value");
+        self.headers.insert(http::header::CONTENT_TYPE, value);
+        self
+    }
+    /// Get content type, if it is not set or invalid, `default` is set and returned.
+    ///
+    /// The header is parsed every time, because [`Response::headers`] can be changed directly.
+    #[inline]
+    pub fn content_type_or_insert(&mut self, default: Mime) -> Mime {
+        match self.content_type() {
+            Some(mime) => mime,
+            None => {
+                self.set_content_type(default.clone());
+                default
+            }
+        }
+    }
 
     /// Sets status code and returns `&mut Self`.
     ///
@@ -377,6 +550,50 @@ impl Response {
         piece.render(self);
     }
 
+    /// Render `value` as json, this is a shortcut of `res.render(Json(value))`.
+    ///
+    /// Unlike rendering `Json`, serialization error is returned and the response is not changed.
+    #[inline]
+    pub fn json<T>(&mut self, value: &T) -> crate::Result<()>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        crate::writing::json::write_json(self, value)
+    }
+    /// Render plain text, this is a shortcut of `res.render(Text::Plain(text))`.
+    #[inline]
+    pub fn text(&mut self, text: impl Into<String>) -> &mut Self {
+        self.render(crate::writing::Text::Plain(text.into()));
+        self
+    }
+    /// Render html, this is a shortcut of `res.render(Text::Html(html))`.
+    #[inline]
+    pub fn html(&mut self, html: impl Into<String>) -> &mut Self {
+        self.render(crate::writing::Text::Html(html.into()));
+        self
+    }
+    /// Render `value` as json and returns the response, see [`Response::json`].
+    #[inline]
+    pub fn with_json<T>(mut self, value: &T) -> crate::Result<Self>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.json(value)?;
+        Ok(self)
+    }
+    /// Render plain text and returns the response, see [`Response::text`].
+    #[inline]
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text(text);
+        self
+    }
+    /// Render html and returns the response, see [`Response::html`].
+    #[inline]
+    pub fn with_html(mut self, html: impl Into<String>) -> Self {
+        self.html(html);
+        self
+    }
+
     /// Render content with status code.
     #[inline]
     pub fn stuff<P>(&mut self, code: StatusCode, piece: P)
@@ -387,15 +604,105 @@ impl Response {
         piece.render(self);
     }
 
+    /// Render content with status code `201 Created` and set `Location` header.
+    ///
+    /// If `location` is not a valid header value, an error is returned and the response is not modified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::{Response, StatusCode};
+    ///
+    /// let mut res = Response::new();
+    /// res.created_at("/users/42", "created").unwrap();
+    /// assert_eq!(res.status_code, Some(StatusCode::CREATED));
+    /// ```
+    pub fn created_at<P>(&mut self, location: impl AsRef<str>, piece: P) -> crate::Result<&mut Self>
+    where
+        P: Scribe,
+    {
+        let location = HeaderValue::try_from(location.as_ref())
+            .map_err(|_| Error::Other("invalid location header value".into()))?;
+        self.headers.insert(http::header::LOCATION, location);
+        self.stuff(StatusCode::CREATED, piece);
+        Ok(self)
+    }
+
+    /// Write [`ProblemDetail`] to response as `application/problem+json` content, status code is set to
+    /// the problem's status.
+    pub fn problem_detail(&mut self, problem: ProblemDetail) -> &mut Self {
+        self.status_code(problem.status);
+        match serde_json::to_vec(&problem) {
+            Ok(bytes) => {
+                self.headers.insert(
+                    http::header::CONTENT_TYPE,
+                    HeaderValue::from_static("application/problem+json"),
+                );
+                self.body(ResBody::Once(bytes.into()));
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "problem detail write error");
+                self.render(StatusError::internal_server_error());
+            }
+        }
+        self
+    }
+
+    cfg_feature! {
+        #![feature = "link-hints"]
+        /// Add `Link` headers for resource hints such as `preload` and `preconnect` to response.
+        ///
+        /// No informational `103 Early Hints` response is sent, hyper does not provide a way to send
+        /// informational responses from server side. The hints are only added as `Link` headers on the final
+        /// response, browsers can still use them to preload resources.
+        ///
+        /// Returns an error if any hint's `rel` is not a recognized relation, and in this case no header is added.
+        pub fn link_hints(&mut self, links: &[LinkHint]) -> crate::Result<()> {
+            let mut values = Vec::with_capacity(links.len());
+            for link in links {
+                values.push(link.to_header_value()?);
+            }
+            for value in values {
+                self.headers.append(http::header::LINK, value);
+            }
+            Ok(())
+        }
+    }
+
+    /// Add a `Link: <{path}>; rel=preload` header, so client can start to load the resource at `path` before
+    /// it parses the body.
+    ///
+    /// It is not an HTTP/2 server push, no `PUSH_PROMISE` frame is sent because hyper does not support it, and
+    /// it works the same for all HTTP versions. An error is returned if body is already set or `path` does not
+    /// start with `/`.
+    pub fn preload_hint(&mut self, path: &str) -> crate::Result<()> {
+        if !self.body.is_none() {
+            return Err(Error::other("preload hint must be added before the body begins"));
+        }
+        if !path.starts_with('/') {
+            return Err(Error::other(format!("invalid preload path `{path}`")));
+        }
+        let value = HeaderValue::try_from(format!("<{path}>; rel=preload"))
+            .map_err(|_| Error::other("invalid link header value"))?;
+        self.headers.append(http::header::LINK, value);
+        Ok(())
+    }
+
     /// Attempts to send a file. If file not exists, not found error will occur.
     ///
+    /// `Content-Type`, `Content-Length` and `Last-Modified` headers are set from the file, and the content is
+    /// streamed. Paths contain `..` are rejected with forbidden error to prevent path traversal. Use
+    /// [`Response::send_file_in`] if the file should be confined to a root directory.
+    ///
     /// If you want more settings, you can use `NamedFile::builder` to create a new [`NamedFileBuilder`](crate::fs::NamedFileBuilder).
     pub async fn send_file<P>(&mut self, path: P, req_headers: &HeaderMap)
     where
         P: Into<PathBuf> + Send,
     {
         let path = path.into();
-        if !path.exists() {
+        if path.components().any(|c| c == std::path::Component::ParentDir) {
+            self.render(StatusError::forbidden());
+        } else if !path.exists() {
             self.render(StatusError::not_found());
         } else {
             match NamedFile::builder(path).build().await {
@@ -405,6 +712,46 @@ impl Response {
         }
     }
 
+    /// Attempts to send a file under `root` directory, `path` is relative to `root`. If file not exists, not
+    /// found error will occur.
+    ///
+    /// Both `root` and the joined path are canonicalized, the file is sent only if it is inside of `root`,
+    /// otherwise forbidden error is responded.
+    pub async fn send_file_in<R, P>(&mut self, root: R, path: P, req_headers: &HeaderMap)
+    where
+        R: Into<PathBuf> + Send,
+        P: Into<PathBuf> + Send,
+    {
+        let path = path.into();
+        if path.components().any(|c| c == std::path::Component::ParentDir) {
+            self.render(StatusError::forbidden());
+            return;
+        }
+        let root = match tokio::fs::canonicalize(root.into()).await {
+            Ok(root) => root,
+            Err(e) => {
+                tracing::error!(error = ?e, "canonicalize root dir failed");
+                self.render(StatusError::internal_server_error());
+                return;
+            }
+        };
+        let path = match tokio::fs::canonicalize(root.join(path)).await {
+            Ok(path) => path,
+            Err(_) => {
+                self.render(StatusError::not_found());
+                return;
+            }
+        };
+        if !path.starts_with(&root) {
+            self.render(StatusError::forbidden());
+        } else {
+            match NamedFile::builder(path).build().await {
+                Ok(file) => file.send(req_headers, self).await,
+                Err(_) => self.render(StatusError::internal_server_error()),
+            }
+        }
+    }
+
     /// Write bytes data to body. If body is none, a new `ResBody` will created.
     pub fn write_body(&mut self, data: impl Into<Bytes>) -> crate::Result<()> {
         match self.body_mut() {
@@ -468,6 +815,192 @@ impl Response {
         self.body = body;
         sender
     }
+    /// Create a new [`ResponseBuilder`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::{Response, StatusCode};
+    ///
+    /// let res = Response::builder()
+    ///     .status(200)
+    ///     .header("x-foo", "bar")
+    ///     .body("hello")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(res.status_code, Some(StatusCode::OK));
+    /// ```
+    #[inline]
+    pub fn builder() -> ResponseBuilder {
+        ResponseBuilder::new()
+    }
+}
+
+/// Builder for [`Response`], see [`Response::builder`].
+///
+/// The first error is kept and returned by [`ResponseBuilder::build`], later settings are ignored.
+#[derive(Debug)]
+pub struct ResponseBuilder {
+    inner: crate::Result<Response>,
+}
+impl Default for ResponseBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl ResponseBuilder {
+    /// Create a new `ResponseBuilder`.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: Ok(Response::new()),
+        }
+    }
+    fn and_then<F>(self, f: F) -> Self
+    where
+        F: FnOnce(Response) -> crate::Result<Response>,
+    {
+        Self {
+            inner: self.inner.and_then(f),
+        }
+    }
+    /// Sets status code.
+    #[inline]
+    pub fn status<T>(self, code: T) -> Self
+    where
+        T: TryInto<StatusCode>,
+    {
+        self.and_then(|mut res| {
+            let code = code
+                .try_into()
+                .map_err(|_| Error::Other("invalid status code".into()))?;
+            res.status_code(code);
+            Ok(res)
+        })
+    }
+    /// Appends a header.
+    #[inline]
+    pub fn header<N, V>(self, name: N, value: V) -> Self
+    where
+        N: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+    {
+        self.and_then(|mut res| {
+            res.add_header(name, value, false)?;
+            Ok(res)
+        })
+    }
+    /// Sets version.
+    #[inline]
+    pub fn version(self, version: Version) -> Self {
+        self.and_then(|mut res| {
+            res.version = version;
+            Ok(res)
+        })
+    }
+    /// Adds a cookie.
+    #[cfg(feature = "cookie")]
+    #[inline]
+    pub fn cookie(self, cookie: Cookie<'static>) -> Self {
+        self.and_then(|mut res| {
+            res.add_cookie(cookie);
+            Ok(res)
+        })
+    }
+    /// Sets body.
+    #[inline]
+    pub fn body(self, body: impl Into<ResBody>) -> Self {
+        self.and_then(|mut res| {
+            res.body(body.into());
+            Ok(res)
+        })
+    }
+    /// Renders content, see [`Response::render`].
+    #[inline]
+    pub fn render<P>(self, piece: P) -> Self
+    where
+        P: Scribe,
+    {
+        self.and_then(|mut res| {
+            res.render(piece);
+            Ok(res)
+        })
+    }
+    /// Inserts an extension.
+    #[inline]
+    pub fn extension<T>(self, value: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.and_then(|mut res| {
+            res.extensions.insert(value);
+            Ok(res)
+        })
+    }
+    /// Consumes the builder and returns the [`Response`].
+    ///
+    /// Returns error if any setting failed or status code is not set.
+    #[inline]
+    pub fn build(self) -> crate::Result<Response> {
+        let res = self.inner?;
+        if res.status_code.is_none() {
+            return Err(Error::Other("status code is not set".into()));
+        }
+        Ok(res)
+    }
+}
+
+cfg_feature! {
+    #![feature = "link-hints"]
+    /// A `Link` hint used by [`Response::link_hints`].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub struct LinkHint {
+        /// The uri of the linked resource.
+        pub uri: String,
+        /// The relation type, such as `preload` or `preconnect`.
+        pub rel: String,
+        /// The `as` attribute of the link, such as `style` or `script`.
+        pub as_type: Option<String>,
+    }
+}
+#[cfg(feature = "link-hints")]
+impl LinkHint {
+    /// Recognized relation types.
+    pub const RELATIONS: [&'static str; 5] = ["preload", "preconnect", "prefetch", "dns-prefetch", "modulepreload"];
+
+    /// Create a new `LinkHint`.
+    #[inline]
+    pub fn new(uri: impl Into<String>, rel: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            rel: rel.into(),
+            as_type: None,
+        }
+    }
+    /// Create a new `LinkHint` with `rel` set to `preload`.
+    #[inline]
+    pub fn preload(uri: impl Into<String>) -> Self {
+        Self::new(uri, "preload")
+    }
+    /// Sets `as` attribute value and returns `Self`.
+    #[inline]
+    pub fn as_type(mut self, as_type: impl Into<String>) -> Self {
+        self.as_type = Some(as_type.into());
+        self
+    }
+
+    fn to_header_value(&self) -> crate::Result<HeaderValue> {
+        if !Self::RELATIONS.contains(&self.rel.as_str()) {
+            return Err(Error::other(format!("unrecognized link relation `{}`", self.rel)));
+        }
+        let value = match &self.as_type {
+            Some(as_type) => format!("<{}>; rel={}; as={}", self.uri, self.rel, as_type),
+            None => format!("<{}>; rel={}", self.uri, self.rel),
+        };
+        HeaderValue::try_from(value).map_err(|_| Error::other("invalid link header value"))
+    }
 }
 
 impl fmt::Debug for Response {
@@ -495,6 +1028,8 @@ mod test {
     use futures_util::stream::{iter, StreamExt};
     use std::error::Error;
 
+    use crate::test::ResponseExt;
+
     use super::*;
 
     #[test]
@@ -531,4 +1066,411 @@ mod test {
 
         assert_eq!("Hello World", &result)
     }
+
+    #[tokio::test]
+    async fn test_body_into_boxed_stream() {
+        async fn collect(body: ResBody) -> BytesMut {
+            let mut stream = body.into_boxed_stream();
+            let mut result = BytesMut::new();
+            while let Some(Ok(data)) = stream.next().await {
+                result.extend_from_slice(&data)
+            }
+            result
+        }
+        assert!(collect(ResBody::None).await.is_empty());
+        assert_eq!("hello", &collect(ResBody::Once(Bytes::from("hello"))).await);
+        assert_eq!(
+            "Hello World",
+            &collect(ResBody::Chunks(VecDeque::from([
+                Bytes::from("Hello"),
+                Bytes::from(" World")
+            ])))
+            .await
+        );
+        let body = ResBody::stream(iter(vec![
+            Result::<_, Box<dyn Error + Send + Sync>>::Ok("Hello"),
+            Result::<_, Box<dyn Error + Send + Sync>>::Ok(" World"),
+        ]));
+        assert_eq!("Hello World", &collect(body).await);
+
+        let (mut sender, body) = ResBody::channel();
+        tokio::spawn(async move {
+            sender.send_data("Hello World").await.unwrap();
+        });
+        assert_eq!("Hello World", &collect(body).await);
+
+        let (mut sender, body) = ResBody::channel_with_capacity(2);
+        for chunk in ["Hello", " ", "World"] {
+            sender.send_data(chunk).await.unwrap();
+        }
+        drop(sender);
+        assert_eq!("Hello World", &collect(body).await);
+    }
+
+    #[tokio::test]
+    async fn test_body_into_bytes() {
+        assert!(ResBody::None.into_bytes().await.unwrap().is_empty());
+        assert_eq!(ResBody::Once(Bytes::from("hello")).into_bytes().await.unwrap(), "hello");
+        let body = ResBody::Chunks(VecDeque::from([Bytes::from("Hello"), Bytes::from(" World")]));
+        assert_eq!(body.into_bytes().await.unwrap(), "Hello World");
+        let body = ResBody::Chunks(VecDeque::from([Bytes::from("Hello"), Bytes::from(" World")]));
+        assert!(body.into_bytes_with_max_size(8).await.is_err());
+        assert!(ResBody::Once(Bytes::from("hello"))
+            .into_bytes_with_max_size(4)
+            .await
+            .is_err());
+        assert!(ResBody::Error(StatusError::bad_request()).into_bytes().await.is_err());
+
+        let mut res = Response::new();
+        res.body("hello".into());
+        assert_eq!(res.take_body_bytes().await.unwrap(), "hello");
+        assert!(res.body.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_from_body() {
+        let body: ResBody = "hello".into();
+        assert_eq!(body.into_bytes().await.unwrap(), "hello");
+        let body: ResBody = String::from("hello").into();
+        assert!(body.is_once());
+
+        let mut res = Response::from("hello");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+        let mut res = Response::from(vec![1u8, 2, 3]);
+        assert_eq!(res.take_body_bytes().await.unwrap(), vec![1u8, 2, 3]);
+        let mut res = Response::from(Bytes::from_static(b"world"));
+        assert_eq!(res.take_body_bytes().await.unwrap(), "world");
+    }
+
+    #[test]
+    fn test_redirect() {
+        let mut res = Response::new();
+        res.redirect_permanent("/new").unwrap();
+        assert_eq!(res.status_code, Some(StatusCode::PERMANENT_REDIRECT));
+        assert_eq!(res.headers().get(http::header::LOCATION).unwrap(), "/new");
+
+        let mut res = Response::new();
+        res.redirect_see_other(String::from("https://example.com/done"))
+            .unwrap()
+            .add_header("x-flash", "saved", true)
+            .unwrap();
+        assert_eq!(res.status_code, Some(StatusCode::SEE_OTHER));
+        assert_eq!(
+            res.headers().get(http::header::LOCATION).unwrap(),
+            "https://example.com/done"
+        );
+
+        let mut res = Response::new();
+        assert!(res.redirect_temporary("http://exa mple.com").is_err());
+        assert!(res.headers().get(http::header::LOCATION).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_render_shortcuts() {
+        let mut res = Response::new();
+        res.json(&serde_json::json!({"id": 1})).unwrap();
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/json; charset=utf-8"
+        );
+        assert_eq!(res.take_string().await.unwrap(), r#"{"id":1}"#);
+
+        let mut res = Response::new();
+        let mut invalid = std::collections::HashMap::new();
+        invalid.insert(vec![1u8], 1);
+        assert!(res.json(&invalid).is_err());
+        assert!(res.status_code.is_none());
+        assert!(res.headers().get(http::header::CONTENT_TYPE).is_none());
+
+        let mut res = Response::new();
+        res.text("hello");
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let mut res = Response::new();
+        res.html(String::from("<p>hello</p>"));
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(res.take_string().await.unwrap(), "<p>hello</p>");
+
+        let mut res = Response::new().with_json(&[1, 2]).unwrap();
+        assert_eq!(res.take_string().await.unwrap(), "[1,2]");
+        let mut res = Response::new().with_text("hello");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+        let res = Response::new().with_html("<p>hello</p>");
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_file() {
+        let mut res = Response::new();
+        res.send_file("Cargo.toml", &HeaderMap::new()).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.headers().get(http::header::LAST_MODIFIED).is_some());
+        assert!(res.take_string().await.unwrap().contains("salvo_core"));
+
+        let mut res = Response::new();
+        res.send_file("not-exist.txt", &HeaderMap::new()).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        let mut res = Response::new();
+        res.send_file("src/../Cargo.toml", &HeaderMap::new()).await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+
+        let absolute = std::fs::canonicalize("../../Cargo.toml").unwrap();
+        let mut res = Response::new();
+        res.send_file(absolute.clone(), &HeaderMap::new()).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        let mut res = Response::new();
+        res.send_file_in("src", absolute, &HeaderMap::new()).await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+
+        let mut res = Response::new();
+        res.send_file_in("src", "lib.rs", &HeaderMap::new()).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_send_file_symlink_outside_root() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = std::fs::canonicalize("Cargo.toml").unwrap();
+        std::os::unix::fs::symlink(outside, root.path().join("link.toml")).unwrap();
+
+        let mut res = Response::new();
+        res.send_file_in(root.path(), "link.toml", &HeaderMap::new()).await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn test_send_file_range() {
+        use http::header::{ACCEPT_RANGES, CONTENT_RANGE, RANGE};
+
+        let content = std::fs::read("Cargo.toml").unwrap();
+        let total = content.len();
+        async fn send(range: &str) -> Response {
+            let mut headers = HeaderMap::new();
+            headers.insert(RANGE, range.parse().unwrap());
+            let mut res = Response::new();
+            res.send_file("Cargo.toml", &headers).await;
+            res
+        }
+
+        let mut res = send("bytes=0-9").await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(
+            res.headers().get(CONTENT_RANGE).unwrap().to_str().unwrap(),
+            format!("bytes 0-9/{total}")
+        );
+        assert_eq!(res.take_bytes(None).await.unwrap(), &content[0..10]);
+
+        let mut res = send("bytes=20-29, 5-9").await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert_eq!(
+            res.headers().get(CONTENT_RANGE).unwrap().to_str().unwrap(),
+            format!("bytes 5-29/{total}")
+        );
+        assert_eq!(res.take_bytes(None).await.unwrap(), &content[5..30]);
+
+        let mut res = send(&format!("bytes=0-0, {}-", total - 1)).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.headers().get(CONTENT_RANGE).is_none());
+        assert_eq!(res.take_bytes(None).await.unwrap(), &content[..]);
+
+        let res = send(&format!("bytes={total}-")).await;
+        assert_eq!(res.status_code, Some(StatusCode::RANGE_NOT_SATISFIABLE));
+        assert_eq!(
+            res.headers().get(CONTENT_RANGE).unwrap().to_str().unwrap(),
+            format!("bytes */{total}")
+        );
+    }
+
+    #[test]
+    fn test_set_content_type() {
+        let mut res = Response::new();
+        assert_eq!(res.content_type_or_insert(mime::TEXT_PLAIN), mime::TEXT_PLAIN);
+        assert_eq!(res.headers().get(http::header::CONTENT_TYPE).unwrap(), "text/plain");
+        assert_eq!(res.content_type_or_insert(mime::TEXT_HTML), mime::TEXT_PLAIN);
+
+        res.set_content_type(mime::TEXT_HTML_UTF_8);
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(res.headers().get_all(http::header::CONTENT_TYPE).iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_response_builder() {
+        let mut res = Response::builder()
+            .status(StatusCode::CREATED)
+            .header("x-foo", "bar")
+            .header("x-foo", "baz")
+            .body("hello")
+            .build()
+            .unwrap();
+        assert_eq!(res.status_code, Some(StatusCode::CREATED));
+        assert_eq!(res.headers().get_all("x-foo").iter().count(), 2);
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        assert!(Response::builder().body("hello").build().is_err());
+        assert!(Response::builder().status(1000).build().is_err());
+        assert!(Response::builder()
+            .header("x-foo", "bad\nvalue")
+            .status(200)
+            .build()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_problem_detail() {
+        let mut res = Response::new();
+        res.problem_detail(ProblemDetail::new(StatusCode::CONFLICT, "Conflict").instance("/users/42"));
+        assert_eq!(res.status_code, Some(StatusCode::CONFLICT));
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            r#"{"type":"about:blank","title":"Conflict","status":409,"instance":"/users/42"}"#
+        );
+    }
+
+    #[test]
+    fn test_location_and_allow() {
+        let mut res = Response::new();
+        res.location("https://salvo.rs/users?id=1").unwrap();
+        assert_eq!(
+            res.headers().get(http::header::LOCATION).unwrap(),
+            "https://salvo.rs/users?id=1"
+        );
+        assert!(res.location("/users/\n42").is_err());
+        assert_eq!(
+            res.headers().get(http::header::LOCATION).unwrap(),
+            "https://salvo.rs/users?id=1"
+        );
+
+        res.allow(&[http::Method::GET]);
+        res.allow(&[http::Method::GET, http::Method::POST, http::Method::PUT]);
+        assert_eq!(res.headers().get_all(http::header::ALLOW).iter().count(), 1);
+        assert_eq!(res.headers().get(http::header::ALLOW).unwrap(), "GET, POST, PUT");
+    }
+
+    #[tokio::test]
+    async fn test_created_at() {
+        #[derive(serde::Serialize)]
+        struct User {
+            id: u64,
+            name: &'static str,
+        }
+        let mut res = Response::new();
+        res.created_at("/users/42", crate::writing::Json(User { id: 42, name: "jobs" }))
+            .unwrap();
+        assert_eq!(res.status_code, Some(StatusCode::CREATED));
+        assert_eq!(res.headers().get(http::header::LOCATION).unwrap(), "/users/42");
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "application/json; charset=utf-8"
+        );
+        let mut body = res.take_body();
+        let mut result = bytes::BytesMut::new();
+        while let Some(Ok(data)) = body.next().await {
+            result.extend_from_slice(&data.into_data().unwrap_or_default())
+        }
+        assert_eq!(r#"{"id":42,"name":"jobs"}"#, &result);
+
+        let mut res = Response::new();
+        assert!(res.created_at("/users/\n42", "invalid").is_err());
+        assert!(res.status_code.is_none());
+        assert!(res.headers().get(http::header::LOCATION).is_none());
+    }
+
+    #[test]
+    fn test_into_hyper_stream_chunked() {
+        let mut res = Response::new();
+        res.stream(iter(vec![Result::<_, Box<dyn Error + Send + Sync>>::Ok("hello")]));
+        let hyper_res = res.into_hyper();
+        assert_eq!(
+            hyper_res.headers().get(http::header::TRANSFER_ENCODING).unwrap(),
+            "chunked"
+        );
+
+        let mut res = Response::new();
+        *res.version_mut() = Version::HTTP_2;
+        res.stream(iter(vec![Result::<_, Box<dyn Error + Send + Sync>>::Ok("hello")]));
+        let hyper_res = res.into_hyper();
+        assert!(hyper_res.headers().get(http::header::TRANSFER_ENCODING).is_none());
+
+        let mut res = Response::new();
+        res.render("hello");
+        let hyper_res = res.into_hyper();
+        assert!(hyper_res.headers().get(http::header::TRANSFER_ENCODING).is_none());
+
+        for code in [StatusCode::CONTINUE, StatusCode::NO_CONTENT, StatusCode::NOT_MODIFIED] {
+            let mut res = Response::new();
+            res.status_code(code);
+            res.stream(iter(vec![Result::<_, Box<dyn Error + Send + Sync>>::Ok("hello")]));
+            let hyper_res = res.into_hyper();
+            assert!(hyper_res.headers().get(http::header::TRANSFER_ENCODING).is_none());
+        }
+
+        let mut res = Response::new();
+        res.headers_mut().insert(
+            http::header::TRANSFER_ENCODING,
+            HeaderValue::from_static("gzip, chunked"),
+        );
+        res.stream(iter(vec![Result::<_, Box<dyn Error + Send + Sync>>::Ok("hello")]));
+        let hyper_res = res.into_hyper();
+        assert_eq!(
+            hyper_res.headers().get(http::header::TRANSFER_ENCODING).unwrap(),
+            "gzip, chunked"
+        );
+    }
+
+    #[test]
+    fn test_preload_hint() {
+        let mut res = Response::new();
+        res.preload_hint("/style.css").unwrap();
+        assert_eq!(
+            res.headers().get(http::header::LINK).unwrap(),
+            "</style.css>; rel=preload"
+        );
+        assert!(res.preload_hint("style.css").is_err());
+
+        res.render("hello");
+        assert!(res.preload_hint("/app.js").is_err());
+        assert_eq!(res.headers().get_all(http::header::LINK).iter().count(), 1);
+    }
+
+    #[cfg(feature = "link-hints")]
+    #[test]
+    fn test_link_hints() {
+        let mut res = Response::new();
+        res.link_hints(&[
+            LinkHint::preload("/style.css").as_type("style"),
+            LinkHint::new("https://cdn.example.com", "preconnect"),
+        ])
+        .unwrap();
+        let links = res.headers().get_all(http::header::LINK).iter().collect::<Vec<_>>();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0], "</style.css>; rel=preload; as=style");
+        assert_eq!(links[1], "<https://cdn.example.com>; rel=preconnect");
+
+        let mut res = Response::new();
+        assert!(res
+            .link_hints(&[LinkHint::preload("/app.js"), LinkHint::new("/next", "stylesheet")])
+            .is_err());
+        assert!(res.headers().get(http::header::LINK).is_none());
+    }
 }
@@ -55,7 +55,7 @@ cfg_feature! {
 }
 
 pub use self::conn::Listener;
-pub use self::depot::Depot;
+pub use self::depot::{Depot, DepotNamespace, DepotNamespaceRef};
 pub use self::error::{BoxedError, Error};
 pub use self::extract::Extractible;
 pub use self::handler::Handler;
@@ -69,7 +69,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// A list of things that automatically imports into application use salvo_core.
 pub mod prelude {
     pub use async_trait::async_trait;
-    pub use salvo_macros::{handler, Extractible};
+    pub use salvo_macros::{handler, Extractible, FromMultipart};
 
     pub use crate::depot::Depot;
     pub use crate::http::{Request, Response, StatusCode, StatusError};
@@ -115,6 +115,9 @@ pub mod prelude {
 #[doc(hidden)]
 pub mod __private {
     pub use once_cell;
+    pub use serde;
+    pub use serde_json;
+    pub use tokio;
     pub use tracing;
 }
 
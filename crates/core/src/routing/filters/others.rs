@@ -15,8 +15,13 @@ impl MethodFilter {
 }
 impl Filter for MethodFilter {
     #[inline]
-    fn filter(&self, req: &mut Request, _state: &mut PathState) -> bool {
-        req.method() == self.0
+    fn filter(&self, req: &mut Request, state: &mut PathState) -> bool {
+        if req.method() == self.0 {
+            true
+        } else {
+            state.defer_method_miss(&self.0);
+            false
+        }
     }
 }
 impl fmt::Debug for MethodFilter {
@@ -60,19 +65,27 @@ impl fmt::Debug for SchemeFilter {
 }
 
 /// Filter by request uri host.
+///
+/// The host can be a glob pattern, `*` matches one or more characters, for example `*.example.com`
+/// matches `api.example.com`. Hosts are compared case-insensitively and the port is ignored.
+///
+/// Use [`HostFilter::param`] to save the part matched by the first `*` as a request param.
 #[derive(Clone, PartialEq, Eq)]
 pub struct HostFilter {
     /// Host to filter.
     pub host: String,
     /// When host is lack in request uri, use this value.
     pub lack: bool,
+    /// Param name used to save the part matched by the first `*`.
+    pub param: Option<String>,
 }
 impl HostFilter {
     /// Create a new `HostFilter`.
     pub fn new(host: impl Into<String>) -> Self {
         Self {
-            host: host.into(),
+            host: host.into().to_ascii_lowercase(),
             lack: false,
+            param: None,
         }
     }
     /// Set lack value and return `Self`.
@@ -80,24 +93,62 @@ impl HostFilter {
         self.lack = lack;
         self
     }
+    /// Set param name to save the part matched by the first `*` and return `Self`.
+    pub fn param(mut self, name: impl Into<String>) -> Self {
+        self.param = Some(name.into());
+        self
+    }
+}
+/// Matches `host` against glob `pattern`, returns the part matched by the first `*` if any.
+fn match_host<'a>(pattern: &str, host: &'a str) -> Option<Option<&'a str>> {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return (pattern == host).then_some(None);
+    };
+    let remain = host.strip_prefix(prefix)?;
+    // The wildcard matches at least one character, try the shortest match first.
+    let ends = remain
+        .char_indices()
+        .map(|(index, _)| index)
+        .skip(1)
+        .chain([remain.len()]);
+    for end in ends.filter(|end| *end > 0) {
+        if match_host(rest, &remain[end..]).is_some() {
+            return Some(Some(&remain[..end]));
+        }
+    }
+    None
+}
+/// Get host from `Host` header or request uri, without port.
+fn request_host(req: &Request) -> Option<String> {
+    // Http1, if `fix-http1-request-uri` feature is disabled, host is lack. so use header host instead.
+    // https://github.com/hyperium/hyper/issues/1310
+    let host = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| req.uri().host())?;
+    let host = if let Some(ipv6) = host.strip_prefix('[') {
+        ipv6.split_once(']').map(|(ip, _)| ip).unwrap_or(ipv6)
+    } else {
+        host.rsplit_once(':').map(|(host, _)| host).unwrap_or(host)
+    };
+    Some(host.to_ascii_lowercase())
 }
 impl Filter for HostFilter {
     #[inline]
-    fn filter(&self, req: &mut Request, _state: &mut PathState) -> bool {
-        // Http1, if `fix-http1-request-uri` feature is disabled, host is lack. so use header host instead.
-        // https://github.com/hyperium/hyper/issues/1310
-        req.headers()
-            .get(header::HOST)
-            .and_then(|h| h.to_str().ok())
-            .map(|h| {
-                if h.contains(':') {
-                    h.rsplit_once(':').unwrap().0
-                } else {
-                    h
+    fn filter(&self, req: &mut Request, state: &mut PathState) -> bool {
+        let Some(host) = request_host(req) else {
+            return self.lack;
+        };
+        match match_host(&self.host, &host) {
+            Some(captured) => {
+                if let (Some(name), Some(captured)) = (&self.param, captured) {
+                    state.params.insert(name.clone(), captured.to_owned());
                 }
-            })
-            .map(|h| h == self.host)
-            .unwrap_or(self.lack)
+                true
+            }
+            None => false,
+        }
     }
 }
 impl fmt::Debug for HostFilter {
@@ -154,3 +205,41 @@ impl fmt::Debug for PortFilter {
         write!(f, "port:{:?}", self.port)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_host() {
+        assert_eq!(match_host("example.com", "example.com"), Some(None));
+        assert_eq!(match_host("example.com", "api.example.com"), None);
+        assert_eq!(match_host("*.example.com", "api.example.com"), Some(Some("api")));
+        assert_eq!(match_host("*.example.com", "a.b.example.com"), Some(Some("a.b")));
+        assert_eq!(match_host("*.example.com", ".example.com"), None);
+        assert_eq!(match_host("*.example.com", "example.com"), None);
+        assert_eq!(match_host("api.*", "api.example.org"), Some(Some("example.org")));
+        assert_eq!(match_host("*.*.com", "api.example.com"), Some(Some("api")));
+    }
+
+    #[test]
+    fn test_host_filter() {
+        let filter = HostFilter::new("*.Example.com").param("tenant");
+        let mut req = Request::new();
+        req.headers_mut()
+            .insert(header::HOST, "ACME.example.com:8080".parse().unwrap());
+        let mut state = PathState::new("/");
+        assert!(filter.filter(&mut req, &mut state));
+        assert_eq!(state.params["tenant"], "acme");
+
+        let filter = HostFilter::new("::1");
+        req.headers_mut().insert(header::HOST, "[::1]:8080".parse().unwrap());
+        assert!(filter.filter(&mut req, &mut state));
+        req.headers_mut().insert(header::HOST, "[::1]".parse().unwrap());
+        assert!(filter.filter(&mut req, &mut state));
+
+        req.headers_mut().remove(header::HOST);
+        assert!(!filter.filter(&mut req, &mut state));
+        assert!(filter.clone().lack(true).filter(&mut req, &mut state));
+    }
+}
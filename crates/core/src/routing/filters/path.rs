@@ -428,6 +428,24 @@ impl RegexWisp {
     fn new(name: String, regex: Regex) -> RegexWisp {
         RegexWisp { name, regex }
     }
+
+    /// Matches the regex at the start of `input`, named capture groups are also added to params.
+    fn capture(&self, input: &str, state: &mut PathState) -> bool {
+        let Some(caps) = self.regex.captures(input) else {
+            return false;
+        };
+        let Some(cap) = caps.get(0).filter(|cap| cap.start() == 0) else {
+            return false;
+        };
+        for group in self.regex.capture_names().flatten() {
+            if let Some(value) = caps.name(group) {
+                state.params.insert(group.to_owned(), value.as_str().to_owned());
+            }
+        }
+        state.forward(cap.len());
+        state.params.insert(self.name.clone(), cap.as_str().to_owned());
+        true
+    }
 }
 impl PartialEq for RegexWisp {
     #[inline]
@@ -444,15 +462,8 @@ impl PathWisp for RegexWisp {
                 return false;
             }
             if !rest.is_empty() || !self.name.starts_with("*+") {
-                let cap = self.regex.captures(&rest).and_then(|caps| caps.get(0));
-                if let Some(cap) = cap {
-                    let cap = cap.as_str().to_owned();
-                    state.forward(cap.len());
-                    state.params.insert(self.name.clone(), cap);
-                    true
-                } else {
-                    false
-                }
+                let rest = rest.into_owned();
+                self.capture(&rest, state)
             } else {
                 false
             }
@@ -462,15 +473,8 @@ impl PathWisp for RegexWisp {
                 return false;
             }
             let picked = picked.unwrap();
-            let cap = self.regex.captures(picked).and_then(|caps| caps.get(0));
-            if let Some(cap) = cap {
-                let cap = cap.as_str().to_owned();
-                state.forward(cap.len());
-                state.params.insert(self.name.clone(), cap);
-                true
-            } else {
-                false
-            }
+            let picked = picked.to_owned();
+            self.capture(&picked, state)
         }
     }
 }
@@ -1220,4 +1224,30 @@ mod tests {
         let mut state = PathState::new("/users/12/abc");
         assert!(filter.detect(&mut state));
     }
+
+    #[test]
+    fn test_detect_regex_anchored() {
+        let filter = PathFilter::new(r"/files/<name:/[a-z]+\.jpg/>");
+        let mut state = PathState::new("/files/photo.jpg");
+        assert!(filter.detect(&mut state));
+        assert_eq!(state.params["name"], "photo.jpg");
+        for path in ["/files/XXphoto.jpg", "/files/photo.jpgX", "/files/12345"] {
+            let mut state = PathState::new(path);
+            assert!(!filter.detect(&mut state), "{path}");
+        }
+    }
+    #[test]
+    fn test_detect_regex_named_groups() {
+        let filter = PathFilter::new(r"/files/<name:/(?P<stem>[a-z]+)\.(?P<ext>jpg|png)/>");
+        let mut state = PathState::new("/files/photo.png");
+        assert!(filter.detect(&mut state));
+        assert_eq!(state.params["name"], "photo.png");
+        assert_eq!(state.params["stem"], "photo");
+        assert_eq!(state.params["ext"], "png");
+    }
+    #[test]
+    #[should_panic(expected = "regex parse error")]
+    fn test_parse_invalid_regex_panic() {
+        PathFilter::new(r"/files/<name:/[a-z/>");
+    }
 }
@@ -7,15 +7,54 @@ pub use filters::*;
 pub use router::{DetectMatched, Router};
 
 use std::borrow::Cow;
+use std::fmt;
 use std::sync::Arc;
 
 use indexmap::IndexMap;
 
-use crate::http::{Request, Response};
+use crate::http::{Method, Request, Response, StatusCode, StatusError};
 use crate::{Depot, Handler};
 
 #[doc(hidden)]
 pub type PathParams = IndexMap<String, String>;
+/// Handling of requests whose path matches a route but method not, inherited from the nearest
+/// router which configures it.
+#[derive(Clone)]
+pub(crate) struct MethodFallback {
+    pub(crate) auto_options: bool,
+    pub(crate) handler: Option<Arc<dyn Handler>>,
+}
+impl Default for MethodFallback {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            auto_options: true,
+            handler: None,
+        }
+    }
+}
+impl fmt::Debug for MethodFallback {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MethodFallback")
+            .field("auto_options", &self.auto_options)
+            .field("handler", &self.handler.is_some())
+            .finish()
+    }
+}
+impl PartialEq for MethodFallback {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.auto_options == other.auto_options
+            && match (&self.handler, &other.handler) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+impl Eq for MethodFallback {}
+
 #[doc(hidden)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PathState {
@@ -24,6 +63,15 @@ pub struct PathState {
     pub(crate) cursor: (usize, usize),
     pub(crate) params: PathParams,
     pub(crate) end_slash: bool, // For rest match, we want include the last slash.
+    pub(crate) head_fallback: bool,
+    /// Methods of the routes whose path matched, but method filter failed.
+    pub(crate) allowed_methods: Vec<Method>,
+    /// Methods missed by the filter being checked, they are recorded by router if its other filters match.
+    pub(crate) pending_methods: Vec<Method>,
+    /// Fallback of the router currently being detected.
+    pub(crate) method_fallback: MethodFallback,
+    /// Fallback of the router where the first method miss happened.
+    pub(crate) missed_fallback: Option<MethodFallback>,
 }
 impl PathState {
     /// Create new `PathState`.
@@ -47,9 +95,38 @@ impl PathState {
             cursor: (0, 0),
             params: PathParams::new(),
             end_slash,
+            head_fallback: false,
+            allowed_methods: Vec::new(),
+            pending_methods: Vec::new(),
+            method_fallback: MethodFallback::default(),
+            missed_fallback: None,
         }
     }
 
+    /// Resets the url path to detect, used when the request path is rewritten during route detecting.
+    ///
+    /// The matched params and the consumed part of the previous path are discarded.
+    #[inline]
+    pub fn reset_path(&mut self, url_path: &str) {
+        let PathState {
+            parts,
+            cursor,
+            params,
+            end_slash,
+            ..
+        } = PathState::new(url_path);
+        self.parts = parts;
+        self.cursor = cursor;
+        self.params = params;
+        self.end_slash = end_slash;
+    }
+
+    /// Allows a `HEAD` request to be routed to `GET` routes when no route matches `HEAD` itself.
+    #[inline]
+    pub fn enable_head_fallback(&mut self) {
+        self.head_fallback = true;
+    }
+
     #[inline]
     pub fn pick(&self) -> Option<&str> {
         match self.parts.get(self.cursor.0) {
@@ -105,6 +182,24 @@ impl PathState {
     pub fn is_ended(&self) -> bool {
         self.cursor.0 >= self.parts.len()
     }
+
+    /// Marks a method missed by method filter, it is recorded by [`Router::detect`] only if the other filters
+    /// of the router match.
+    #[inline]
+    pub(crate) fn defer_method_miss(&mut self, method: &Method) {
+        self.pending_methods.push(method.clone());
+    }
+
+    /// Records a method of a route whose path matched the request, used for `405 Method Not Allowed`.
+    #[inline]
+    pub(crate) fn record_method_miss(&mut self, method: &Method) {
+        if !self.allowed_methods.contains(method) {
+            self.allowed_methods.push(method.clone());
+        }
+        if self.missed_fallback.is_none() {
+            self.missed_fallback = Some(self.method_fallback.clone());
+        }
+    }
 }
 
 #[inline]
@@ -208,6 +303,39 @@ impl FlowCtrl {
         self.skip_rest();
         self.is_ceased = true;
     }
+
+    /// Render the error to response and cease all following logic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn auth(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl) {
+    ///     if req.header::<String>("authorization").is_none() {
+    ///         ctrl.abort_with(StatusError::unauthorized().brief("Missing credentials."), res);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn abort_with(&mut self, err: StatusError, res: &mut Response) {
+        res.render(err);
+        self.cease();
+    }
+    /// Set status code to response and cease all following logic.
+    ///
+    /// If the status code is an error, the default error of it is rendered.
+    #[inline]
+    pub fn abort_with_status(&mut self, code: StatusCode, res: &mut Response) {
+        match StatusError::from_code(code) {
+            Some(err) => res.render(err),
+            None => {
+                res.status_code(code);
+            }
+        }
+        self.cease();
+    }
 }
 
 #[cfg(test)]
@@ -215,6 +343,35 @@ mod tests {
     use crate::prelude::*;
     use crate::test::{ResponseExt, TestClient};
 
+    #[tokio::test]
+    async fn test_abort_with() {
+        #[handler]
+        async fn auth(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl) {
+            match req.query::<String>("role").as_deref() {
+                None => ctrl.abort_with(StatusError::unauthorized().brief("Missing role."), res),
+                Some("guest") => ctrl.abort_with_status(StatusCode::FORBIDDEN, res),
+                _ => {}
+            }
+        }
+        #[handler]
+        async fn hello() -> &'static str {
+            "Hello World"
+        }
+        let service = Service::new(Router::with_hoop(auth).get(hello));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert!(res.take_string().await.unwrap().contains("Missing role."));
+        let res = TestClient::get("http://127.0.0.1:5801/?role=guest")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+        let mut res = TestClient::get("http://127.0.0.1:5801/?role=admin")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "Hello World");
+    }
+
     #[tokio::test]
     async fn test_custom_filter() {
         #[handler]
@@ -1,6 +1,8 @@
 use std::fmt::{self, Formatter};
 use std::sync::Arc;
 
+use futures_util::future::BoxFuture;
+
 use super::filters;
 use super::{Filter, FnFilter, PathFilter, PathState};
 use crate::handler::{Handler, WhenHoop};
@@ -93,6 +95,26 @@ pub struct Router {
     pub hoops: Vec<Arc<dyn Handler>>,
     /// The final handler to handle request of current router.
     pub goal: Option<Arc<dyn Handler>>,
+    /// The handler for requests whose path matches a route of current router, but method not.
+    pub method_not_allowed: Option<Arc<dyn Handler>>,
+    /// Whether to respond `OPTIONS` requests automatically, `None` means inherit from the parent.
+    pub auto_options: Option<bool>,
+    pub(crate) predicates: Vec<Predicate>,
+}
+type SyncPredicate = Box<dyn Fn(&Request, &Depot) -> bool + Send + Sync>;
+type AsyncPredicate = Box<dyn for<'a> Fn(&'a Request, &'a Depot) -> BoxFuture<'a, bool> + Send + Sync>;
+/// Predicates added by [`Router::filter_fn_sync`] and [`Router::filter_fn_async`].
+pub(crate) enum Predicate {
+    Sync(SyncPredicate),
+    Async(AsyncPredicate),
+}
+impl Predicate {
+    async fn check(&self, req: &Request, depot: &Depot) -> bool {
+        match self {
+            Self::Sync(func) => func(req, depot),
+            Self::Async(func) => func(req, depot).await,
+        }
+    }
 }
 #[doc(hidden)]
 pub struct DetectMatched {
@@ -116,6 +138,9 @@ impl Router {
             filters: Vec::new(),
             hoops: Vec::new(),
             goal: None,
+            method_not_allowed: None,
+            auto_options: None,
+            predicates: Vec::new(),
         }
     }
 
@@ -153,16 +178,66 @@ impl Router {
     }
 
     /// Detect current router is matched for current request.
-    pub fn detect(&self, req: &mut Request, path_state: &mut PathState) -> Option<DetectMatched> {
+    ///
+    /// The depot is passed to predicates added by [`Router::filter_fn_sync`] and [`Router::filter_fn_async`].
+    pub fn detect<'a>(
+        &'a self,
+        req: &'a mut Request,
+        depot: &'a Depot,
+        path_state: &'a mut PathState,
+    ) -> BoxFuture<'a, Option<DetectMatched>> {
+        Box::pin(async move {
+            if self.method_not_allowed.is_none() && self.auto_options.is_none() {
+                return self.detect_inner(req, depot, path_state).await;
+            }
+            let original_fallback = path_state.method_fallback.clone();
+            if let Some(handler) = &self.method_not_allowed {
+                path_state.method_fallback.handler = Some(handler.clone());
+            }
+            if let Some(auto_options) = self.auto_options {
+                path_state.method_fallback.auto_options = auto_options;
+            }
+            let matched = self.detect_inner(req, depot, path_state).await;
+            path_state.method_fallback = original_fallback;
+            matched
+        })
+    }
+    async fn detect_inner(
+        &self,
+        req: &mut Request,
+        depot: &Depot,
+        path_state: &mut PathState,
+    ) -> Option<DetectMatched> {
+        // Methods missed by method filters are only recorded for `405 Method Not Allowed` if all other filters
+        // match, so a route which fails on host or path does not turn `404` to `405`.
+        let mut missed_methods = Vec::new();
         for filter in &self.filters {
-            if !filter.filter(req, path_state) {
+            let passed = filter.filter(req, path_state);
+            let pending = std::mem::take(&mut path_state.pending_methods);
+            if !passed {
+                if pending.is_empty() || !missed_methods.is_empty() {
+                    return None;
+                }
+                missed_methods = pending;
+            }
+        }
+        for predicate in &self.predicates {
+            if !predicate.check(req, depot).await {
                 return None;
             }
         }
+        if !missed_methods.is_empty() {
+            if path_state.is_ended() {
+                for method in &missed_methods {
+                    path_state.record_method_miss(method);
+                }
+            }
+            return None;
+        }
         if !self.routers.is_empty() {
             let original_cursor = path_state.cursor;
             for child in &self.routers {
-                if let Some(dm) = child.detect(req, path_state) {
+                if let Some(dm) = child.detect(req, depot, path_state).await {
                     return Some(DetectMatched {
                         hoops: [&self.hoops[..], &dm.hoops[..]].concat(),
                         goal: dm.goal.clone(),
@@ -288,6 +363,22 @@ impl Router {
         Router::with_filter(FnFilter(func))
     }
     /// Create a new FnFilter from Fn.
+    ///
+    /// The router only matches if the function returns `true`, otherwise the following routers are
+    /// tried as if this one did not exist. Multiple filters must all return `true` to match. Use
+    /// [`Router::filter_fn_sync`] or [`Router::filter_fn_async`] when [`Depot`] is needed.
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn upload() {}
+    ///
+    /// let router = Router::with_path("upload")
+    ///     .filter_fn(|req, _| req.header::<String>("host").as_deref() == Some("files.example.com"))
+    ///     .filter_fn(|req, _| req.content_type().map(|m| m.type_() == "multipart").unwrap_or(false))
+    ///     .post(upload);
+    /// ```
     #[inline]
     pub fn filter_fn<T>(self, func: T) -> Self
     where
@@ -296,6 +387,82 @@ impl Router {
         self.filter(FnFilter(func))
     }
 
+    /// Create a new router and add a synchronous predicate with [`Router::filter_fn_sync`].
+    #[inline]
+    pub fn with_filter_fn_sync<F>(func: F) -> Self
+    where
+        F: Fn(&Request, &Depot) -> bool + Send + Sync + 'static,
+    {
+        Router::new().filter_fn_sync(func)
+    }
+    /// Add a synchronous predicate which can read both [`Request`] and [`Depot`].
+    ///
+    /// Predicates run after all filters of current router are matched. If one returns `false`, the
+    /// following routers are tried as if this one did not exist. Multiple predicates must all return
+    /// `true` to match. The depot only contains the values set before routing, such as the connection
+    /// state, hoops have not run at this point.
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn upload() {}
+    ///
+    /// let router = Router::with_path("upload")
+    ///     .filter_fn_sync(|req, _| req.content_type().map(|m| m.type_() == "multipart").unwrap_or(false))
+    ///     .post(upload);
+    /// ```
+    #[inline]
+    pub fn filter_fn_sync<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&Request, &Depot) -> bool + Send + Sync + 'static,
+    {
+        self.predicates.push(Predicate::Sync(Box::new(func)));
+        self
+    }
+    /// Create a new router and add an asynchronous predicate with [`Router::filter_fn_async`].
+    #[inline]
+    pub fn with_filter_fn_async<F>(func: F) -> Self
+    where
+        F: for<'a> Fn(&'a Request, &'a Depot) -> BoxFuture<'a, bool> + Send + Sync + 'static,
+    {
+        Router::new().filter_fn_async(func)
+    }
+    /// Add an asynchronous predicate which can read both [`Request`] and [`Depot`].
+    ///
+    /// It works like [`Router::filter_fn_sync`], the returned future is awaited during routing, so it
+    /// should be cheap, every request reaching current router runs it.
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// async fn is_known_tenant(host: &str) -> bool {
+    ///     host.ends_with(".example.com")
+    /// }
+    ///
+    /// #[handler]
+    /// async fn tenant() {}
+    ///
+    /// let router = Router::new()
+    ///     .filter_fn_async(|req, _| {
+    ///         Box::pin(async move {
+    ///             match req.uri().host() {
+    ///                 Some(host) => is_known_tenant(host).await,
+    ///                 None => false,
+    ///             }
+    ///         })
+    ///     })
+    ///     .get(tenant);
+    /// ```
+    #[inline]
+    pub fn filter_fn_async<F>(mut self, func: F) -> Self
+    where
+        F: for<'a> Fn(&'a Request, &'a Depot) -> BoxFuture<'a, bool> + Send + Sync + 'static,
+    {
+        self.predicates.push(Predicate::Async(Box::new(func)));
+        self
+    }
+
     /// Sets current router's handler.
     #[inline]
     pub fn goal<H: Handler>(mut self, goal: H) -> Self {
@@ -303,6 +470,30 @@ impl Router {
         self
     }
 
+    /// Sets the handler for requests whose path matches a route of current router or it's
+    /// descendants, but method not, and returns new `Router`.
+    ///
+    /// The handler runs after the service hoops, with the `Allow` header already set. If it does
+    /// not set a status code, the response is `405 Method Not Allowed`.
+    #[inline]
+    pub fn method_not_allowed_handler<H: Handler>(mut self, handler: H) -> Self {
+        self.method_not_allowed = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets whether to respond `OPTIONS` requests automatically for current router and it's
+    /// descendants, and returns new `Router`.
+    ///
+    /// When enabled, an `OPTIONS` request to a path which has routes for other methods, but no
+    /// `OPTIONS` handler, gets `204 No Content` with an `Allow` header listing the methods.
+    /// Explicitly registered `OPTIONS` handlers and service hoops, such as CORS, take precedence.
+    /// Routers inherit the setting of their parent, default is `true`.
+    #[inline]
+    pub fn auto_options(mut self, enabled: bool) -> Self {
+        self.auto_options = Some(enabled);
+        self
+    }
+
     /// When you want write router chain, this function will be useful,
     /// You can write your custom logic in FnOnce.
     #[inline]
@@ -323,6 +514,9 @@ impl Router {
 
     /// Add a [`HostFilter`] to current router.
     ///
+    /// The host can be a glob pattern such as `*.example.com`, requests with other hosts fall
+    /// through to the following routers.
+    ///
     /// [`HostFilter`]: super::filters::HostFilter
     #[inline]
     pub fn host(self, host: impl Into<String>) -> Self {
@@ -394,6 +588,94 @@ impl Router {
     }
 }
 
+/// Description of a router node, used by [`Router::print_tree`] and [`Router::to_dot`].
+struct NodeInfo {
+    path: String,
+    others: Vec<String>,
+    hoops: Vec<&'static str>,
+    goal: Option<&'static str>,
+}
+impl NodeInfo {
+    fn new(router: &Router) -> Self {
+        let mut path = "".to_owned();
+        let mut others = Vec::with_capacity(router.filters.len());
+        if router.filters.is_empty() {
+            path = "!NULL!".to_owned();
+        } else {
+            for filter in &router.filters {
+                let info = format!("{filter:?}");
+                if info.starts_with("path:") {
+                    path = info.split_once(':').unwrap().1.to_owned();
+                } else {
+                    let mut parts = info.splitn(2, ':').collect::<Vec<_>>();
+                    if !parts.is_empty() {
+                        others.push(parts.pop().unwrap().to_owned());
+                    }
+                }
+            }
+        }
+        Self {
+            path,
+            others,
+            hoops: router.hoops.iter().map(|hoop| hoop.type_name()).collect(),
+            goal: router.goal.as_ref().map(|goal| goal.type_name()),
+        }
+    }
+}
+impl fmt::Display for NodeInfo {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.path)?;
+        if !self.others.is_empty() {
+            write!(f, "[{}]", self.others.join(","))?;
+        }
+        if !self.hoops.is_empty() {
+            write!(f, " +{}", self.hoops.join(" +"))?;
+        }
+        if let Some(goal) = self.goal {
+            write!(f, " -> {goal}")?;
+        }
+        Ok(())
+    }
+}
+
+cfg_feature! {
+    #![any(debug_assertions, feature = "debug-router")]
+    impl Router {
+        /// Print the routing tree to stdout, for debugging.
+        ///
+        /// Each line shows the path, other filters such as methods, middlewares prefixed with `+`
+        /// and the goal handler after `->`. It is the same as the `Debug` format of router.
+        ///
+        /// This method and [`Router::to_dot`] are only available in debug builds or with `debug-router`
+        /// feature enabled.
+        pub fn print_tree(&self) {
+            print!("{self:?}");
+        }
+
+        /// Render the routing tree as a [Graphviz DOT](https://graphviz.org/doc/info/lang.html) graph.
+        pub fn to_dot(&self) -> String {
+            fn write_node(out: &mut String, next_id: &mut usize, router: &Router) -> usize {
+                let id = *next_id;
+                *next_id += 1;
+                let label = NodeInfo::new(router)
+                    .to_string()
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"");
+                out.push_str(&format!("    n{id} [label=\"{label}\"];\n"));
+                for child in &router.routers {
+                    let child_id = write_node(out, next_id, child);
+                    out.push_str(&format!("    n{id} -> n{child_id};\n"));
+                }
+                id
+            }
+            let mut out = "digraph router {\n    node [shape=box];\n".to_owned();
+            write_node(&mut out, &mut 0, self);
+            out.push_str("}\n");
+            out
+        }
+    }
+}
+
 const SYMBOL_DOWN: &str = "│";
 const SYMBOL_TEE: &str = "├";
 const SYMBOL_ELL: &str = "└";
@@ -401,38 +683,12 @@ const SYMBOL_RIGHT: &str = "─";
 impl fmt::Debug for Router {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         fn print(f: &mut Formatter, prefix: &str, last: bool, router: &Router) -> fmt::Result {
-            let mut path = "".to_owned();
-            let mut others = Vec::with_capacity(router.filters.len());
-            if router.filters.is_empty() {
-                path = "!NULL!".to_owned();
-            } else {
-                for filter in &router.filters {
-                    let info = format!("{filter:?}");
-                    if info.starts_with("path:") {
-                        path = info.split_once(':').unwrap().1.to_owned();
-                    } else {
-                        let mut parts = info.splitn(2, ':').collect::<Vec<_>>();
-                        if !parts.is_empty() {
-                            others.push(parts.pop().unwrap().to_owned());
-                        }
-                    }
-                }
-            }
             let cp = if last {
                 format!("{prefix}{SYMBOL_ELL}{SYMBOL_RIGHT}{SYMBOL_RIGHT}")
             } else {
                 format!("{prefix}{SYMBOL_TEE}{SYMBOL_RIGHT}{SYMBOL_RIGHT}")
             };
-            let hd = if let Some(goal) = &router.goal {
-                format!(" -> {}", goal.type_name())
-            } else {
-                "".into()
-            };
-            if !others.is_empty() {
-                writeln!(f, "{cp}{path}[{}]{hd}", others.join(","))?;
-            } else {
-                writeln!(f, "{cp}{path}{hd}")?;
-            }
+            writeln!(f, "{cp}{}", NodeInfo::new(router))?;
             let routers = router.routers();
             if !routers.is_empty() {
                 let np = if last {
@@ -455,7 +711,7 @@ mod tests {
     use super::{PathState, Router};
     use crate::handler;
     use crate::test::TestClient;
-    use crate::Response;
+    use crate::{Depot, Response};
 
     #[handler]
     async fn fake_handler(_res: &mut Response) {}
@@ -501,18 +757,164 @@ mod tests {
         );
     }
     #[test]
-    fn test_router_detect1() {
+    fn test_router_hoops_and_dot() {
+        #[handler]
+        async fn fake_hoop() {}
+        let router = Router::with_path("users")
+            .hoop(fake_hoop)
+            .push(Router::with_path("<id>").get(fake_handler));
+        assert_eq!(
+            format!("{:?}", router),
+            r#"└──users +salvo_core::routing::router::tests::test_router_hoops_and_dot::fake_hoop
+    └──<id>
+        └──[GET] -> salvo_core::routing::router::tests::fake_handler
+"#
+        );
+        #[cfg(any(debug_assertions, feature = "debug-router"))]
+        assert_eq!(
+            router.to_dot(),
+            r#"digraph router {
+    node [shape=box];
+    n0 [label="users +salvo_core::routing::router::tests::test_router_hoops_and_dot::fake_hoop"];
+    n1 [label="<id>"];
+    n2 [label="[GET] -> salvo_core::routing::router::tests::fake_handler"];
+    n1 -> n2;
+    n0 -> n1;
+}
+"#
+        );
+    }
+    #[tokio::test]
+    async fn test_router_filter_fn() {
+        use crate::test::ResponseExt;
+
+        #[handler]
+        async fn api() -> &'static str {
+            "api"
+        }
+        #[handler]
+        async fn www() -> &'static str {
+            "www"
+        }
+        let router = Router::new()
+            .push(
+                Router::new()
+                    .filter_fn(|req, _| req.uri().host() == Some("api.example.com"))
+                    .filter_fn(|req, _| req.header::<String>("x-version").as_deref() == Some("2"))
+                    .get(api),
+            )
+            .push(Router::new().get(www));
+        let service = crate::Service::new(router);
+
+        let content = TestClient::get("http://api.example.com/")
+            .add_header("x-version", "2", true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "api");
+        for (url, version) in [("http://api.example.com/", "1"), ("http://www.example.com/", "2")] {
+            let content = TestClient::get(url)
+                .add_header("x-version", version, true)
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, "www");
+        }
+    }
+    #[tokio::test]
+    async fn test_router_filter_fn_with_depot() {
+        let router = Router::new()
+            .push(
+                Router::with_path("files")
+                    .filter_fn_sync(|_, depot| depot.get::<bool>("enabled").copied().unwrap_or(false))
+                    .filter_fn_async(|req, depot| {
+                        Box::pin(async move {
+                            let tenant = depot.get::<String>("tenant").ok();
+                            tenant.map(|t| t.as_str()) == req.header::<&str>("x-tenant")
+                        })
+                    })
+                    .get(fake_handler),
+            )
+            .push(Router::with_path("<**rest>").get(fake_handler));
+
+        let mut depot = Depot::new();
+        depot.insert("enabled", true);
+        depot.insert("tenant", "acme".to_owned());
+        let mut req = TestClient::get("http://local.host/files")
+            .add_header("x-tenant", "acme", true)
+            .build();
+        let mut path_state = PathState::new(req.uri().path());
+        router.detect(&mut req, &depot, &mut path_state).await.unwrap();
+        assert!(path_state.params.is_empty());
+
+        for (enabled, tenant) in [(false, "acme"), (true, "other")] {
+            let mut depot = Depot::new();
+            depot.insert("enabled", enabled);
+            depot.insert("tenant", tenant.to_owned());
+            let mut req = TestClient::get("http://local.host/files")
+                .add_header("x-tenant", "acme", true)
+                .build();
+            let mut path_state = PathState::new(req.uri().path());
+            router.detect(&mut req, &depot, &mut path_state).await.unwrap();
+            assert_eq!(path_state.params.get("**rest").map(|s| s.as_str()), Some("files"));
+        }
+    }
+    #[tokio::test]
+    async fn test_router_virtual_host() {
+        use crate::routing::filters::HostFilter;
+        use crate::test::ResponseExt;
+        use crate::Request;
+
+        #[handler]
+        async fn tenant(req: &mut Request) -> String {
+            format!("tenant {}", req.param::<String>("tenant").unwrap())
+        }
+        #[handler]
+        async fn api() -> &'static str {
+            "api"
+        }
+        #[handler]
+        async fn fallback() -> &'static str {
+            "fallback"
+        }
+        let router = Router::new()
+            .push(Router::new().host("api.example.com").get(api))
+            .push(Router::with_filter(HostFilter::new("*.example.com").param("tenant")).get(tenant))
+            .push(Router::new().get(fallback));
+        let service = crate::Service::new(router);
+
+        for (host, expected) in [
+            ("api.example.com", "api"),
+            ("acme.example.com:8080", "tenant acme"),
+            ("example.org", "fallback"),
+        ] {
+            let content = TestClient::get("http://127.0.0.1/")
+                .add_header("host", host, true)
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, expected);
+        }
+    }
+    #[tokio::test]
+    async fn test_router_detect1() {
         let router = Router::default().push(
             Router::with_path("users")
                 .push(Router::with_path("<id>").push(Router::with_path("emails").get(fake_handler))),
         );
         let mut req = TestClient::get("http://local.host/users/12/emails").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect2() {
+    #[tokio::test]
+    async fn test_router_detect2() {
         let router = Router::new()
             .push(Router::with_path("users").push(Router::with_path("<id>").get(fake_handler)))
             .push(
@@ -521,11 +923,11 @@ mod tests {
             );
         let mut req = TestClient::get("http://local.host/users/12/emails").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect3() {
+    #[tokio::test]
+    async fn test_router_detect3() {
         let router = Router::new().push(
             Router::with_path("users").push(
                 Router::with_path(r"<id:/\d+/>")
@@ -534,17 +936,17 @@ mod tests {
         );
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
 
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights/23").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         // assert_eq!(format!("{:?}", path_state), "");
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect4() {
+    #[tokio::test]
+    async fn test_router_detect4() {
         let router = Router::new().push(
             Router::with_path("users").push(
                 Router::with_path(r"<id:/\d+/>")
@@ -553,17 +955,17 @@ mod tests {
         );
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         // assert_eq!(format!("{:?}", path_state), "");
         assert!(matched.is_none());
 
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights/23").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect5() {
+    #[tokio::test]
+    async fn test_router_detect5() {
         let router =
             Router::new().push(Router::with_path("users").push(Router::with_path(r"<id:/\d+/>").push(
                 Router::new().push(
@@ -572,17 +974,17 @@ mod tests {
             )));
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
 
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights/23").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
         assert_eq!(path_state.params["id"], "12");
     }
-    #[test]
-    fn test_router_detect6() {
+    #[tokio::test]
+    async fn test_router_detect6() {
         let router =
             Router::new().push(Router::with_path("users").push(Router::with_path(r"<id:/\d+/>").push(
                 Router::new().push(
@@ -591,16 +993,16 @@ mod tests {
             )));
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_none());
 
         let mut req = TestClient::get("http://local.host/users/12/facebook/insights/23").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect_utf8() {
+    #[tokio::test]
+    async fn test_router_detect_utf8() {
         let router =
             Router::new().push(Router::with_path("用户").push(Router::with_path(r"<id:/\d+/>").push(
                 Router::new().push(
@@ -609,85 +1011,85 @@ mod tests {
             )));
         let mut req = TestClient::get("http://local.host/%E7%94%A8%E6%88%B7/12/facebook/insights").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_none());
 
         let mut req = TestClient::get("http://local.host/%E7%94%A8%E6%88%B7/12/facebook/insights/23").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect9() {
+    #[tokio::test]
+    async fn test_router_detect9() {
         let router = Router::new().push(Router::with_path("users/<sub:/(images|css)/>/<filename>").goal(fake_handler));
         let mut req = TestClient::get("http://local.host/users/12/m.jpg").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_none());
 
         let mut req = TestClient::get("http://local.host/users/css/m.jpg").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect10() {
+    #[tokio::test]
+    async fn test_router_detect10() {
         let router = Router::new().push(Router::with_path(r"users/<*sub:/(images|css)/.+/>").goal(fake_handler));
         let mut req = TestClient::get("http://local.host/users/12/m.jpg").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_none());
 
         let mut req = TestClient::get("http://local.host/users/css/abc/m.jpg").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect11() {
+    #[tokio::test]
+    async fn test_router_detect11() {
         let router =
             Router::new().push(Router::with_path(r"avatars/<width:/\d+/>x<height:/\d+/>.<ext>").goal(fake_handler));
         let mut req = TestClient::get("http://local.host/avatars/321x641f.webp").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_none());
 
         let mut req = TestClient::get("http://local.host/avatars/320x640.webp").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
-    #[test]
-    fn test_router_detect12() {
+    #[tokio::test]
+    async fn test_router_detect12() {
         let router = Router::new().push(Router::with_path("/.well-known/acme-challenge/<token>").goal(fake_handler));
 
         let mut req = TestClient::get("http://local.host/.well-known/acme-challenge/q1XXrxIx79uXNl3I").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
     }
 
-    #[test]
-    fn test_router_detect13() {
+    #[tokio::test]
+    async fn test_router_detect13() {
         let router = Router::new()
             .path("user/<id:/[0-9a-z]{8}(-[0-9a-z]{4}){3}-[0-9a-z]{12}/>")
             .get(fake_handler);
         let mut req = TestClient::get("http://local.host/user/726d694c-7af0-4bb0-9d22-706f7e38641e").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
         let mut req = TestClient::get("http://local.host/user/726d694c-7af0-4bb0-9d22-706f7e386e").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_none());
     }
 
-    #[test]
-    fn test_router_detect_path_encoded() {
+    #[tokio::test]
+    async fn test_router_detect_path_encoded() {
         let router = Router::new().path("api/<p>").get(fake_handler);
         let mut req = TestClient::get("http://127.0.0.1:6060/api/a%2fb%2fc").build();
         let mut path_state = PathState::new(req.uri().path());
-        let matched = router.detect(&mut req, &mut path_state);
+        let matched = router.detect(&mut req, &Depot::new(), &mut path_state).await;
         assert!(matched.is_some());
         assert_eq!(path_state.params["p"], "a/b/c");
     }
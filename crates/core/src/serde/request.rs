@@ -4,7 +4,7 @@ use std::iter::Iterator;
 
 use indexmap::IndexMap;
 use multimap::MultiMap;
-use serde::de::value::Error as ValError;
+use serde::de::value::{Error as ValError, MapDeserializer};
 use serde::de::{self, Deserialize, Error as DeError, IntoDeserializer};
 use serde::forward_to_deserialize_any;
 use serde_json::value::RawValue;
@@ -74,6 +74,7 @@ pub(crate) struct RequestDeserializer<'de> {
     field_source: Option<&'de Source>,
     field_str_value: Option<&'de str>,
     field_vec_value: Option<Vec<CowValue<'de>>>,
+    field_default_value: Option<Result<serde_json::Value, serde_json::Error>>,
 }
 
 impl<'de> RequestDeserializer<'de> {
@@ -118,6 +119,7 @@ impl<'de> RequestDeserializer<'de> {
             field_source: None,
             field_str_value: None,
             field_vec_value: None,
+            field_default_value: None,
         })
     }
 
@@ -151,7 +153,9 @@ impl<'de> RequestDeserializer<'de> {
                 .fields
                 .get(self.field_index as usize)
                 .expect("field must exist.");
-            let metadata = field.metadata.expect("Field's metadata must exist");
+            let Some(metadata) = field.metadata else {
+                return seed.deserialize(MapDeserializer::new(self.collect_rest(field).into_iter()));
+            };
             seed.deserialize(RequestDeserializer {
                 params: self.params,
                 queries: self.queries,
@@ -165,7 +169,18 @@ impl<'de> RequestDeserializer<'de> {
                 field_source: None,
                 field_str_value: None,
                 field_vec_value: None,
+                field_default_value: None,
             })
+            .map_err(|e| ValError::custom(format!("{}: {e}", field.decl_name)))
+        } else if let Some(value) = self.field_default_value.take() {
+            let field = self
+                .metadata
+                .fields
+                .get(self.field_index as usize)
+                .expect("field must exist.");
+            value
+                .and_then(|value| seed.deserialize(value))
+                .map_err(|e| ValError::custom(format!("{}: {e}", field.decl_name)))
         } else {
             let source = self
                 .field_source
@@ -192,6 +207,61 @@ impl<'de> RequestDeserializer<'de> {
         }
     }
 
+    fn field_name(&self, field: &'de Field) -> Cow<'de, str> {
+        metadata_field_name(self.metadata, field)
+    }
+
+    // Collect all key-value pairs from field's sources, keys used by other fields are skipped.
+    fn collect_rest(&self, field: &'de Field) -> Vec<(CowValue<'de>, RestValue<'de>)> {
+        let mut reserved: Vec<Cow<'de, str>> = Vec::new();
+        reserve_field_names(self.metadata, &mut reserved);
+        let mut entries: Vec<(CowValue<'de>, RestValue<'de>)> = Vec::new();
+        let mut push = |key: &'de str, value: RestValue<'de>| {
+            if !reserved.iter().any(|r| r == key) && !entries.iter().any(|(k, _)| k.0 == key) {
+                entries.push((CowValue(key.into()), value));
+            }
+        };
+        for source in &field.sources {
+            match source.from {
+                SourceFrom::Param => {
+                    for (key, value) in self.params {
+                        push(key, RestValue::Str(value));
+                    }
+                }
+                SourceFrom::Query => {
+                    for (key, value) in self.queries.iter() {
+                        push(key, RestValue::Str(value));
+                    }
+                }
+                SourceFrom::Header => {
+                    for (key, value) in self.headers {
+                        push(key.as_str(), RestValue::Str(value.to_str().unwrap_or_default()));
+                    }
+                }
+                #[cfg(feature = "cookie")]
+                SourceFrom::Cookie => {
+                    for cookie in self.cookies.iter() {
+                        push(cookie.name(), RestValue::Str(cookie.value()));
+                    }
+                }
+                SourceFrom::Body => match &self.payload {
+                    Some(Payload::FormData(form_data)) => {
+                        for (key, value) in form_data.fields.iter() {
+                            push(key, RestValue::Str(value));
+                        }
+                    }
+                    Some(Payload::JsonMap(map)) => {
+                        for (key, value) in map {
+                            push(key, RestValue::Json(value.get()));
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+        entries
+    }
+
     #[allow(unreachable_patterns)]
     fn fill_value(&mut self, field: &'de Field) -> bool {
         if field.flatten {
@@ -207,30 +277,18 @@ impl<'de> RequestDeserializer<'de> {
             return false;
         };
 
-        let field_name: Cow<'_, str> = if let Some(rename) = field.rename {
-            Cow::from(rename)
-        } else if let Some(serde_rename) = field.serde_rename {
-            Cow::from(serde_rename)
-        } else if let Some(rename_all) = self.metadata.rename_all {
-            rename_all.apply_to_field(field.decl_name).into()
-        } else if let Some(serde_rename_all) = self.metadata.serde_rename_all {
-            serde_rename_all.apply_to_field(field.decl_name).into()
-        } else {
-            field.decl_name.into()
-        };
+        let field_name = self.field_name(field);
+        // Aliases are tried in order before the field name, the first present one wins.
+        let names = field
+            .aliases
+            .iter()
+            .copied()
+            .chain(std::iter::once(field_name.as_ref()));
 
         for source in sources {
             match source.from {
                 SourceFrom::Param => {
-                    let mut value = self.params.get(&*field_name);
-                    if value.is_none() {
-                        for alias in &field.aliases {
-                            value = self.params.get(*alias);
-                            if value.is_some() {
-                                break;
-                            }
-                        }
-                    }
+                    let value = names.clone().find_map(|name| self.params.get(name));
                     if let Some(value) = value {
                         self.field_str_value = Some(value);
                         self.field_source = Some(source);
@@ -238,15 +296,7 @@ impl<'de> RequestDeserializer<'de> {
                     }
                 }
                 SourceFrom::Query => {
-                    let mut value = self.queries.get_vec(field_name.as_ref());
-                    if value.is_none() {
-                        for alias in &field.aliases {
-                            value = self.queries.get_vec(*alias);
-                            if value.is_some() {
-                                break;
-                            }
-                        }
-                    }
+                    let value = names.clone().find_map(|name| self.queries.get_vec(name));
                     if let Some(value) = value {
                         self.field_vec_value = Some(value.iter().map(|v| CowValue(v.into())).collect());
                         self.field_source = Some(source);
@@ -254,17 +304,10 @@ impl<'de> RequestDeserializer<'de> {
                     }
                 }
                 SourceFrom::Header => {
-                    let mut value = None;
-                    if self.headers.contains_key(field_name.as_ref()) {
-                        value = Some(self.headers.get_all(field_name.as_ref()))
-                    } else {
-                        for alias in &field.aliases {
-                            if self.headers.contains_key(*alias) {
-                                value = Some(self.headers.get_all(*alias));
-                                break;
-                            }
-                        }
-                    };
+                    let value = names
+                        .clone()
+                        .find(|name| self.headers.contains_key(*name))
+                        .map(|name| self.headers.get_all(name));
                     if let Some(value) = value {
                         self.field_vec_value = Some(
                             value
@@ -278,17 +321,7 @@ impl<'de> RequestDeserializer<'de> {
                 }
                 #[cfg(feature = "cookie")]
                 SourceFrom::Cookie => {
-                    let mut value = None;
-                    if let Some(cookie) = self.cookies.get(field_name.as_ref()) {
-                        value = Some(cookie.value());
-                    } else {
-                        for alias in &field.aliases {
-                            if let Some(cookie) = self.cookies.get(alias) {
-                                value = Some(cookie.value());
-                                break;
-                            }
-                        }
-                    };
+                    let value = names.clone().find_map(|name| self.cookies.get(name).map(|c| c.value()));
                     if let Some(value) = value {
                         self.field_str_value = Some(value);
                         self.field_source = Some(source);
@@ -302,15 +335,7 @@ impl<'de> RequestDeserializer<'de> {
                             if let Some(payload) = &self.payload {
                                 match payload {
                                     Payload::FormData(form_data) => {
-                                        let mut value = form_data.fields.get(field_name.as_ref());
-                                        if value.is_none() {
-                                            for alias in &field.aliases {
-                                                value = form_data.fields.get(*alias);
-                                                if value.is_some() {
-                                                    break;
-                                                }
-                                            }
-                                        }
+                                        let value = names.clone().find_map(|name| form_data.fields.get(name));
                                         if let Some(value) = value {
                                             self.field_str_value = Some(value);
                                             self.field_source = Some(source);
@@ -320,15 +345,7 @@ impl<'de> RequestDeserializer<'de> {
                                         }
                                     }
                                     Payload::JsonMap(ref map) => {
-                                        let mut value = map.get(field_name.as_ref());
-                                        if value.is_none() {
-                                            for alias in &field.aliases {
-                                                value = map.get(alias);
-                                                if value.is_some() {
-                                                    break;
-                                                }
-                                            }
-                                        }
+                                        let value = names.clone().find_map(|name| map.get(name));
                                         if let Some(value) = value {
                                             self.field_str_value = Some(value.get());
                                             self.field_source = Some(source);
@@ -349,15 +366,7 @@ impl<'de> RequestDeserializer<'de> {
                         }
                         SourceParser::MultiMap => {
                             if let Some(Payload::FormData(form_data)) = self.payload {
-                                let mut value = form_data.fields.get_vec(field_name.as_ref());
-                                if value.is_none() {
-                                    for alias in &field.aliases {
-                                        value = form_data.fields.get_vec(*alias);
-                                        if value.is_some() {
-                                            break;
-                                        }
-                                    }
-                                }
+                                let value = names.clone().find_map(|name| form_data.fields.get_vec(name));
                                 if let Some(value) = value {
                                     self.field_vec_value = Some(value.iter().map(|v| CowValue(Cow::from(v))).collect());
                                     self.field_source = Some(source);
@@ -378,6 +387,14 @@ impl<'de> RequestDeserializer<'de> {
         }
         false
     }
+    fn fill_default_value(&mut self, field: &'de Field) -> bool {
+        if let Some(default) = field.default {
+            self.field_default_value = Some(default());
+            true
+        } else {
+            false
+        }
+    }
     fn next(&mut self) -> Option<Cow<'_, str>> {
         while self.field_index < self.metadata.fields.len() as isize - 1 {
             self.field_index += 1;
@@ -385,8 +402,9 @@ impl<'de> RequestDeserializer<'de> {
             self.field_flatten = field.flatten;
             self.field_str_value = None;
             self.field_vec_value = None;
+            self.field_default_value = None;
 
-            if self.fill_value(field) {
+            if self.fill_value(field) || self.fill_default_value(field) {
                 return field.serde_rename.map(Cow::from).or_else(|| {
                     if let Some(serde_rename_all) = self.metadata.serde_rename_all {
                         Some(Cow::Owned(serde_rename_all.apply_to_field(field.decl_name)))
@@ -419,9 +437,182 @@ impl<'de> de::Deserializer<'de> for RequestDeserializer<'de> {
         self.deserialize_seq(visitor)
     }
 
+    #[inline]
+    fn deserialize_newtype_struct<V>(mut self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(&mut self)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        mut self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(&mut self)
+    }
+
     forward_to_deserialize_any! {
         bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct tuple_struct map seq
+        bytes byte_buf option unit unit_struct map seq
+        struct enum identifier ignored_any
+    }
+}
+
+// Fields of tuple struct are deserialized by position, missing field is deserialized as `None`.
+impl<'de> de::SeqAccess<'de> for RequestDeserializer<'de> {
+    type Error = ValError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.field_index >= self.metadata.fields.len() as isize - 1 {
+            return Ok(None);
+        }
+        self.field_index += 1;
+        let field = &self.metadata.fields[self.field_index as usize];
+        self.field_flatten = field.flatten;
+        self.field_str_value = None;
+        self.field_vec_value = None;
+        self.field_default_value = None;
+        if self.fill_value(field) || self.fill_default_value(field) {
+            self.deserialize_value(seed).map(Some)
+        } else {
+            seed.deserialize(MissingValue(field.rename.unwrap_or(field.decl_name)))
+                .map(Some)
+        }
+    }
+}
+
+fn metadata_field_name<'de>(metadata: &'de Metadata, field: &'de Field) -> Cow<'de, str> {
+    if let Some(rename) = field.rename {
+        Cow::from(rename)
+    } else if let Some(serde_rename) = field.serde_rename {
+        Cow::from(serde_rename)
+    } else if let Some(rename_all) = metadata.rename_all {
+        rename_all.apply_to_field(field.decl_name).into()
+    } else if let Some(serde_rename_all) = metadata.serde_rename_all {
+        serde_rename_all.apply_to_field(field.decl_name).into()
+    } else {
+        field.decl_name.into()
+    }
+}
+
+// Names and aliases of fields, including fields of nested flatten metadata, which should not be collected
+// into the rest map of a flatten field.
+fn reserve_field_names<'de>(metadata: &'de Metadata, reserved: &mut Vec<Cow<'de, str>>) {
+    for field in &metadata.fields {
+        if field.flatten {
+            if let Some(nested) = field.metadata {
+                reserve_field_names(nested, reserved);
+            }
+        } else {
+            reserved.push(metadata_field_name(metadata, field));
+            reserved.extend(field.aliases.iter().map(|alias| Cow::from(*alias)));
+        }
+    }
+}
+
+// Value of flattened map field, json values are deserialized as they are.
+enum RestValue<'de> {
+    Str(&'de str),
+    Json(&'de str),
+}
+impl<'de> IntoDeserializer<'de, ValError> for RestValue<'de> {
+    type Deserializer = Self;
+
+    #[inline]
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+// Typed values are parsed by `CowValue` for plain strings and by `serde_json` for json values.
+macro_rules! forward_rest_value {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                match self {
+                    Self::Str(value) => CowValue(value.into()).$method($($arg,)* visitor),
+                    Self::Json(value) => serde_json::Deserializer::new(serde_json::de::StrRead::new(value))
+                        .$method($($arg,)* visitor)
+                        .map_err(ValError::custom),
+                }
+            }
+        )*
+    };
+}
+impl<'de> de::Deserializer<'de> for RestValue<'de> {
+    type Error = ValError;
+
+    forward_rest_value! {
+        deserialize_any(),
+        deserialize_option(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+}
+
+struct MissingValue(&'static str);
+impl<'de> de::Deserializer<'de> for MissingValue {
+    type Error = ValError;
+
+    #[inline]
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(ValError::missing_field(self.0))
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_none()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct tuple tuple_struct map seq
         struct enum identifier ignored_any
     }
 }
@@ -467,6 +658,8 @@ impl<'de> de::MapAccess<'de> for RequestDeserializer<'de> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use serde::{Deserialize, Serialize};
 
     use crate::macros::Extractible;
@@ -477,7 +670,9 @@ mod tests {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "query")))]
         struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
             q1: String,
+            #[salvo(extract(compile_check = "required"))]
             q2: i64,
         }
         let mut req = TestClient::get("http://127.0.0.1:5800/test/1234/param2v")
@@ -500,7 +695,7 @@ mod tests {
         #[salvo(extract(default_source(from = "query")))]
         struct RequestData<'a> {
             #[salvo(extract(source(from = "param"), source(from = "query")))]
-            #[salvo(extract(source(from = "body")))]
+            #[salvo(extract(source(from = "body"), compile_check = "required"))]
             q1: &'a str,
             // #[salvo(extract(source(from = "query")))]
             // #[serde(alias = "param2", alias = "param3")]
@@ -515,12 +710,44 @@ mod tests {
         assert_eq!(data, RequestData { q1: "q1v" });
     }
 
+    #[tokio::test]
+    async fn test_de_request_with_borrowed_queries() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct QueryParams<'a> {
+            #[salvo(extract(compile_check = "required"))]
+            name: &'a str,
+            #[salvo(extract(compile_check = "required"))]
+            tag: &'a str,
+        }
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/test")
+            .query("name", "jobs")
+            .query("tag", "rust")
+            .build();
+        let data = <QueryParams<'_> as crate::extract::Extractible>::extract(&mut req)
+            .await
+            .unwrap();
+        assert_eq!(
+            data,
+            QueryParams {
+                name: "jobs",
+                tag: "rust"
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_de_request_with_rename() {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "query")))]
         struct RequestData<'a> {
-            #[salvo(extract(source(from = "param"), source(from = "query"), rename = "abc"))]
+            #[salvo(extract(
+                source(from = "param"),
+                source(from = "query"),
+                rename = "abc",
+                compile_check = "required"
+            ))]
             q1: &'a str,
         }
 
@@ -536,8 +763,9 @@ mod tests {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "query"), rename_all = "PascalCase"))]
         struct RequestData<'a> {
+            #[salvo(extract(compile_check = "required"))]
             first_name: &'a str,
-            #[salvo(extract(rename = "lastName"))]
+            #[salvo(extract(rename = "lastName", compile_check = "required"))]
             last_name: &'a str,
         }
 
@@ -561,15 +789,17 @@ mod tests {
         #[salvo(extract(default_source(from = "query")))]
         struct RequestData<'a> {
             #[salvo(extract(source(from = "param")))]
-            #[salvo(extract(alias = "param1"))]
+            #[salvo(extract(alias = "param1", compile_check = "required"))]
             p1: String,
-            #[salvo(extract(source(from = "param"), alias = "param2"))]
+            #[salvo(extract(source(from = "param"), alias = "param2", compile_check = "required"))]
             p2: &'a str,
-            #[salvo(extract(source(from = "param"), alias = "param3"))]
+            #[salvo(extract(source(from = "param"), alias = "param3", compile_check = "required"))]
             p3: usize,
             // #[salvo(extract(source(from = "query")))]
+            #[salvo(extract(compile_check = "required"))]
             q1: String,
             // #[salvo(extract(source(from = "query")))]
+            #[salvo(extract(compile_check = "required"))]
             q2: i64,
             // #[salvo(extract(source(from = "body", parse = "json")))]
             // body: RequestBody<'a>,
@@ -595,13 +825,312 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_de_request_with_alias_list() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query"), default_source(from = "body")))]
+        struct RequestData {
+            #[salvo(extract(alias = ["userId", "uid"], compile_check = "required"))]
+            user_id: i64,
+            #[salvo(extract(source(from = "header"), alias = ["x-request-id"], compile_check = "required"))]
+            request_id: String,
+        }
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users")
+            .query("uid", "7")
+            .add_header("x-request-id", "abc", true)
+            .build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                user_id: 7,
+                request_id: "abc".into()
+            }
+        );
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/users")
+            .json(&serde_json::json!({"userId": 8}))
+            .add_header("request_id", "def", true)
+            .build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                user_id: 8,
+                request_id: "def".into()
+            }
+        );
+
+        // Aliases are tried in order before the field name.
+        let mut req = TestClient::get("http://127.0.0.1:5800/users")
+            .query("user_id", "1")
+            .query("uid", "2")
+            .query("userId", "3")
+            .add_header("request_id", "old", true)
+            .add_header("x-request-id", "new", true)
+            .build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                user_id: 3,
+                request_id: "new".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_flatten() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Pagination {
+            #[salvo(extract(compile_check = "required"))]
+            page: u32,
+            #[salvo(extract(compile_check = "required"))]
+            per_page: u32,
+        }
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
+            keyword: String,
+            #[salvo(extract(flatten))]
+            pagination: Pagination,
+        }
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users")
+            .query("keyword", "rust")
+            .query("page", "2")
+            .query("per_page", "20")
+            .build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                keyword: "rust".into(),
+                pagination: Pagination { page: 2, per_page: 20 }
+            }
+        );
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users")
+            .query("keyword", "rust")
+            .query("page", "2")
+            .build();
+        let err = req.extract::<RequestData>().await.unwrap_err();
+        match err {
+            crate::http::ParseError::Deserialize(e) => {
+                assert_eq!(e.to_string(), "pagination: missing field `per_page`")
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_default() {
+        fn default_size() -> u32 {
+            20
+        }
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct RequestData {
+            #[salvo(extract(default))]
+            page: u32,
+            #[salvo(extract(default = "default_size"))]
+            size: u32,
+            keyword: Option<String>,
+        }
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users").build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                page: 0,
+                size: 20,
+                keyword: None
+            }
+        );
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users?page=3&size=5").build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                page: 3,
+                size: 5,
+                keyword: None
+            }
+        );
+    }
+
+    #[cfg(feature = "cookie")]
+    #[tokio::test]
+    async fn test_de_request_with_default_conversion_error() {
+        fn default_map() -> HashMap<Vec<u8>, u8> {
+            HashMap::from([(vec![1], 1)])
+        }
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct RequestData {
+            #[salvo(extract(default = "default_map"))]
+            map: HashMap<Vec<u8>, u8>,
+        }
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users").build();
+        let err = req.extract::<RequestData>().await.unwrap_err();
+        assert!(matches!(err, crate::http::ParseError::Deserialize(e) if e.to_string().contains("map")));
+    }
+
+    #[tokio::test]
+    async fn test_de_request_from_cookie() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        struct RequestData {
+            #[salvo(extract(source(from = "cookie"), compile_check = "required"))]
+            session: String,
+        }
+        let mut req = TestClient::get("http://127.0.0.1:5800/users")
+            .add_header("cookie", "session=abc", true)
+            .build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(data, RequestData { session: "abc".into() });
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_flatten_map() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Search {
+            #[salvo(extract(alias = "q", compile_check = "required"))]
+            keyword: String,
+            #[salvo(extract(source(from = "query"), flatten))]
+            filters: HashMap<String, String>,
+        }
+        let mut req = TestClient::get("http://127.0.0.1:5800/search?q=rust&lang=en&sort=stars").build();
+        let data: Search = req.extract().await.unwrap();
+        assert_eq!(data.keyword, "rust");
+        assert_eq!(data.filters.len(), 2);
+        assert_eq!(data.filters["lang"], "en");
+        assert_eq!(data.filters["sort"], "stars");
+
+        #[derive(Deserialize, Extractible, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "body")))]
+        struct Update {
+            #[salvo(extract(compile_check = "required"))]
+            id: i64,
+            #[salvo(extract(source(from = "body"), flatten))]
+            rest: HashMap<String, serde_json::Value>,
+        }
+        let mut req = TestClient::post("http://127.0.0.1:5800/users")
+            .json(&serde_json::json!({"id": 1, "name": "chris", "tags": ["a", "b"]}))
+            .build();
+        let data: Update = req.extract().await.unwrap();
+        assert_eq!(data.id, 1);
+        assert_eq!(data.rest.len(), 2);
+        assert_eq!(data.rest["name"], "chris");
+        assert_eq!(data.rest["tags"], serde_json::json!(["a", "b"]));
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_flatten_typed_map() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Page {
+            #[salvo(extract(alias = "p", compile_check = "required"))]
+            page: u32,
+        }
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct Search {
+            #[salvo(extract(compile_check = "required"))]
+            keyword: String,
+            #[salvo(extract(flatten))]
+            page: Page,
+            #[salvo(extract(source(from = "query"), flatten))]
+            counts: HashMap<String, u32>,
+        }
+        let mut req = TestClient::get("http://127.0.0.1:5800/search?keyword=rust&p=2&stars=10&forks=3").build();
+        let data: Search = req.extract().await.unwrap();
+        assert_eq!(data.keyword, "rust");
+        assert_eq!(data.page, Page { page: 2 });
+        assert_eq!(
+            data.counts,
+            HashMap::from([("stars".to_owned(), 10), ("forks".to_owned(), 3)])
+        );
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/search?keyword=rust&p=2&stars=many").build();
+        assert!(req.extract::<Search>().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_serde_deserialize_with() {
+        fn comma_separated<'de, D>(deserializer: D) -> Result<Vec<i64>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let value = String::deserialize(deserializer)?;
+            value
+                .split(',')
+                .map(|v| v.trim().parse().map_err(serde::de::Error::custom))
+                .collect()
+        }
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct RequestData {
+            #[serde(deserialize_with = "comma_separated")]
+            #[salvo(extract(compile_check = "required"))]
+            ids: Vec<i64>,
+        }
+        let mut req = TestClient::get("http://127.0.0.1:5800/users?ids=1,2,3").build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(data, RequestData { ids: vec![1, 2, 3] });
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users?ids=1,a").build();
+        assert!(req.extract::<RequestData>().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_extract_deserialize_with() {
+        fn comma_separated<'de, D>(deserializer: D) -> Result<Vec<i64>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let value = String::deserialize(deserializer)?;
+            value
+                .split(',')
+                .map(|v| v.trim().parse().map_err(serde::de::Error::custom))
+                .collect()
+        }
+        #[derive(Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query")))]
+        struct RequestData {
+            #[salvo(extract(deserialize_with = "comma_separated", alias = "id", compile_check = "required"))]
+            ids: Vec<i64>,
+            #[serde(default)]
+            name: String,
+        }
+        let mut req = TestClient::get("http://127.0.0.1:5800/users?id=1,2,3").build();
+        let data = <RequestData as crate::Extractible>::extract(&mut req).await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                ids: vec![1, 2, 3],
+                name: String::new()
+            }
+        );
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users?ids=1,a").build();
+        assert!(<RequestData as crate::Extractible>::extract(&mut req).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_de_request_with_json_vec() {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "body", parse = "json")))]
         struct RequestData<'a> {
-            #[salvo(extract(source(from = "param")))]
+            #[salvo(extract(source(from = "param"), compile_check = "required"))]
             p2: &'a str,
+            #[salvo(extract(compile_check = "required"))]
             users: Vec<User>,
         }
         #[derive(Deserialize, Serialize, Eq, PartialEq, Debug)]
@@ -647,8 +1176,9 @@ mod tests {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "body", parse = "json")))]
         struct RequestData<'a> {
-            #[salvo(extract(source(from = "param")))]
+            #[salvo(extract(source(from = "param"), compile_check = "required"))]
             p2: &'a str,
+            #[salvo(extract(compile_check = "required"))]
             b: bool,
         }
 
@@ -664,8 +1194,9 @@ mod tests {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "body", parse = "json")))]
         struct RequestData<'a> {
-            #[salvo(extract(source(from = "param")))]
+            #[salvo(extract(source(from = "param"), compile_check = "required"))]
             p2: &'a str,
+            #[salvo(extract(compile_check = "required"))]
             s: &'a str,
         }
 
@@ -692,8 +1223,9 @@ mod tests {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "body", parse = "json")))]
         struct RequestData<'a> {
-            #[salvo(extract(source(from = "param")))]
+            #[salvo(extract(source(from = "param"), compile_check = "required"))]
             p2: &'a str,
+            #[salvo(extract(compile_check = "required"))]
             user: User<'a>,
         }
         let mut req = TestClient::get("http://127.0.0.1:5800/test/1234/param2v")
@@ -714,8 +1246,9 @@ mod tests {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(rename_all = "kebab-case", default_source(from = "query")))]
         struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
             full_name: String,
-            #[salvo(extract(rename = "currAge"))]
+            #[salvo(extract(rename = "currAge", compile_check = "required"))]
             curr_age: usize,
         }
         let mut req =
@@ -730,13 +1263,36 @@ mod tests {
         );
     }
     #[tokio::test]
+    async fn test_de_request_with_extract_rename_all_json_body() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(rename_all = "camelCase", default_source(from = "body", parse = "json")))]
+        struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
+            full_name: String,
+            #[salvo(extract(rename = "age", compile_check = "required"))]
+            curr_age: usize,
+        }
+        let mut req = TestClient::post("http://127.0.0.1:5800/users")
+            .json(&serde_json::json!({"fullName": "chris young", "age": 20}))
+            .build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                full_name: "chris young".into(),
+                curr_age: 20
+            }
+        );
+    }
+    #[tokio::test]
     async fn test_de_request_with_serde_rename_all() {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
         #[salvo(extract(default_source(from = "query")))]
         #[serde(rename_all = "kebab-case")]
         struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
             full_name: String,
-            #[salvo(extract(rename = "currAge"))]
+            #[salvo(extract(rename = "currAge", compile_check = "required"))]
             curr_age: usize,
         }
         let mut req =
@@ -756,8 +1312,9 @@ mod tests {
         #[salvo(extract(rename_all = "kebab-case", default_source(from = "query")))]
         #[serde(rename_all = "camelCase")]
         struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
             full_name: String,
-            #[salvo(extract(rename = "currAge"))]
+            #[salvo(extract(rename = "currAge", compile_check = "required"))]
             curr_age: usize,
         }
         let mut req =
@@ -771,4 +1328,155 @@ mod tests {
             }
         );
     }
+
+    #[tokio::test]
+    async fn test_de_request_with_secret() {
+        #[derive(Deserialize, Extractible)]
+        #[salvo(extract(default_source(from = "query"), debug))]
+        struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
+            username: String,
+            #[salvo(extract(secret, compile_check = "required"))]
+            password: String,
+        }
+        let mut req = TestClient::get("http://127.0.0.1:5800/login")
+            .query("username", "jobs")
+            .query("password", "p@ssw0rd")
+            .build();
+        let data: RequestData = req.extract().await.unwrap();
+        assert_eq!(data.password, "p@ssw0rd");
+        assert_eq!(
+            format!("{:?}", data),
+            r#"RequestData { username: "jobs", password: "***" }"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_json_body_delegated() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "body", parse = "json")))]
+        #[serde(deny_unknown_fields)]
+        struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
+            name: String,
+            #[serde(default)]
+            age: u8,
+        }
+        let mut req = TestClient::post("http://127.0.0.1:5800/users")
+            .raw_json(r#"{"name": "jobs"}"#)
+            .build();
+        let data = <RequestData as crate::extract::Extractible>::extract(&mut req)
+            .await
+            .unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                name: "jobs".into(),
+                age: 0
+            }
+        );
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/users")
+            .raw_json(r#"{"name": "jobs", "unknown": 1}"#)
+            .build();
+        assert!(<RequestData as crate::extract::Extractible>::extract(&mut req)
+            .await
+            .is_err());
+    }
+
+    #[cfg(feature = "serde-qs")]
+    #[tokio::test]
+    async fn test_de_request_with_qs_query() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Filter {
+            name: String,
+            age: u8,
+        }
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        #[salvo(extract(default_source(from = "query", parse = "qs")))]
+        struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
+            filter: Filter,
+            #[salvo(extract(compile_check = "required"))]
+            ids: Vec<u64>,
+        }
+        let mut req =
+            TestClient::get("http://127.0.0.1:5800/users?filter[name]=jobs&filter[age]=30&ids%5B%5D=1&ids%5B%5D=2")
+                .build();
+        let data = <RequestData as crate::extract::Extractible>::extract(&mut req)
+            .await
+            .unwrap();
+        assert_eq!(
+            data,
+            RequestData {
+                filter: Filter {
+                    name: "jobs".into(),
+                    age: 30
+                },
+                ids: vec![1, 2]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_on_error() {
+        use crate::http::{ParseError, StatusCode, StatusError};
+        use crate::prelude::*;
+
+        fn unprocessable(e: ParseError) -> StatusError {
+            StatusError::unprocessable_entity().cause(e)
+        }
+        #[derive(Deserialize, Extractible, Debug)]
+        #[salvo(extract(default_source(from = "query"), on_error = "unprocessable"))]
+        struct RequestData {
+            #[salvo(extract(compile_check = "required"))]
+            id: i64,
+        }
+        #[handler]
+        async fn show(data: RequestData) -> String {
+            data.id.to_string()
+        }
+
+        let service = Service::new(Router::new().get(show));
+        let res = TestClient::get("http://127.0.0.1:5800/?id=1").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let res = TestClient::get("http://127.0.0.1:5800/?id=abc").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    #[tokio::test]
+    async fn test_de_request_with_tuple_struct() {
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        struct UserId(#[salvo(extract(source(from = "param"), rename = "id", compile_check = "required"))] u64);
+
+        #[derive(Deserialize, Extractible, Eq, PartialEq)]
+        #[salvo(extract(default_source(from = "query"), debug))]
+        struct Page(
+            #[salvo(extract(compile_check = "required"))]
+            #[salvo(extract(rename = "page"))]
+            u32,
+            #[salvo(extract(rename = "size"))] Option<u32>,
+            #[salvo(extract(compile_check = "required"))]
+            #[salvo(extract(source(from = "header"), rename = "x-token", secret))]
+            String,
+        );
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users")
+            .query("page", "2")
+            .add_header("x-token", "abc", true)
+            .build();
+        req.params_mut().insert("id".into(), "42".into());
+        let data: UserId = req.extract().await.unwrap();
+        assert_eq!(data, UserId(42));
+        let data = <UserId as crate::extract::Extractible>::extract(&mut req)
+            .await
+            .unwrap();
+        assert_eq!(data, UserId(42));
+        let data: Page = req.extract().await.unwrap();
+        assert_eq!(data, Page(2, None, "abc".into()));
+        assert_eq!(format!("{data:?}"), r#"Page(2, None, "***")"#);
+
+        let mut req = TestClient::get("http://127.0.0.1:5800/users").build();
+        assert!(req.extract::<UserId>().await.is_err());
+    }
 }
@@ -12,24 +12,41 @@ compile_error!(
 use hyper::server::conn::http1;
 #[cfg(feature = "http2")]
 use hyper::server::conn::http2;
+#[cfg(feature = "advanced")]
+use once_cell::sync::OnceCell;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use tokio::sync::Notify;
+use tokio::sync::{Notify, Semaphore};
 use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
 
 #[cfg(feature = "quinn")]
 use crate::conn::quinn;
+#[cfg(feature = "advanced")]
+use crate::conn::{SocketAddr, TlsClientCertSlot};
+#[cfg(all(feature = "http1", feature = "http2"))]
+use crate::conn::ProtocolPriority;
 use crate::conn::{Accepted, Acceptor, Holding, HttpBuilder};
-use crate::http::{HeaderValue, HttpConnection, Version};
-use crate::Service;
+use crate::http::header::CONNECTION;
+#[cfg(feature = "advanced")]
+use crate::http::uri::Scheme;
+use crate::http::{HeaderValue, HttpConnection, Request, Response, StatusError, Version};
+use crate::{async_trait, Depot, FlowCtrl, Handler, Router, Service};
 
 /// Server handle is used to stop server.
 #[derive(Clone)]
 pub struct ServerHandle {
     tx_cmd: UnboundedSender<ServerCommand>,
+    alive_connections: Arc<AtomicUsize>,
+    notify: Arc<Notify>,
 }
 
 impl ServerHandle {
+    /// Get the count of active connections.
+    #[inline]
+    pub fn active_connections(&self) -> usize {
+        self.alive_connections.load(Ordering::Acquire)
+    }
+
     /// Force stop server.
     ///
     /// Call this function will stop server immediately.
@@ -72,6 +89,91 @@ impl ServerHandle {
     pub fn stop_graceful(&self, timeout: impl Into<Option<Duration>>) {
         self.tx_cmd.send(ServerCommand::StopGraceful(timeout.into())).ok();
     }
+
+    /// Graceful stop server and wait until all connections are closed.
+    ///
+    /// The server stops accepting new connections immediately and in-flight requests are allowed
+    /// to complete. Connections still open after `timeout` are closed forcibly.
+    ///
+    /// # Examples
+    ///
+    /// Stop server when receiving `Ctrl+C` or `SIGTERM`, this requires `signal` feature of `tokio`.
+    ///
+    /// ```ignore
+    /// use std::time::Duration;
+    ///
+    /// use salvo_core::prelude::*;
+    /// use salvo_core::server::ServerHandle;
+    /// use tokio::signal;
+    ///
+    /// async fn shutdown_signal(handle: ServerHandle) {
+    ///     let ctrl_c = async {
+    ///         signal::ctrl_c().await.expect("failed to install Ctrl+C handler");
+    ///     };
+    ///     #[cfg(unix)]
+    ///     let terminate = async {
+    ///         signal::unix::signal(signal::unix::SignalKind::terminate())
+    ///             .expect("failed to install signal handler")
+    ///             .recv()
+    ///             .await;
+    ///     };
+    ///     #[cfg(not(unix))]
+    ///     let terminate = std::future::pending::<()>();
+    ///     tokio::select! {
+    ///         _ = ctrl_c => {},
+    ///         _ = terminate => {},
+    ///     }
+    ///     handle.graceful_stop(Duration::from_secs(30)).await;
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let acceptor = TcpListener::new("127.0.0.1:5800").bind().await;
+    ///     let server = Server::new(acceptor);
+    ///     tokio::spawn(shutdown_signal(server.handle()));
+    ///     server.serve(Router::new()).await;
+    /// }
+    /// ```
+    pub async fn graceful_stop(self, timeout: Duration) {
+        self.stop_graceful(timeout);
+        wait_connections_closed(&self.alive_connections, &self.notify).await;
+    }
+}
+
+/// Wait until the count of alive connections becomes zero.
+async fn wait_connections_closed(alive_connections: &AtomicUsize, notify: &Notify) {
+    loop {
+        let notified = notify.notified();
+        tokio::pin!(notified);
+        // Register before checking the count, so notification from the last connection is not missed.
+        notified.as_mut().enable();
+        if alive_connections.load(Ordering::Acquire) == 0 {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Tracks an alive connection, the count is decremented when it is dropped.
+struct ConnGuard {
+    alive_connections: Arc<AtomicUsize>,
+    notify: Arc<Notify>,
+}
+impl ConnGuard {
+    fn new(alive_connections: Arc<AtomicUsize>, notify: Arc<Notify>) -> Self {
+        alive_connections.fetch_add(1, Ordering::Release);
+        Self {
+            alive_connections,
+            notify,
+        }
+    }
+}
+impl Drop for ConnGuard {
+    fn drop(&mut self) {
+        if self.alive_connections.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.notify.notify_waiters();
+        }
+    }
 }
 
 enum ServerCommand {
@@ -79,17 +181,85 @@ enum ServerCommand {
     StopGraceful(Option<Duration>),
 }
 
+cfg_feature! {
+    #![feature = "advanced"]
+    /// Information of a connection, it is used to create per-connection state by [`Server::with_make_service`].
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    pub struct ConnectInfo {
+        /// Remote address of the peer.
+        pub peer_addr: SocketAddr,
+        /// Whether the connection is secured by TLS.
+        pub is_tls: bool,
+        /// DER encoded certificate presented by the TLS client, it is `None` if the connection is not secured
+        /// by TLS or the client does not present a certificate.
+        pub tls_client_cert: Option<Vec<u8>>,
+    }
+
+    pub(crate) type DepotInjector = Arc<dyn Fn(&mut Depot) + Send + Sync>;
+    type MakeService = Arc<dyn Fn(ConnectInfo) -> DepotInjector + Send + Sync>;
+
+    /// State of a connection, it is created by the first request, after the TLS handshake is finished.
+    pub(crate) struct ConnState {
+        make_service: MakeService,
+        peer_addr: SocketAddr,
+        is_tls: bool,
+        tls_client_cert: TlsClientCertSlot,
+        injector: OnceCell<DepotInjector>,
+    }
+    impl ConnState {
+        pub(crate) fn inject(&self, depot: &mut Depot) {
+            let injector = self.injector.get_or_init(|| {
+                (self.make_service)(ConnectInfo {
+                    peer_addr: self.peer_addr.clone(),
+                    is_tls: self.is_tls,
+                    tls_client_cert: self.tls_client_cert.get(),
+                })
+            });
+            injector(depot);
+        }
+    }
+}
+
 /// HTTP Server
 ///
 /// A `Server` is created to listen on a port, parse HTTP requests, and hand them off to a [`Service`].
+///
+/// When both `http1` and `http2` features are enabled, plain-text connections are served as HTTP/2 if they
+/// start with the HTTP/2 connection preface (h2c with prior knowledge), otherwise as HTTP/1.1, so the same
+/// router serves both kinds of clients, this can be changed by [`Server::protocol_priority`]. The
+/// `Upgrade: h2c` mechanism is deprecated by [RFC 9113](https://www.rfc-editor.org/rfc/rfc9113#section-3.1),
+/// so it is disabled by default and such requests are answered with HTTP/1.1, use [`Server::with_h2c`] to
+/// enable it.
 pub struct Server<A> {
     acceptor: A,
     builder: HttpBuilder,
     conn_idle_timeout: Option<Duration>,
+    max_connections: Option<usize>,
+    alive_connections: Arc<AtomicUsize>,
+    notify: Arc<Notify>,
+    #[cfg(feature = "advanced")]
+    make_service: Option<MakeService>,
     tx_cmd: UnboundedSender<ServerCommand>,
     rx_cmd: UnboundedReceiver<ServerCommand>,
 }
 
+/// Idle timeout of connections exceeding `Server::with_max_connections`.
+const UNAVAILABLE_IDLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Handler used to respond connections exceeding `Server::with_max_connections`.
+struct ServiceUnavailable;
+#[async_trait]
+impl Handler for ServiceUnavailable {
+    async fn handle(&self, req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
+        // `Connection` is a connection-specific header, which is forbidden in HTTP/2 and HTTP/3.
+        if req.version() <= Version::HTTP_11 {
+            res.headers_mut().insert(CONNECTION, HeaderValue::from_static("close"));
+        }
+        res.render(StatusError::service_unavailable());
+    }
+}
+
 impl<A: Acceptor + Send> Server<A> {
     /// Create new `Server` with [`Acceptor`].
     ///
@@ -114,6 +284,10 @@ impl<A: Acceptor + Send> Server<A> {
                 http2: http2::Builder::new(crate::rt::tokio::TokioExecutor::new()),
                 #[cfg(feature = "quinn")]
                 quinn: crate::conn::quinn::Builder::new(),
+                #[cfg(all(feature = "http1", feature = "http2"))]
+                h2c: false,
+                #[cfg(all(feature = "http1", feature = "http2"))]
+                protocol_priority: ProtocolPriority::default(),
             },
         )
     }
@@ -125,6 +299,11 @@ impl<A: Acceptor + Send> Server<A> {
             acceptor,
             builder,
             conn_idle_timeout: None,
+            max_connections: None,
+            alive_connections: Arc::new(AtomicUsize::new(0)),
+            notify: Arc::new(Notify::new()),
+            #[cfg(feature = "advanced")]
+            make_service: None,
             tx_cmd,
             rx_cmd,
         }
@@ -134,9 +313,17 @@ impl<A: Acceptor + Send> Server<A> {
     pub fn handle(&self) -> ServerHandle {
         ServerHandle {
             tx_cmd: self.tx_cmd.clone(),
+            alive_connections: self.alive_connections.clone(),
+            notify: self.notify.clone(),
         }
     }
 
+    /// Get the count of active connections.
+    #[inline]
+    pub fn active_connections(&self) -> usize {
+        self.alive_connections.load(Ordering::Acquire)
+    }
+
     /// Force stop server.
     ///
     /// Call this function will stop server immediately.
@@ -191,6 +378,80 @@ impl<A: Acceptor + Send> Server<A> {
         self
     }
 
+    /// Specify max count of concurrently served connections, it is used to avoid exhausting file descriptors.
+    ///
+    /// When the limit is reached, new connections are responded with `503 Service Unavailable`
+    /// and closed immediately instead of being queued.
+    #[must_use]
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = Some(max_connections);
+        self
+    }
+
+    cfg_feature! {
+        #![all(feature = "http1", feature = "http2")]
+        /// Enable the `Upgrade: h2c` mechanism on plain-text connections.
+        ///
+        /// HTTP/1.1 requests with `Upgrade: h2c` and `Connection: Upgrade, HTTP2-Settings` headers are answered
+        /// with `101 Switching Protocols`, then the connection is served as HTTP/2, and the request is answered on
+        /// HTTP/2 stream 1. Upgrades of requests whose header block exceeds 16 KiB or whose body exceeds 64 KiB
+        /// are ignored, such requests are answered with HTTP/1.1.
+        #[must_use]
+        pub fn with_h2c(mut self) -> Self {
+            self.builder.h2c = true;
+            self
+        }
+
+        /// Specify which protocol is preferred on plain-text connections, see [`ProtocolPriority`].
+        #[must_use]
+        pub fn protocol_priority(mut self, priority: ProtocolPriority) -> Self {
+            self.builder.protocol_priority = priority;
+            self
+        }
+    }
+
+    cfg_feature! {
+        #![feature = "advanced"]
+        /// Specify a factory to create state for each connection.
+        ///
+        /// The state is created once per connection with its [`ConnectInfo`], after the TLS handshake is
+        /// finished, so the client certificate of mTLS is available. It is injected into [`Depot`] of every
+        /// request on this connection, and can be obtained by `depot.obtain::<T>()`.
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use salvo_core::prelude::*;
+        /// use salvo_core::server::ConnectInfo;
+        ///
+        /// #[derive(Clone)]
+        /// struct ClientCert(Option<Vec<u8>>);
+        ///
+        /// #[tokio::main]
+        /// async fn main() {
+        ///     let acceptor = TcpListener::new("127.0.0.1:5800").bind().await;
+        ///     Server::new(acceptor)
+        ///         .with_make_service(|info: ConnectInfo| ClientCert(info.tls_client_cert))
+        ///         .serve(Router::new())
+        ///         .await;
+        /// }
+        /// ```
+        #[must_use]
+        pub fn with_make_service<F, T>(mut self, factory: F) -> Self
+        where
+            F: Fn(ConnectInfo) -> T + Send + Sync + 'static,
+            T: Clone + Send + Sync + 'static,
+        {
+            self.make_service = Some(Arc::new(move |info| {
+                let state = factory(info);
+                Arc::new(move |depot: &mut Depot| {
+                    depot.inject(state.clone());
+                })
+            }));
+            self
+        }
+    }
+
     /// Serve a [`Service`].
     ///
     /// # Example
@@ -227,11 +488,35 @@ impl<A: Acceptor + Send> Server<A> {
             mut acceptor,
             builder,
             conn_idle_timeout,
+            max_connections,
+            alive_connections,
+            notify,
+            #[cfg(feature = "advanced")]
+            make_service,
             mut rx_cmd,
             ..
         } = self;
-        let alive_connections = Arc::new(AtomicUsize::new(0));
-        let notify = Arc::new(Notify::new());
+        let semaphore = max_connections.map(|max| Arc::new(Semaphore::new(max)));
+        let unavailable_service = Arc::new(Service::new(Router::new().goal(ServiceUnavailable)));
+        // Connections exceeding the limit are served once without keep-alive, and closed soon if the
+        // protocol can not disable keep-alive.
+        let unavailable_builder = Arc::new(HttpBuilder {
+            #[cfg(feature = "http1")]
+            http1: {
+                let mut http1 = builder.http1.clone();
+                http1.keep_alive(false);
+                http1
+            },
+            #[cfg(feature = "http2")]
+            http2: builder.http2.clone(),
+            #[cfg(feature = "quinn")]
+            quinn: crate::conn::quinn::Builder::new(),
+            // The upgraded request would be served by the limited service, so answer it with HTTP/1.1.
+            #[cfg(all(feature = "http1", feature = "http2"))]
+            h2c: false,
+            #[cfg(all(feature = "http1", feature = "http2"))]
+            protocol_priority: builder.protocol_priority,
+        });
         let timeout_token = CancellationToken::new();
 
         let mut alt_svc_h3 = None;
@@ -280,19 +565,39 @@ impl<A: Acceptor + Send> Server<A> {
                 },
                 accepted = acceptor.accept() => {
                     match accepted {
-                        Ok(Accepted { conn, local_addr, remote_addr, http_scheme, ..}) => {
-                            alive_connections.fetch_add(1, Ordering::Release);
+                        Ok(accepted) => {
+                            let Accepted { conn, local_addr, remote_addr, http_scheme, .. } = accepted;
+                            let guard = ConnGuard::new(alive_connections.clone(), notify.clone());
 
-                            let service = service.clone();
-                            let alive_connections = alive_connections.clone();
-                            let notify = notify.clone();
-                            let handler = service.hyper_handler(local_addr, remote_addr, http_scheme, alt_svc_h3.clone());
-                            let builder = builder.clone();
+                            let (permit, service, builder, idle_timeout) = match &semaphore {
+                                Some(semaphore) => match semaphore.clone().try_acquire_owned() {
+                                    Ok(permit) => (Some(permit), service.clone(), builder.clone(), conn_idle_timeout),
+                                    Err(_) => {
+                                        tracing::warn!(%remote_addr, "max connections reached, respond service unavailable");
+                                        (None, unavailable_service.clone(), unavailable_builder.clone(), Some(UNAVAILABLE_IDLE_TIMEOUT))
+                                    }
+                                },
+                                None => (None, service.clone(), builder.clone(), conn_idle_timeout),
+                            };
+                            #[cfg(feature = "advanced")]
+                            let conn_state = make_service.as_ref().map(|make_service| Arc::new(ConnState {
+                                make_service: make_service.clone(),
+                                peer_addr: remote_addr.clone(),
+                                is_tls: http_scheme == Scheme::HTTPS,
+                                tls_client_cert: accepted.tls_client_cert,
+                                injector: OnceCell::new(),
+                            }));
+                            #[cfg_attr(not(feature = "advanced"), allow(unused_mut))]
+                            let mut handler = service.hyper_handler(local_addr, remote_addr, http_scheme, alt_svc_h3.clone());
+                            #[cfg(feature = "advanced")]
+                            {
+                                handler.conn_state = conn_state;
+                            }
 
                             let timeout_token = timeout_token.clone();
 
                             tokio::spawn(async move {
-                                let conn = conn.serve(handler, builder, conn_idle_timeout);
+                                let conn = conn.serve(handler, builder, idle_timeout);
                                 tokio::select! {
                                     _ = conn => {
                                     },
@@ -300,9 +605,8 @@ impl<A: Acceptor + Send> Server<A> {
                                     }
                                 }
 
-                                if alive_connections.fetch_sub(1, Ordering::Acquire) == 1 {
-                                    notify.notify_waiters();
-                                }
+                                drop(permit);
+                                drop(guard);
                             });
                         },
                         Err(e) => {
@@ -313,9 +617,11 @@ impl<A: Acceptor + Send> Server<A> {
             }
         }
 
+        // Stop listening, so new connections are refused.
+        drop(acceptor);
         if alive_connections.load(Ordering::Acquire) > 0 {
             tracing::info!("wait for all connections to close.");
-            notify.notified().await;
+            wait_connections_closed(&alive_connections, &notify).await;
         }
 
         tracing::info!("server stopped");
@@ -325,11 +631,167 @@ impl<A: Acceptor + Send> Server<A> {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "advanced")]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "advanced")]
+    use std::sync::Arc;
+
     use serde::Serialize;
 
+    #[cfg(feature = "advanced")]
+    use super::ConnectInfo;
     use crate::prelude::*;
     use crate::test::{ResponseExt, TestClient};
 
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    #[tokio::test]
+    async fn test_serve_h2c_and_http1() {
+        use bytes::Bytes;
+        use http_body_util::{BodyExt, Empty};
+        use hyper::client::conn::{http1, http2};
+        use tokio::net::TcpStream;
+
+        use crate::rt::tokio::{TokioExecutor, TokioIo};
+
+        #[handler]
+        async fn version(req: &mut Request) -> String {
+            format!("{:?}", req.version())
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let server = Server::new(acceptor);
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::new().get(version)));
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) = http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(conn);
+        let req = hyper::Request::get(format!("http://{addr}/"))
+            .body(Empty::<Bytes>::new())
+            .unwrap();
+        let res = sender.send_request(req).await.unwrap();
+        assert_eq!(res.version(), http::Version::HTTP_2);
+        assert_eq!(res.into_body().collect().await.unwrap().to_bytes(), "HTTP/2.0");
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) = http1::handshake(TokioIo::new(stream)).await.unwrap();
+        tokio::spawn(conn);
+        let req = hyper::Request::get("/")
+            .header("host", addr.to_string())
+            .header("connection", "Upgrade, HTTP2-Settings")
+            .header("upgrade", "h2c")
+            .header("http2-settings", "AAMAAABkAARAAAAAAAIAAAAA")
+            .body(Empty::<Bytes>::new())
+            .unwrap();
+        let res = sender.send_request(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.into_body().collect().await.unwrap().to_bytes(), "HTTP/1.1");
+
+        handle.stop_forcible();
+    }
+
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    #[tokio::test]
+    async fn test_serve_h2c_upgrade() {
+        use bytes::Bytes;
+        use http_body_util::Empty;
+        use hyper::client::conn::http2;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        use crate::conn::ProtocolPriority;
+        use crate::rt::tokio::{TokioExecutor, TokioIo};
+
+        #[handler]
+        async fn version(req: &mut Request) -> String {
+            let body = req.payload_with_max_size(1 << 20).await.unwrap().clone();
+            format!("{:?} {}", req.version(), String::from_utf8(body.to_vec()).unwrap())
+        }
+
+        /// Send an upgrade request, returns the body of stream 1 if it is answered with HTTP/2.
+        async fn upgrade(addr: std::net::SocketAddr, method: &str, body: &str) -> Option<String> {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            let req = format!(
+                "{method} /?q=1 HTTP/1.1\r\nHost: {addr}\r\nConnection: Upgrade, HTTP2-Settings\r\n\
+                Upgrade: h2c\r\nHTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(req.as_bytes()).await.unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                head.push(stream.read_u8().await.unwrap());
+            }
+            let head = String::from_utf8(head).unwrap();
+            if !head.starts_with("HTTP/1.1 101") {
+                return None;
+            }
+            assert!(head.to_ascii_lowercase().contains("upgrade: h2c"));
+
+            stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n").await.unwrap();
+            stream.write_all(&[0, 0, 0, 4, 0, 0, 0, 0, 0]).await.unwrap();
+            let mut status = None;
+            let mut data = Vec::new();
+            loop {
+                let mut frame = [0; 9];
+                stream.read_exact(&mut frame).await.unwrap();
+                let len = usize::from(frame[0]) << 16 | usize::from(frame[1]) << 8 | usize::from(frame[2]);
+                let mut payload = vec![0; len];
+                stream.read_exact(&mut payload).await.unwrap();
+                let stream_id = u32::from_be_bytes([frame[5], frame[6], frame[7], frame[8]]);
+                match (frame[3], stream_id) {
+                    // HEADERS of stream 1, the first byte is the indexed `:status: 200`.
+                    (0x1, 1) => status = payload.first().copied(),
+                    (0x0, 1) => {
+                        data.extend_from_slice(&payload);
+                        if frame[4] & 0x1 != 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(status, Some(0x88));
+            Some(String::from_utf8(data).unwrap())
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let server = Server::new(acceptor).with_h2c();
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::new().get(version).post(version)));
+
+        assert_eq!(upgrade(addr, "GET", "").await.unwrap(), "HTTP/2.0 ");
+        let body = "a".repeat(20_000);
+        assert_eq!(upgrade(addr, "POST", &body).await.unwrap(), format!("HTTP/2.0 {body}"));
+        // Too large body to be replayed, answered with HTTP/1.1.
+        assert!(upgrade(addr, "POST", &"a".repeat(70_000)).await.is_none());
+        handle.stop_forcible();
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let server = Server::new(acceptor)
+            .with_h2c()
+            .protocol_priority(ProtocolPriority::Http1);
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::new().get(version)));
+
+        assert_eq!(upgrade(addr, "GET", "").await.unwrap(), "HTTP/2.0 ");
+        // Prior knowledge is not detected when HTTP/1.1 is preferred.
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) = http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(conn);
+        let req = hyper::Request::get(format!("http://{addr}/"))
+            .body(Empty::<Bytes>::new())
+            .unwrap();
+        assert!(sender.send_request(req).await.is_err());
+        handle.stop_forcible();
+    }
+
     #[tokio::test]
     async fn test_server() {
         #[handler]
@@ -396,4 +858,196 @@ mod tests {
             .unwrap();
         assert!(result.contains("<code>404</code>"));
     }
+
+    #[tokio::test]
+    async fn test_max_connections() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        #[handler]
+        async fn hello() -> &'static str {
+            "Hello World"
+        }
+
+        async fn request(stream: &mut TcpStream) -> String {
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n")
+                .await
+                .unwrap();
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let server = Server::new(acceptor).with_max_connections(1);
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::new().get(hello)));
+
+        let mut stream0 = TcpStream::connect(addr).await.unwrap();
+        assert!(request(&mut stream0).await.starts_with("HTTP/1.1 200"));
+        let mut stream1 = TcpStream::connect(addr).await.unwrap();
+        assert!(request(&mut stream1).await.starts_with("HTTP/1.1 503"));
+        // The connection is closed after the response.
+        let mut rest = Vec::new();
+        let closed = tokio::time::timeout(std::time::Duration::from_secs(3), stream1.read_to_end(&mut rest)).await;
+        assert!(closed.is_ok());
+        assert!(handle.active_connections() >= 1);
+
+        drop(stream0);
+        drop(stream1);
+        handle.stop_forcible();
+    }
+
+    #[cfg(feature = "advanced")]
+    #[tokio::test]
+    async fn test_make_service() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        #[derive(Clone)]
+        struct ConnId(usize);
+
+        #[handler]
+        async fn conn_id(depot: &mut Depot) -> String {
+            depot.obtain::<ConnId>().map(|id| id.0.to_string()).unwrap_or_default()
+        }
+
+        async fn request(stream: &mut TcpStream) -> String {
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n")
+                .await
+                .unwrap();
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        }
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let server = Server::new(acceptor).with_make_service(move |info: ConnectInfo| {
+            assert!(!info.is_tls);
+            assert!(info.tls_client_cert.is_none());
+            ConnId(counter.fetch_add(1, Ordering::Relaxed))
+        });
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::new().get(conn_id)));
+
+        let mut stream0 = TcpStream::connect(addr).await.unwrap();
+        assert!(request(&mut stream0).await.ends_with("\r\n\r\n0"));
+        assert!(request(&mut stream0).await.ends_with("\r\n\r\n0"));
+        let mut stream1 = TcpStream::connect(addr).await.unwrap();
+        assert!(request(&mut stream1).await.ends_with("\r\n\r\n1"));
+
+        drop(stream0);
+        drop(stream1);
+        handle.stop_forcible();
+    }
+
+    #[cfg(all(feature = "advanced", feature = "rustls"))]
+    #[tokio::test]
+    async fn test_make_service_tls_client_cert() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+        use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+        use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+        use tokio_rustls::TlsConnector;
+
+        use crate::conn::rustls::{Keycert, RustlsConfig};
+
+        #[derive(Clone)]
+        struct ClientCert(Option<Vec<u8>>);
+
+        #[handler]
+        async fn client_cert(depot: &mut Depot, res: &mut Response) {
+            let cert = depot.obtain::<ClientCert>().unwrap();
+            res.write_body(cert.0.clone().unwrap_or_default()).unwrap();
+        }
+
+        let cert_pem = include_bytes!("../certs/cert.pem").as_slice();
+        let key_pem = include_bytes!("../certs/key.pem").as_slice();
+        let chain_pem = include_bytes!("../certs/chain.pem").as_slice();
+        let config = RustlsConfig::new(Keycert::new().key(key_pem).cert(cert_pem)).client_auth_optional(chain_pem);
+        let acceptor = TcpListener::new("127.0.0.1:0").rustls(config).bind().await;
+        let server = Server::new(acceptor).with_make_service(|info: ConnectInfo| {
+            assert!(info.is_tls);
+            ClientCert(info.tls_client_cert)
+        });
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::new().get(client_cert)));
+
+        let certs: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut &*cert_pem)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let key = rustls_pemfile::private_key(&mut &*key_pem).unwrap().unwrap();
+        let mut roots = RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut &*chain_pem) {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let client_config = ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_client_auth_cert(certs.clone(), PrivateKeyDer::from(key))
+            .unwrap();
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut stream = TlsConnector::from(Arc::new(client_config))
+            .connect(ServerName::try_from("testserver.com").unwrap(), stream)
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: testserver.com\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = Vec::new();
+        let _ = stream.read_to_end(&mut buf).await;
+        assert!(buf.ends_with(certs[0].as_ref()));
+
+        handle.stop_forcible();
+    }
+
+    #[tokio::test]
+    async fn test_graceful_stop() {
+        use std::time::{Duration, Instant};
+
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        #[handler]
+        async fn slow() -> &'static str {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            "done"
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let server = Server::new(acceptor);
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        let serving = tokio::spawn(server.serve(Router::new().get(slow)));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let idle = TcpStream::connect(addr).await.unwrap();
+        while handle.active_connections() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let started = Instant::now();
+        let stopping = tokio::spawn(handle.clone().graceful_stop(Duration::from_millis(500)));
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf).ends_with("done"));
+
+        // The idle connection is closed forcibly after timeout.
+        stopping.await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(500));
+        assert_eq!(handle.active_connections(), 0);
+        serving.await.unwrap();
+        assert!(TcpStream::connect(addr).await.is_err());
+        drop(idle);
+    }
 }
@@ -3,7 +3,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use headers::HeaderValue;
-use http::header::{ALT_SVC, CONTENT_TYPE};
+use http::header::{HeaderName, ALT_SVC, CONTENT_TYPE};
 use http::uri::Scheme;
 use hyper::service::Service as HyperService;
 use hyper::{Method, Request as HyperRequest, Response as HyperResponse};
@@ -27,8 +27,12 @@ pub struct Service {
     pub hoops: Vec<Arc<dyn Handler>>,
     /// The allowed media types of this service.
     pub allowed_media_types: Arc<Vec<Mime>>,
+    /// The value of `X-Powered-By` header, `None` means the header will not be added.
+    pub x_powered_by: Option<HeaderValue>,
 }
 
+const X_POWERED_BY: HeaderName = HeaderName::from_static("x-powered-by");
+
 impl Service {
     /// Create a new Service with a [`Router`].
     #[inline]
@@ -41,6 +45,7 @@ impl Service {
             catcher: None,
             hoops: vec![],
             allowed_media_types: Arc::new(vec![]),
+            x_powered_by: Some(HeaderValue::from_static("Salvo")),
         }
     }
 
@@ -119,6 +124,31 @@ impl Service {
         self
     }
 
+    /// Sets the value of `X-Powered-By` header and returns `Self` for write code chained.
+    ///
+    /// The header is added after all handlers are executed if it is not already set.
+    /// Default value is `Some("Salvo")`, set it to `None` to omit the header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use salvo_core::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let service = Service::new(Router::new()).x_powered_by(None);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn x_powered_by(mut self, value: Option<&str>) -> Self {
+        self.x_powered_by = value.map(|v| v.parse().expect("invalid `X-Powered-By` header value"));
+        self
+    }
+
     #[doc(hidden)]
     #[inline]
     pub fn hyper_handler(
@@ -137,6 +167,9 @@ impl Service {
             hoops: self.hoops.clone(),
             allowed_media_types: self.allowed_media_types.clone(),
             alt_svc_h3,
+            x_powered_by: self.x_powered_by.clone(),
+            #[cfg(all(feature = "server", feature = "advanced"))]
+            conn_state: None,
         }
     }
     /// Handle new request, this function only used for test.
@@ -176,45 +209,82 @@ pub struct HyperHandler {
     pub(crate) hoops: Vec<Arc<dyn Handler>>,
     pub(crate) allowed_media_types: Arc<Vec<Mime>>,
     pub(crate) alt_svc_h3: Option<HeaderValue>,
+    pub(crate) x_powered_by: Option<HeaderValue>,
+    #[cfg(all(feature = "server", feature = "advanced"))]
+    pub(crate) conn_state: Option<Arc<crate::server::ConnState>>,
 }
 impl HyperHandler {
     /// Handle [`Request`] and returns [`Response`].
     pub fn handle(&self, mut req: Request) -> impl Future<Output = Response> {
         let catcher = self.catcher.clone();
         let allowed_media_types = self.allowed_media_types.clone();
+        let x_powered_by = self.x_powered_by.clone();
         req.local_addr = self.local_addr.clone();
         req.remote_addr = self.remote_addr.clone();
         #[cfg(not(feature = "cookie"))]
         let mut res = Response::new();
         #[cfg(feature = "cookie")]
         let mut res = Response::with_cookies(req.cookies.clone());
+        *res.version_mut() = req.version();
         if let Some(alt_svc_h3) = &self.alt_svc_h3 {
             if !res.headers().contains_key(ALT_SVC) {
                 res.headers_mut().insert(ALT_SVC, alt_svc_h3.clone());
             }
         }
         let mut depot = Depot::new();
+        #[cfg(all(feature = "server", feature = "advanced"))]
+        if let Some(conn_state) = &self.conn_state {
+            conn_state.inject(&mut depot);
+        }
         let mut path_state = PathState::new(req.uri().path());
         let router = self.router.clone();
 
         let hoops = self.hoops.clone();
         async move {
-            if let Some(dm) = router.detect(&mut req, &mut path_state) {
+            let mut matched = router.detect(&mut req, &depot, &mut path_state).await;
+            if matched.is_none() && path_state.head_fallback && req.method() == Method::HEAD {
+                *req.method_mut() = Method::GET;
+                let mut get_state = PathState::new(req.uri().path());
+                matched = router.detect(&mut req, &depot, &mut get_state).await;
+                *req.method_mut() = Method::HEAD;
+                if matched.is_some() {
+                    path_state = get_state;
+                }
+            }
+            if let Some(dm) = matched {
                 req.params = path_state.params;
                 let mut ctrl = FlowCtrl::new([&hoops[..], &dm.hoops[..], &[dm.goal]].concat());
                 ctrl.call_next(&mut req, &mut depot, &mut res).await;
                 if res.status_code.is_none() {
                     res.status_code = Some(StatusCode::OK);
                 }
-            } else if !hoops.is_empty() {
+            } else {
+                let allowed = std::mem::take(&mut path_state.allowed_methods);
+                let fallback = path_state.missed_fallback.take().unwrap_or_default();
+                let auto_options = fallback.auto_options && !allowed.is_empty() && req.method() == Method::OPTIONS;
                 req.params = path_state.params;
-                let mut ctrl = FlowCtrl::new(hoops);
-                ctrl.call_next(&mut req, &mut depot, &mut res).await;
+                let mut handlers = hoops;
+                if !allowed.is_empty() && !auto_options {
+                    if let Some(handler) = fallback.handler {
+                        res.allow(&allowed);
+                        handlers.push(handler);
+                    }
+                }
+                if !handlers.is_empty() {
+                    let mut ctrl = FlowCtrl::new(handlers);
+                    ctrl.call_next(&mut req, &mut depot, &mut res).await;
+                }
                 if res.status_code.is_none() {
-                    res.status_code = Some(StatusCode::NOT_FOUND);
+                    if allowed.is_empty() {
+                        res.status_code(StatusCode::NOT_FOUND);
+                    } else if auto_options {
+                        res.allow(&[&allowed[..], &[Method::OPTIONS]].concat());
+                        res.status_code(StatusCode::NO_CONTENT);
+                    } else {
+                        res.allow(&allowed);
+                        res.status_code(StatusCode::METHOD_NOT_ALLOWED);
+                    }
                 }
-            } else {
-                res.status_code(StatusCode::NOT_FOUND);
             }
 
             let status = res.status_code.unwrap();
@@ -260,6 +330,11 @@ impl HyperHandler {
                     write_error_default(&req, &mut res, None);
                 }
             }
+            if let Some(x_powered_by) = x_powered_by {
+                if !res.headers().contains_key(X_POWERED_BY) {
+                    res.headers_mut().insert(X_POWERED_BY, x_powered_by);
+                }
+            }
             #[cfg(debug_assertions)]
             if Method::HEAD == *req.method() && !res.body.is_none() {
                 tracing::warn!("request with head method should not have body: https://developer.mozilla.org/en-US/docs/Web/HTTP/Methods/HEAD");
@@ -392,4 +467,146 @@ mod tests {
         let content = access(&service, "3").await;
         assert_eq!(content, "before1before2before3");
     }
+
+    #[tokio::test]
+    async fn test_x_powered_by() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        #[handler]
+        async fn custom(res: &mut Response) -> &'static str {
+            res.headers_mut().insert("x-powered-by", "Custom".parse().unwrap());
+            "custom"
+        }
+        let router = Router::new()
+            .push(Router::with_path("hello").get(hello))
+            .push(Router::with_path("custom").get(custom));
+
+        let service = Service::new(router);
+        let res = TestClient::get("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.headers().get("x-powered-by").unwrap(), "Salvo");
+        let res = TestClient::get("http://127.0.0.1:5801/custom").send(&service).await;
+        assert_eq!(res.headers().get("x-powered-by").unwrap(), "Custom");
+
+        let service = service.x_powered_by(None);
+        let res = TestClient::get("http://127.0.0.1:5801/hello").send(&service).await;
+        assert!(res.headers().get("x-powered-by").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_method_not_allowed() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        #[handler]
+        async fn handle405(res: &mut Response, ctrl: &mut FlowCtrl) {
+            if res.status_code == Some(StatusCode::METHOD_NOT_ALLOWED) {
+                res.render("custom 405");
+                ctrl.skip_rest();
+            }
+        }
+        let router = Router::with_path("hello").get(hello).post(hello);
+        let service = Service::new(router);
+
+        let res = TestClient::delete("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get("allow").unwrap(), "GET, POST");
+        let res = TestClient::delete("http://127.0.0.1:5801/other").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        assert!(res.headers().get("allow").is_none());
+
+        let service = service.catcher(crate::catcher::Catcher::default().hoop(handle405));
+        let mut res = TestClient::put("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.take_string().await.unwrap(), "custom 405");
+    }
+
+    #[tokio::test]
+    async fn test_method_not_allowed_with_other_filters() {
+        use crate::routing::filters;
+
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        let router = Router::with_path("hello").push(
+            Router::with_filter(filters::get())
+                .filter(filters::host("salvo.rs"))
+                .goal(hello),
+        );
+        let service = Service::new(router);
+
+        let res = TestClient::delete("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        assert!(res.headers().get("allow").is_none());
+        let res = TestClient::delete("http://salvo.rs/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get("allow").unwrap(), "GET");
+    }
+
+    #[tokio::test]
+    async fn test_method_not_allowed_handler() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        #[handler]
+        async fn not_allowed(res: &mut Response) {
+            res.render("custom 405");
+        }
+        let router = Router::new()
+            .push(
+                Router::with_path("api")
+                    .method_not_allowed_handler(not_allowed)
+                    .push(Router::with_path("hello").get(hello)),
+            )
+            .push(Router::with_path("hello").get(hello));
+        let service = Service::new(router);
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/api/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get("allow").unwrap(), "GET");
+        assert_eq!(res.take_string().await.unwrap(), "custom 405");
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_ne!(res.take_string().await.unwrap(), "custom 405");
+    }
+
+    #[tokio::test]
+    async fn test_auto_options() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        #[handler]
+        async fn options(res: &mut Response) {
+            res.status_code(StatusCode::OK).render("custom options");
+        }
+        let router = Router::new()
+            .push(Router::with_path("hello").get(hello).post(hello))
+            .push(Router::with_path("custom").get(hello).options(options))
+            .push(
+                Router::with_path("manual")
+                    .auto_options(false)
+                    .push(Router::with_path("hello").get(hello)),
+            );
+        let service = Service::new(router);
+
+        let res = TestClient::options("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NO_CONTENT));
+        assert_eq!(res.headers().get("allow").unwrap(), "GET, POST, OPTIONS");
+        let mut res = TestClient::options("http://127.0.0.1:5801/custom").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), "custom options");
+        let res = TestClient::options("http://127.0.0.1:5801/other").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        let res = TestClient::options("http://127.0.0.1:5801/manual/hello")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get("allow").unwrap(), "GET");
+    }
 }
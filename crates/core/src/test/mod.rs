@@ -1,8 +1,37 @@
 //! Test utils for unit tests.
+//!
+//! [`TestClient`] sends requests to a [`Router`](crate::Router), [`Service`](crate::Service) or
+//! [`Handler`](crate::Handler) in-process, no socket is created.
+//!
+//! # Example
+//!
+//! ```
+//! use salvo_core::prelude::*;
+//! use salvo_core::test::{ResponseExt, TestClient};
+//!
+//! #[handler]
+//! async fn hello(req: &mut Request) -> String {
+//!     let name = req.query::<String>("name").unwrap_or_default();
+//!     let lang = req.header::<String>("accept-language").unwrap_or_default();
+//!     format!("hello {name}, {lang}")
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let service = Service::new(Router::new().get(hello));
+//!     let mut res = TestClient::get("http://127.0.0.1:5800/")
+//!         .query("name", "salvo")
+//!         .header("accept-language", "en")
+//!         .send(&service)
+//!         .await;
+//!     assert_eq!(res.status_code, Some(StatusCode::OK));
+//!     assert_eq!(res.take_string().await.unwrap(), "hello salvo, en");
+//! }
+//! ```
 
 mod client;
 mod request;
 mod response;
 pub use client::TestClient;
 pub use request::{RequestBuilder, SendTarget};
-pub use response::ResponseExt;
+pub use response::{ResponseExt, TestResponse};
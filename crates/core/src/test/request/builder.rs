@@ -188,6 +188,15 @@ impl RequestBuilder {
             .or_insert(HeaderValue::from_static("application/x-www-form-urlencoded"));
         self.body(value.into())
     }
+    /// Sets a header for this request, the value will be replaced if the header is already present.
+    #[inline]
+    pub fn header<N, V>(self, name: N, value: V) -> Self
+    where
+        N: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+    {
+        self.add_header(name, value, true)
+    }
     /// Modify a header for this response.
     ///
     /// When `overwrite` is set to `true`, If the header is already present, the value will be replaced.
@@ -8,11 +8,13 @@ use flate2::write::{GzDecoder, ZlibDecoder};
 use http_body_util::BodyExt;
 use mime::Mime;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use tokio::io::{Error as IoError, ErrorKind};
 use zstd::stream::write::Decoder as ZstdDecoder;
 
 use crate::catcher::status_error_bytes;
-use crate::http::header::{self, CONTENT_ENCODING};
+use crate::http::header::{self, AsHeaderName, HeaderMap, HeaderValue, CONTENT_ENCODING};
+use crate::http::StatusCode;
 use crate::http::response::{ResBody, Response};
 use crate::Error;
 
@@ -145,3 +147,207 @@ impl ResponseExt for Response {
         Ok(bytes)
     }
 }
+
+/// A fully received response with assertion helpers, for test usage.
+///
+/// All assertions panic with a message showing the actual value, and return `&Self` so they can
+/// be chained.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_core::test::{TestClient, TestResponse};
+///
+/// #[handler]
+/// async fn hello() -> Json<&'static str> {
+///     Json("hello")
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let res = TestClient::get("http://127.0.0.1:5800/").send(Router::new().get(hello)).await;
+/// TestResponse::from_response(res)
+///     .await
+///     .assert_status(StatusCode::OK)
+///     .assert_content_type("application/json")
+///     .assert_header("x-powered-by", "Salvo")
+///     .assert_header_missing("set-cookie")
+///     .assert_body_json(&"hello");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TestResponse {
+    status_code: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestResponse {
+    /// Create new `TestResponse` by taking all body of the [`Response`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if failed to read the response body.
+    pub async fn from_response(mut res: Response) -> Self {
+        let content_type = res
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<Mime>().ok());
+        let body = res
+            .take_bytes(content_type.as_ref())
+            .await
+            .expect("failed to read response body");
+        Self {
+            status_code: res.status_code.unwrap_or(StatusCode::OK),
+            headers: std::mem::take(res.headers_mut()),
+            body,
+        }
+    }
+
+    /// Get status code.
+    #[inline]
+    pub fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+    /// Get headers reference.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+    /// Get header value with name.
+    #[inline]
+    pub fn header(&self, name: impl AsHeaderName) -> Option<&HeaderValue> {
+        self.headers.get(name)
+    }
+    /// Get body bytes.
+    #[inline]
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+    /// Get body as text, invalid UTF-8 sequences are replaced.
+    #[inline]
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+    /// Deserialize body as JSON to type `T` instance.
+    #[inline]
+    pub fn json<T: DeserializeOwned>(&self) -> crate::Result<T> {
+        serde_json::from_slice(&self.body).map_err(Error::SerdeJson)
+    }
+
+    /// Assert status code equals to `expected`.
+    #[track_caller]
+    pub fn assert_status(&self, expected: StatusCode) -> &Self {
+        assert_eq!(
+            self.status_code, expected,
+            "expected status code `{expected}`, but got `{}`",
+            self.status_code
+        );
+        self
+    }
+    /// Assert header `name` is present and its value equals to `expected`.
+    #[track_caller]
+    pub fn assert_header(&self, name: impl AsHeaderName + Copy + std::fmt::Display, expected: &str) -> &Self {
+        match self.headers.get(name) {
+            Some(value) => assert!(
+                value == expected,
+                "expected header `{name}` to be `{expected}`, but got `{value:?}`"
+            ),
+            None => panic!("expected header `{name}` to be `{expected}`, but it is missing"),
+        }
+        self
+    }
+    /// Assert header `name` is not present.
+    #[track_caller]
+    pub fn assert_header_missing(&self, name: impl AsHeaderName + Copy + std::fmt::Display) -> &Self {
+        if let Some(value) = self.headers.get(name) {
+            panic!("expected header `{name}` to be missing, but got `{value:?}`");
+        }
+        self
+    }
+    /// Assert body text equals to `expected`.
+    #[track_caller]
+    pub fn assert_body_text(&self, expected: &str) -> &Self {
+        let text = self.text();
+        assert!(text == expected, "expected body `{expected}`, but got `{text}`");
+        self
+    }
+    /// Assert body is JSON and equals to `expected` after serializing it.
+    #[track_caller]
+    pub fn assert_body_json<T: Serialize + ?Sized>(&self, expected: &T) -> &Self {
+        let expected = serde_json::to_value(expected).expect("failed to serialize expected value");
+        match serde_json::from_slice::<serde_json::Value>(&self.body) {
+            Ok(actual) => assert!(actual == expected, "expected JSON body `{expected}`, but got `{actual}`"),
+            Err(e) => panic!("expected JSON body `{expected}`, but body is not JSON ({e}): `{}`", self.text()),
+        }
+        self
+    }
+    /// Assert the mime type of `Content-Type` header equals to `expected`.
+    ///
+    /// Parameters such as `charset` are only compared if `expected` contains them.
+    #[track_caller]
+    pub fn assert_content_type(&self, expected: &str) -> &Self {
+        let actual = self.headers.get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok());
+        let matched = match actual {
+            Some(actual) if expected.contains(';') => actual.eq_ignore_ascii_case(expected),
+            Some(actual) => actual
+                .split(';')
+                .next()
+                .map(|essence| essence.trim().eq_ignore_ascii_case(expected))
+                .unwrap_or(false),
+            None => false,
+        };
+        assert!(matched, "expected content type `{expected}`, but got `{actual:?}`");
+        self
+    }
+}
+
+impl From<hyper::Response<Bytes>> for TestResponse {
+    fn from(res: hyper::Response<Bytes>) -> Self {
+        let (parts, body) = res.into_parts();
+        Self {
+            status_code: parts.status,
+            headers: parts.headers,
+            body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response() -> TestResponse {
+        hyper::Response::builder()
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Bytes::from_static(br#"{"name":"salvo","tags":[1,2]}"#))
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_assertions() {
+        response()
+            .assert_status(StatusCode::OK)
+            .assert_header("content-type", "application/json; charset=utf-8")
+            .assert_header_missing("x-request-id")
+            .assert_content_type("application/json")
+            .assert_content_type("application/json; charset=utf-8")
+            .assert_body_text(r#"{"name":"salvo","tags":[1,2]}"#)
+            .assert_body_json(&serde_json::json!({"tags": [1, 2], "name": "salvo"}));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected status code `404 Not Found`, but got `200 OK`")]
+    fn test_assert_status_panics() {
+        response().assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected header `x-request-id` to be `1`, but it is missing")]
+    fn test_assert_header_panics() {
+        response().assert_header("x-request-id", "1");
+    }
+}
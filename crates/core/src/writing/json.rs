@@ -14,22 +14,27 @@ where
     T: Serialize + Send,
 {
     fn render(self, res: &mut Response) {
-        match serde_json::to_vec(&self.0) {
-            Ok(bytes) => {
-                res.headers_mut().insert(
-                    CONTENT_TYPE,
-                    HeaderValue::from_static("application/json; charset=utf-8"),
-                );
-                res.write_body(bytes).ok();
-            }
-            Err(e) => {
-                tracing::error!(error = ?e, "JsonContent write error");
-                res.render(StatusError::internal_server_error());
-            }
+        if let Err(e) = write_json(res, &self.0) {
+            tracing::error!(error = ?e, "JsonContent write error");
+            res.render(StatusError::internal_server_error());
         }
     }
 }
 
+/// Serializes `value` and writes it to `res` with json content type, the response is not touched if
+/// serialization fails.
+pub(crate) fn write_json<T>(res: &mut Response, value: &T) -> crate::Result<()>
+where
+    T: Serialize + ?Sized,
+{
+    let bytes = serde_json::to_vec(value)?;
+    res.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    res.write_body(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
@@ -56,4 +61,37 @@ mod tests {
             "application/json; charset=utf-8"
         );
     }
+
+    #[tokio::test]
+    async fn test_write_vec_as_json() {
+        #[derive(Serialize, Debug)]
+        struct User {
+            name: String,
+        }
+        #[handler]
+        async fn users() -> Vec<User> {
+            vec![User { name: "jobs".into() }, User { name: "gates".into() }]
+        }
+        #[handler]
+        fn values() -> Vec<serde_json::Value> {
+            vec![serde_json::json!({"id": 1}), serde_json::json!("two")]
+        }
+
+        let router = Router::new()
+            .push(Router::with_path("users").get(users))
+            .push(Router::with_path("values").get(values));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/users").send(&service).await;
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            r#"[{"name":"jobs"},{"name":"gates"}]"#
+        );
+        assert_eq!(
+            res.headers().get("content-type").unwrap(),
+            "application/json; charset=utf-8"
+        );
+        let mut res = TestClient::get("http://127.0.0.1:5800/values").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), r#"[{"id":1},"two"]"#);
+    }
 }
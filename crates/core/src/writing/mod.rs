@@ -1,6 +1,6 @@
 //! Writer trait and it's implements.
 
-mod json;
+pub(crate) mod json;
 mod redirect;
 mod seek;
 mod text;
@@ -154,4 +154,33 @@ mod tests {
         assert_eq!(res.take_string().await.unwrap(), "hello");
         assert_eq!(res.headers().get("content-type").unwrap(), "text/plain; charset=utf-8");
     }
+
+    #[tokio::test]
+    async fn test_write_impl_scribe() {
+        struct HtmlPage(String);
+        impl Scribe for HtmlPage {
+            fn render(self, res: &mut Response) {
+                res.render(Text::Html(self.0));
+            }
+        }
+        #[handler]
+        async fn page() -> impl Scribe {
+            HtmlPage("<h1>hello</h1>".into())
+        }
+        #[handler]
+        async fn nothing() -> impl Scribe {}
+
+        let router = Router::new()
+            .push(Router::with_path("page").get(page))
+            .push(Router::with_path("nothing").get(nothing));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/page").send(&service).await;
+        assert_eq!(res.headers().get("content-type").unwrap(), "text/html; charset=utf-8");
+        assert_eq!(res.take_string().await.unwrap(), "<h1>hello</h1>");
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/nothing").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.take_string().await.unwrap().is_empty());
+    }
 }
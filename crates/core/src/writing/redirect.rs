@@ -1,10 +1,20 @@
+use async_trait::async_trait;
+
 use super::Scribe;
 use crate::http::header::{HeaderValue, LOCATION};
 use crate::http::uri::Uri;
-use crate::http::{Response, StatusCode};
-use crate::Error;
+use crate::http::{Request, Response, StatusCode};
+use crate::{Depot, Error, FlowCtrl, Handler};
 
 /// Response that redirects the request to another location.
+///
+/// `Redirect` also implements [`Handler`], so it can be used as a router goal directly:
+///
+/// ```
+/// use salvo_core::prelude::*;
+///
+/// let router = Router::with_path("old-path").get(Redirect::permanent("/new-path"));
+/// ```
 #[derive(Clone, Debug)]
 pub struct Redirect {
     status_code: StatusCode,
@@ -28,6 +38,20 @@ impl Redirect {
         Self::with_status_code(StatusCode::SEE_OTHER, uri).expect("invalid uri")
     }
 
+    /// Create a new [`Redirect`] that uses a [`303 See Other`][mdn] status code.
+    ///
+    /// This is an alias of [`Redirect::other`], useful for post-redirect-get pattern.
+    ///
+    /// # Panics
+    ///
+    /// If `uri` isn't a valid [`Uri`].
+    ///
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/303
+    #[inline]
+    pub fn see_other(uri: impl TryInto<Uri>) -> Self {
+        Self::other(uri)
+    }
+
     /// Create a new [`Redirect`] that uses a [`307 Temporary Redirect`][mdn] status code.
     ///
     /// # Panics
@@ -41,6 +65,11 @@ impl Redirect {
 
     /// Create a new [`Redirect`] that uses a [`308 Permanent Redirect`][mdn] status code.
     ///
+    /// `308` is the permanent counterpart of [`Redirect::temporary`], clients must keep the request method
+    /// and body, while with `301 Moved Permanently` they may change `POST` to `GET`. Use
+    /// `Redirect::with_status_code(StatusCode::MOVED_PERMANENTLY, uri)` if `301` is needed, for example
+    /// by legacy clients which do not understand `308`.
+    ///
     /// # Panics
     ///
     /// If `uri` isn't a valid [`Uri`].
@@ -92,3 +121,39 @@ impl Scribe for Redirect {
         res.headers_mut().insert(LOCATION, location);
     }
 }
+
+#[async_trait]
+impl Handler for Redirect {
+    #[inline]
+    async fn handle(&self, _req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
+        res.render(self.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test::TestClient;
+
+    #[tokio::test]
+    async fn test_redirect_handler() {
+        let router = Router::new()
+            .push(Router::with_path("old-path").get(Redirect::permanent("/new-path")))
+            .push(Router::with_path("form").post(Redirect::see_other("/done")));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/old-path").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::PERMANENT_REDIRECT));
+        assert_eq!(res.headers().get("location").unwrap(), "/new-path");
+
+        let res = TestClient::post("http://127.0.0.1:5801/form").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::SEE_OTHER));
+        assert_eq!(res.headers().get("location").unwrap(), "/done");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid uri")]
+    fn test_redirect_invalid_uri() {
+        let _ = Redirect::temporary("http://[::1");
+    }
+}
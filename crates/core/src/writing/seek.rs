@@ -96,17 +96,24 @@ where
         let mut offset = 0;
         let mut length = self.length;
         // check for range header
-        let range = req_headers.get(RANGE);
-        if let Some(range) = range {
+        let mut partial = false;
+        if let Some(range) = req_headers.get(RANGE) {
             if let Ok(range) = range.to_str() {
-                if let Ok(range) = HttpRange::parse(range, length) {
-                    length = range[0].length;
-                    offset = range[0].start;
-                } else {
-                    res.headers_mut().typed_insert(ContentRange::unsatisfied_bytes(length));
-                    res.status_code(StatusCode::RANGE_NOT_SATISFIABLE);
-                    return;
-                };
+                match HttpRange::parse(range, length) {
+                    Ok(ranges) if ranges.iter().any(|range| range.length > 0) => {
+                        // Ranges too far apart to be merged are ignored, and the whole content is sent.
+                        if let Some(range) = HttpRange::coalesce(&ranges) {
+                            length = range.length;
+                            offset = range.start;
+                            partial = true;
+                        }
+                    }
+                    _ => {
+                        res.headers_mut().typed_insert(ContentRange::unsatisfied_bytes(length));
+                        res.status_code(StatusCode::RANGE_NOT_SATISFIABLE);
+                        return;
+                    }
+                }
             } else {
                 res.status_code(StatusCode::BAD_REQUEST);
                 return;
@@ -121,9 +128,9 @@ where
             return;
         }
 
-        if offset != 0 || length != self.length || range.is_some() {
+        if partial {
             res.status_code(StatusCode::PARTIAL_CONTENT);
-            match ContentRange::bytes(offset..offset + length - 1, self.length) {
+            match ContentRange::bytes(offset..offset + length, self.length) {
                 Ok(content_range) => {
                     res.headers_mut().typed_insert(content_range);
                 }
@@ -55,6 +55,10 @@ pub use self::{
 };
 
 static WILDCARD: HeaderValue = HeaderValue::from_static("*");
+const ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK: HeaderName =
+    HeaderName::from_static("access-control-allow-private-network");
+const ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK: HeaderName =
+    HeaderName::from_static("access-control-request-private-network");
 
 /// Represents a wildcard value (`*`) used with some CORS headers such as
 /// [`Cors::allow_methods`].
@@ -93,6 +97,8 @@ pub struct Cors {
     expose_headers: ExposeHeaders,
     max_age: MaxAge,
     vary: Vary,
+    reflect_origin: bool,
+    allow_private_network: bool,
 }
 impl Default for Cors {
     #[inline]
@@ -113,6 +119,8 @@ impl Cors {
             expose_headers: Default::default(),
             max_age: Default::default(),
             vary: Default::default(),
+            reflect_origin: false,
+            allow_private_network: false,
         }
     }
 
@@ -206,6 +214,32 @@ impl Cors {
     #[inline]
     pub fn allow_origin(mut self, origin: impl Into<AllowOrigin>) -> Self {
         self.allow_origin = origin.into();
+        self.reflect_origin = false;
+        self
+    }
+
+    /// Set the `Access-Control-Allow-Origin` header to the request's `Origin` header value,
+    /// and `Origin` will always be added to `Vary` header.
+    ///
+    /// Use it only when all origins are trusted. It can not be combined with `allow_credentials(true)`,
+    /// because every origin would be allowed to send credentials.
+    #[inline]
+    pub fn reflect_origin(mut self) -> Self {
+        self.allow_origin = AllowOrigin::mirror_request();
+        self.reflect_origin = true;
+        self
+    }
+
+    /// Sets whether to add the [`Access-Control-Allow-Private-Network`][spec] header to preflight responses
+    /// which carry `Access-Control-Request-Private-Network: true`.
+    ///
+    /// It is required by Chrome's Private Network Access when resources on local network are accessed from
+    /// public origins.
+    ///
+    /// [spec]: https://wicg.github.io/private-network-access/
+    #[inline]
+    pub fn allow_private_network(mut self, allow_private_network: bool) -> Self {
+        self.allow_private_network = allow_private_network;
         self
     }
 
@@ -234,37 +268,63 @@ impl Cors {
     }
 
     /// Returns a new `CorsHandler` using current cors settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the settings are invalid, see [`Cors::try_into_handler`].
     pub fn into_handler(self) -> CorsHandler {
-        self.ensure_usable_cors_rules();
-        CorsHandler(self)
+        match self.try_into_handler() {
+            Ok(handler) => handler,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Returns a new `CorsHandler` using current cors settings, returns an error if the settings are invalid.
+    ///
+    /// `Access-Control-Allow-Credentials: true` can not be combined with wildcard (`*`) origin, methods,
+    /// headers or expose headers, or with [`Cors::reflect_origin`].
+    pub fn try_into_handler(mut self) -> Result<CorsHandler, salvo_core::Error> {
+        self.ensure_usable_cors_rules().map_err(salvo_core::Error::other)?;
+        if self.reflect_origin {
+            self.vary.ensure(header::ORIGIN);
+        }
+        Ok(CorsHandler(self))
     }
 
-    fn ensure_usable_cors_rules(&self) {
+    fn ensure_usable_cors_rules(&self) -> Result<(), &'static str> {
         if self.allow_credentials.is_true() {
-            assert!(
-                !self.allow_headers.is_wildcard(),
-                "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
-                 with `Access-Control-Allow-Headers: *`"
-            );
-
-            assert!(
-                !self.allow_methods.is_wildcard(),
-                "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
-                 with `Access-Control-Allow-Methods: *`"
-            );
-
-            assert!(
-                !self.allow_origin.is_wildcard(),
-                "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
-                 with `Access-Control-Allow-Origin: *`"
-            );
-
-            assert!(
-                !self.expose_headers.is_wildcard(),
-                "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
-                 with `Access-Control-Expose-Headers: *`"
-            );
+            if self.allow_headers.is_wildcard() {
+                return Err(
+                    "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
+                 with `Access-Control-Allow-Headers: *`",
+                );
+            }
+            if self.allow_methods.is_wildcard() {
+                return Err(
+                    "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
+                 with `Access-Control-Allow-Methods: *`",
+                );
+            }
+            if self.allow_origin.is_wildcard() {
+                return Err(
+                    "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
+                 with `Access-Control-Allow-Origin: *`",
+                );
+            }
+            if self.reflect_origin {
+                return Err(
+                    "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
+                 with `Cors::reflect_origin`",
+                );
+            }
+            if self.expose_headers.is_wildcard() {
+                return Err(
+                    "Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` \
+                 with `Access-Control-Expose-Headers: *`",
+                );
+            }
         }
+        Ok(())
     }
 }
 
@@ -305,6 +365,15 @@ impl Handler for CorsHandler {
             headers.extend(self.0.allow_methods.to_header(origin, req, depot));
             headers.extend(self.0.allow_headers.to_header(origin, req, depot));
             headers.extend(self.0.max_age.to_header(origin, req, depot));
+            if self.0.allow_private_network
+                && req
+                    .headers()
+                    .get(ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK)
+                    .map(|v| v == "true")
+                    .unwrap_or(false)
+            {
+                headers.insert(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK, HeaderValue::from_static("true"));
+            }
             res.status_code = Some(StatusCode::NO_CONTENT);
         } else {
             // This header is applied only to non-preflight requests
@@ -384,4 +453,79 @@ mod tests {
         );
         assert!(headers.get(ACCESS_CONTROL_ALLOW_HEADERS).is_none());
     }
+
+    #[tokio::test]
+    async fn test_reflect_origin_and_private_network() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let cors_handler = Cors::new()
+            .vary::<Vary>([ACCESS_CONTROL_REQUEST_METHOD])
+            .reflect_origin()
+            .allow_methods(vec![Method::GET])
+            .allow_private_network(true)
+            .into_handler();
+        let router = Router::with_hoop(cors_handler).push(Router::with_path("hello").goal(hello));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("Origin", "https://example.com", true)
+            .send(&service)
+            .await;
+        let headers = res.headers();
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), "https://example.com");
+        assert!(headers.get_all(VARY).iter().any(|v| v == "origin"));
+        assert!(headers.get(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK).is_none());
+
+        let res = TestClient::options("http://127.0.0.1:5801/hello")
+            .add_header("Origin", "https://salvo.rs", true)
+            .add_header("Access-Control-Request-Method", "GET", true)
+            .add_header("Access-Control-Request-Private-Network", "true", true)
+            .send(&service)
+            .await;
+        let headers = res.headers();
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), "https://salvo.rs");
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK).unwrap(), "true");
+
+        let res = TestClient::options("http://127.0.0.1:5801/hello")
+            .add_header("Origin", "https://salvo.rs", true)
+            .add_header("Access-Control-Request-Method", "GET", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_allow_origin_after_reflect_origin() {
+        let cors_handler = Cors::new()
+            .vary::<Vary>([ACCESS_CONTROL_REQUEST_METHOD])
+            .reflect_origin()
+            .allow_origin("https://salvo.rs")
+            .into_handler();
+        let service = Service::new(Router::with_hoop(cors_handler));
+
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Origin", "https://salvo.rs", true)
+            .send(&service)
+            .await;
+        assert!(!res.headers().get_all(VARY).iter().any(|v| v == "origin"));
+    }
+
+    #[test]
+    fn test_try_into_handler() {
+        let result = Cors::new().allow_origin(Any).allow_credentials(true).try_into_handler();
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("`Access-Control-Allow-Origin: *`"));
+        assert!(Cors::new()
+            .allow_origin("https://salvo.rs")
+            .allow_credentials(true)
+            .try_into_handler()
+            .is_ok());
+        let result = Cors::new().reflect_origin().allow_credentials(true).try_into_handler();
+        assert!(result.unwrap_err().to_string().contains("`Cors::reflect_origin`"));
+    }
 }
@@ -23,6 +23,13 @@ impl Vary {
         Self(headers.into_iter().map(Into::into).collect())
     }
 
+    pub(super) fn ensure(&mut self, header: HeaderName) {
+        let value = HeaderValue::from(header);
+        if !self.0.contains(&value) {
+            self.0.push(value);
+        }
+    }
+
     pub(super) fn values(&self) -> impl Iterator<Item = HeaderValue> + '_ {
         self.0.iter().cloned()
     }
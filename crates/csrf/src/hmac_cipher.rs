@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
 use hmac::{Hmac, Mac};
@@ -9,6 +11,7 @@ use super::CsrfCipher;
 pub struct HmacCipher {
     hmac_key: [u8; 32],
     token_size: usize,
+    ttl: Option<Duration>,
 }
 
 impl HmacCipher {
@@ -18,6 +21,7 @@ impl HmacCipher {
         Self {
             hmac_key,
             token_size: 32,
+            ttl: None,
         }
     }
 
@@ -29,6 +33,24 @@ impl HmacCipher {
         self
     }
 
+    /// Sets the max age of tokens.
+    ///
+    /// The generation time is appended to the token and authenticated by the HMAC,
+    /// so expired tokens are rejected without any server side state.
+    #[inline]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    #[inline]
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+
     #[inline]
     fn hmac(&self) -> Hmac<Sha256> {
         Hmac::<Sha256>::new_from_slice(&self.hmac_key).expect("HMAC can take key of any size")
@@ -41,19 +63,33 @@ impl CsrfCipher for HmacCipher {
             URL_SAFE_NO_PAD.decode(token.as_bytes()),
             URL_SAFE_NO_PAD.decode(proof.as_bytes()),
         ) {
-            if proof.len() != self.token_size {
-                false
-            } else {
-                let mut hmac = self.hmac();
-                hmac.update(&token);
-                hmac.verify((&*proof).into()).is_ok()
+            let token_size = self.token_size + if self.ttl.is_some() { 8 } else { 0 };
+            if token.len() != token_size {
+                return false;
+            }
+            let mut hmac = self.hmac();
+            hmac.update(&token);
+            // `verify_slice` compares in constant time.
+            if hmac.verify_slice(&proof).is_err() {
+                return false;
+            }
+            match self.ttl {
+                Some(ttl) => {
+                    let mut issued_at = [0u8; 8];
+                    issued_at.copy_from_slice(&token[self.token_size..]);
+                    Self::now().saturating_sub(u64::from_be_bytes(issued_at)) <= ttl.as_secs()
+                }
+                None => true,
             }
         } else {
             false
         }
     }
     fn generate(&self) -> (String, String) {
-        let token = self.random_bytes(self.token_size);
+        let mut token = self.random_bytes(self.token_size);
+        if self.ttl.is_some() {
+            token.extend_from_slice(&Self::now().to_be_bytes());
+        }
         let mut hmac = self.hmac();
         hmac.update(&token);
         let mac = hmac.finalize();
@@ -108,4 +144,28 @@ mod tests {
         let (token, proof) = hmac_cipher.generate();
         assert!(hmac_cipher.verify(&token, &proof));
     }
+
+    #[test]
+    fn test_verify_with_token_size() {
+        let hmac_cipher = HmacCipher::new([0u8; 32]).token_size(16);
+        let (token, proof) = hmac_cipher.generate();
+        assert!(hmac_cipher.verify(&token, &proof));
+    }
+
+    #[test]
+    fn test_verify_ttl() {
+        let hmac_cipher = HmacCipher::new([0u8; 32]).ttl(Duration::from_secs(10));
+        let (token, proof) = hmac_cipher.generate();
+        assert!(hmac_cipher.verify(&token, &proof));
+
+        let mut token = hmac_cipher.random_bytes(hmac_cipher.token_size);
+        token.extend_from_slice(&(HmacCipher::now() - 100).to_be_bytes());
+        let mut hmac = hmac_cipher.hmac();
+        hmac.update(&token);
+        let proof = URL_SAFE_NO_PAD.encode(hmac.finalize().into_bytes());
+        let token = URL_SAFE_NO_PAD.encode(token);
+        assert!(!hmac_cipher.verify(&token, &proof));
+        assert!(HmacCipher::new([0u8; 32]).ttl(Duration::from_secs(1000)).verify(&token, &proof));
+        assert!(!HmacCipher::new([0u8; 32]).verify(&token, &proof));
+    }
 }
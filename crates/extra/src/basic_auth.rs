@@ -1,6 +1,7 @@
 //! basic auth middleware.
 //!
 //! Read more: <https://salvo.rs>
+use std::collections::HashMap;
 use std::future::Future;
 
 use salvo_core::http::header::{HeaderName, PROXY_AUTHORIZATION, AUTHORIZATION};
@@ -16,6 +17,77 @@ pub trait BasicAuthValidator: Send + Sync {
     /// Validate is that username and password is right.
     fn validate(&self, username: &str, password: &str, depot: &mut Depot) -> impl Future<Output = bool> + Send;
 }
+/// Validator checks credentials against a static username to password map.
+///
+/// Passwords are compared in constant time.
+#[derive(Clone, Debug, Default)]
+pub struct StaticValidator(pub HashMap<String, String>);
+impl StaticValidator {
+    /// Create new `StaticValidator` from (username, password) pairs.
+    #[inline]
+    pub fn new<U, P>(credentials: impl IntoIterator<Item = (U, P)>) -> Self
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        Self(credentials.into_iter().map(|(u, p)| (u.into(), p.into())).collect())
+    }
+}
+impl BasicAuthValidator for StaticValidator {
+    async fn validate(&self, username: &str, password: &str, _depot: &mut Depot) -> bool {
+        match self.0.get(username) {
+            Some(expected) => constant_time_eq(expected.as_bytes(), password.as_bytes()),
+            None => {
+                // Compare anyway, so unknown usernames take the same time.
+                std::hint::black_box(constant_time_eq(DUMMY_PASSWORD, password.as_bytes()));
+                false
+            }
+        }
+    }
+}
+
+/// Validator uses an async function to check credentials.
+///
+/// ```
+/// use salvo_extra::basic_auth::{AsyncFnValidator, BasicAuth};
+///
+/// let auth = BasicAuth::new(AsyncFnValidator::new(|username: String, password: String| async move {
+///     username == "root" && password == "pwd"
+/// }));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AsyncFnValidator<F>(pub F);
+impl<F> AsyncFnValidator<F> {
+    /// Create new `AsyncFnValidator`.
+    #[inline]
+    pub fn new(validator: F) -> Self {
+        Self(validator)
+    }
+}
+impl<F, Fut> BasicAuthValidator for AsyncFnValidator<F>
+where
+    F: Fn(String, String) -> Fut + Send + Sync,
+    Fut: Future<Output = bool> + Send,
+{
+    async fn validate(&self, username: &str, password: &str, _depot: &mut Depot) -> bool {
+        (self.0)(username.to_owned(), password.to_owned()).await
+    }
+}
+
+/// Secret compared against when the username is unknown.
+const DUMMY_PASSWORD: &[u8] = b"salvo-basic-auth-dummy-password";
+
+/// Compare two byte slices in constant time for the same length, to prevent timing attacks.
+#[inline(never)]
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let (a, b) = (std::hint::black_box(a), std::hint::black_box(b));
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
 /// BasicAuthDepotExt
 pub trait BasicAuthDepotExt {
     /// Get basic auth username reference.
@@ -49,6 +121,13 @@ where
         }
     }
 
+    /// Sets the realm sent in `WWW-Authenticate` header and returns new `BasicAuth`.
+    #[inline]
+    pub fn realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = realm.into();
+        self
+    }
+
     #[doc(hidden)]
     #[inline]
     pub fn set_header_names(mut self, header_names: impl Into<Vec<HeaderName>>) -> Self {
@@ -177,4 +256,54 @@ mod tests {
             .unwrap();
         assert!(content.contains("Unauthorized"));
     }
+
+    #[tokio::test]
+    async fn test_static_validator() {
+        let auth_handler = BasicAuth::new(StaticValidator::new([("root", "pwd")])).realm("admin");
+        let router = Router::with_hoop(auth_handler).goal(hello);
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5800/")
+            .basic_auth("root", Some("pwd"))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        for (username, password) in [("root", "pwd2"), ("admin", "pwd")] {
+            let res = TestClient::get("http://127.0.0.1:5800/")
+                .basic_auth(username, Some(password))
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+            assert_eq!(res.headers().get("www-authenticate").unwrap(), r#"Basic realm="admin""#);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_fn_validator() {
+        #[handler]
+        async fn show_username(depot: &mut Depot) -> String {
+            depot.basic_auth_username().cloned().unwrap_or_default()
+        }
+        let auth_handler = BasicAuth::new(AsyncFnValidator::new(|username: String, password: String| async move {
+            username == "root" && password == "pwd"
+        }));
+        let router = Router::with_hoop(auth_handler).goal(show_username);
+
+        let content = TestClient::get("http://127.0.0.1:5800/")
+            .basic_auth("root", Some("pwd"))
+            .send(router)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "root");
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"pwd", b"pwd"));
+        assert!(!constant_time_eq(b"pwd", b"pwe"));
+        assert!(!constant_time_eq(b"pwd", b"pwd2"));
+    }
 }
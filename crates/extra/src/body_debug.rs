@@ -0,0 +1,178 @@
+//! Body debug logging middleware.
+//!
+//! This middleware is intended for development only, it logs request and response bodies,
+//! and it is disabled unless environment variable `SALVO_LOG_BODIES` is set to `1` in debug build.
+//! It is always disabled in release build.
+//!
+//! Read more: <https://salvo.rs>
+use std::borrow::Cow;
+
+use salvo_core::http::header::CONTENT_LENGTH;
+use salvo_core::http::request::secure_max_size;
+use salvo_core::http::{ReqBody, Request, ResBody, Response};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+/// Environment variable used to enable body logging.
+pub const LOG_BODIES_ENV: &str = "SALVO_LOG_BODIES";
+/// Default max size of body will be logged.
+pub const DEFAULT_MAX_SIZE: usize = 4 * 1024;
+
+/// A middleware logs request and response bodies for debugging.
+///
+/// Bodies are truncated to `max_size` bytes (default 4 KiB) before logging. Request body without
+/// `Content-Length` or larger than [`secure_max_size`] is not captured, so large uploads are never buffered.
+#[derive(Debug)]
+pub struct BodyDebugLogger {
+    enabled: bool,
+    max_size: usize,
+}
+impl Default for BodyDebugLogger {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl BodyDebugLogger {
+    /// Create new `BodyDebugLogger` middleware.
+    ///
+    /// It is enabled only in debug build and when environment variable `SALVO_LOG_BODIES` is `1`.
+    #[inline]
+    pub fn new() -> Self {
+        BodyDebugLogger {
+            enabled: cfg!(debug_assertions) && std::env::var(LOG_BODIES_ENV).map(|v| v == "1").unwrap_or(false),
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+    /// Sets max size of body will be logged and returns new `BodyDebugLogger`.
+    #[inline]
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+    /// Check is logging enabled.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn format<'a>(&self, data: &'a [u8]) -> (Cow<'a, str>, bool) {
+        if data.len() > self.max_size {
+            (String::from_utf8_lossy(&data[..self.max_size]), true)
+        } else {
+            (String::from_utf8_lossy(data), false)
+        }
+    }
+
+    async fn log_request(&self, req: &mut Request) {
+        let content_length = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        if req.body().is_none() || content_length == Some(0) {
+            return;
+        }
+        match content_length {
+            Some(size) if size <= secure_max_size() => {}
+            _ => {
+                tracing::debug!(content_length, "request body not captured");
+                return;
+            }
+        }
+        let payload = match req.payload().await {
+            Ok(payload) => payload.clone(),
+            Err(e) => {
+                tracing::debug!(error = ?e, "request body read failed");
+                return;
+            }
+        };
+        let (body, truncated) = self.format(&payload);
+        tracing::debug!(size = payload.len(), truncated, %body, "request body");
+        req.replace_body(ReqBody::Once(payload));
+    }
+
+    fn log_response(&self, res: &Response) {
+        match &res.body {
+            ResBody::Once(bytes) => {
+                let (body, truncated) = self.format(bytes);
+                tracing::debug!(size = bytes.len(), truncated, %body, "response body");
+            }
+            ResBody::Chunks(chunks) => {
+                let data = chunks.iter().flat_map(|c| c.iter().copied()).collect::<Vec<u8>>();
+                let (body, truncated) = self.format(&data);
+                tracing::debug!(size = data.len(), truncated, %body, "response body");
+            }
+            ResBody::None | ResBody::Error(_) => {}
+            _ => {
+                tracing::debug!("response body is streaming, not captured");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for BodyDebugLogger {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if !self.enabled {
+            return;
+        }
+        self.log_request(req).await;
+        ctrl.call_next(req, depot, res).await;
+        self.log_response(res);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[handler]
+    async fn echo(req: &mut Request) -> String {
+        String::from_utf8(req.payload().await.unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_log_bodies() {
+        let logger = BodyDebugLogger {
+            enabled: true,
+            max_size: 8,
+        };
+        let router = Router::with_hoop(logger).push(Router::with_path("echo").post(echo));
+        let content = TestClient::post("http://127.0.0.1:5801/echo")
+            .text("hello body logger")
+            .send(router)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "hello body logger");
+        assert!(logs_contain("request body"));
+        assert!(logs_contain("response body"));
+        assert!(logs_contain("hello bo"));
+        assert!(!logs_contain("hello body"));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_disabled() {
+        let logger = BodyDebugLogger {
+            enabled: false,
+            max_size: DEFAULT_MAX_SIZE,
+        };
+        let router = Router::with_hoop(logger).push(Router::with_path("echo").post(echo));
+        let content = TestClient::post("http://127.0.0.1:5801/echo")
+            .text("hello")
+            .send(router)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "hello");
+        assert!(!logs_contain("request body"));
+    }
+}
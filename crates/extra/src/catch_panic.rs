@@ -1,6 +1,8 @@
 //! Catch panic middleware.
 //!
 //! Read more: <https://salvo.rs>
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
 use std::panic::AssertUnwindSafe;
 
 use futures_util::FutureExt;
@@ -8,15 +10,55 @@ use futures_util::FutureExt;
 use salvo_core::http::{Request, Response, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Error, Handler};
 
+type PanicHook = Box<dyn Fn(&str, &Request) + Send + Sync>;
+
 /// This middleware catches panics and write `500 INTERNAL SERVER ERROR`
 /// into response. This middleware should be used as the first middleware.
-#[derive(Default, Debug)]
-pub struct CatchPanic {}
+///
+/// Panics in any following middleware or handler, however deeply nested, are caught. The panic message is
+/// logged and is included in the response in debug build only. The backtrace is printed by the panic hook
+/// of the standard library when `RUST_BACKTRACE` is set, as it is not available after unwinding.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::catch_panic::CatchPanic;
+///
+/// let catch_panic = CatchPanic::new().on_panic(|message, req| {
+///     // Send alert to error tracking service here.
+///     eprintln!("{} panicked: {message}", req.uri());
+/// });
+/// let router = Router::with_hoop(catch_panic);
+/// ```
+#[derive(Default)]
+pub struct CatchPanic {
+    on_panic: Option<PanicHook>,
+}
 impl CatchPanic {
     /// Create new `CatchPanic` middleware.
     #[inline]
     pub fn new() -> Self {
-        CatchPanic {}
+        CatchPanic { on_panic: None }
+    }
+
+    /// Sets a hook called with the panic message and the request after a panic is caught, and returns
+    /// new `CatchPanic`.
+    #[inline]
+    pub fn on_panic<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &Request) + Send + Sync + 'static,
+    {
+        self.on_panic = Some(Box::new(hook));
+        self
+    }
+}
+
+impl Debug for CatchPanic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CatchPanic")
+            .field("on_panic", &self.on_panic.is_some())
+            .finish()
     }
 }
 
@@ -24,16 +66,30 @@ impl CatchPanic {
 impl Handler for CatchPanic {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
         if let Err(e) = AssertUnwindSafe(ctrl.call_next(req, depot, res)).catch_unwind().await {
-            tracing::error!(error = ?e, "panic occurred");
-            res.render(
-                StatusError::internal_server_error()
-                    .brief("panic occurred on server")
-                    .cause(Error::other(format!("{e:#?}"))),
-            );
+            let message = panic_message(&*e);
+            tracing::error!(error = message, "panic occurred");
+            if let Some(hook) = &self.on_panic {
+                hook(&message, req);
+            }
+            let mut error = StatusError::internal_server_error();
+            if cfg!(debug_assertions) {
+                error = error.brief(format!("panic occurred on server: {message}"));
+            }
+            res.render(error.cause(Error::other(message)));
         }
     }
 }
 
+fn panic_message(e: &(dyn Any + Send)) -> String {
+    if let Some(message) = e.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = e.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use salvo_core::prelude::*;
@@ -54,12 +110,36 @@ mod tests {
             .hoop(CatchPanic::new())
             .push(Router::with_path("hello").get(hello));
 
-        TestClient::get("http://127.0.0.1:5801/hello")
-            .send(router)
-            .await
-            .take_string()
-            .await
-            .unwrap();
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello").send(router).await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(res.take_string().await.unwrap().contains("panic error!"));
         assert!(logs_contain("panic occurred"));
     }
+
+    #[tokio::test]
+    async fn test_catch_panic_nested_with_hook() {
+        use std::sync::{Arc, Mutex};
+
+        #[handler]
+        async fn nested(req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+            ctrl.call_next(req, depot, res).await;
+        }
+        #[handler]
+        async fn hello() -> &'static str {
+            panic!("nested panic");
+        }
+
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let catch_panic = CatchPanic::new().on_panic({
+            let messages = messages.clone();
+            move |message, req| messages.lock().unwrap().push(format!("{} {message}", req.uri().path()))
+        });
+        let router = Router::with_hoop(catch_panic).push(Router::with_hoop(nested).push(
+            Router::with_hoop(nested).push(Router::with_path("hello").get(hello)),
+        ));
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello").send(router).await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert_eq!(*messages.lock().unwrap(), vec!["/hello nested panic".to_owned()]);
+    }
 }
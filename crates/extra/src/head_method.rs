@@ -0,0 +1,155 @@
+//! Head method middleware.
+//!
+//! Makes `GET` handlers respond to `HEAD` requests automatically.
+//!
+//! Read more: <https://salvo.rs>
+use salvo_core::http::header::{HeaderValue, CONTENT_LENGTH};
+use salvo_core::http::{Method, Request, ResBody, Response};
+use salvo_core::routing::{Filter, PathState};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Router};
+
+/// Middleware routes `HEAD` request to `GET` handlers and strips the response body.
+///
+/// Route detecting happens before any middleware is called, so `HeadMethod` is also a [`Filter`]
+/// which allows `HEAD` requests to fall back to `GET` routes. The fallback is only used when no route
+/// matches `HEAD` itself, so explicit `head` handlers still take precedence, and the request method is
+/// never changed: handlers see `HEAD`. Use [`HeadMethod::attach`] to add it as both filter and hoop:
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::head_method::HeadMethod;
+///
+/// #[handler]
+/// async fn hello() -> &'static str {
+///     "hello"
+/// }
+/// let router = HeadMethod::new().attach(Router::new().push(Router::with_path("hello").get(hello)));
+/// ```
+///
+/// The `Content-Length` computed from the response body is preserved.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct HeadMethod {}
+impl HeadMethod {
+    /// Create new `HeadMethod` middleware.
+    #[inline]
+    pub fn new() -> Self {
+        HeadMethod {}
+    }
+    /// Add this middleware to router as filter and hoop, and returns the router.
+    #[inline]
+    pub fn attach(self, router: Router) -> Router {
+        router.filter(self).hoop(self)
+    }
+}
+
+impl Filter for HeadMethod {
+    #[inline]
+    fn filter(&self, req: &mut Request, state: &mut PathState) -> bool {
+        if req.method() == Method::HEAD {
+            state.enable_head_fallback();
+        }
+        true
+    }
+}
+
+#[async_trait]
+impl Handler for HeadMethod {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if req.method() != Method::HEAD {
+            return;
+        }
+        ctrl.call_next(req, depot, res).await;
+
+        let size = match &res.body {
+            ResBody::Once(bytes) => Some(bytes.len()),
+            ResBody::Chunks(chunks) => Some(chunks.iter().map(|c| c.len()).sum()),
+            _ => None,
+        };
+        if let Some(size) = size {
+            if !res.headers().contains_key(CONTENT_LENGTH) {
+                res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(size));
+            }
+        }
+        if !res.body.is_error() {
+            res.body = ResBody::None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::http::header::CONTENT_LENGTH;
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    #[tokio::test]
+    async fn test_head_method() {
+        let router = HeadMethod::new().attach(Router::new().push(Router::with_path("hello").get(hello)));
+        let service = Service::new(router);
+
+        let mut res = TestClient::head("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), "5");
+        assert!(res.take_string().await.unwrap().is_empty());
+
+        let content = TestClient::get("http://127.0.0.1:5801/hello")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_head_method_not_found() {
+        let router = HeadMethod::new().attach(Router::new().push(Router::with_path("hello").get(hello)));
+        let res = TestClient::head("http://127.0.0.1:5801/world").send(router).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
+
+    #[handler]
+    async fn explicit_head(res: &mut Response) {
+        res.add_header("x-head", "explicit", true).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_head_method_prefers_head_route() {
+        let router = HeadMethod::new().attach(
+            Router::new()
+                .push(Router::with_path("hello").get(hello).head(explicit_head))
+                .push(Router::with_path("world").get(hello)),
+        );
+        let service = Service::new(router);
+
+        let res = TestClient::head("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get("x-head").unwrap(), "explicit");
+
+        let res = TestClient::head("http://127.0.0.1:5801/world").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), "5");
+    }
+
+    #[tokio::test]
+    async fn test_head_method_keeps_sibling_head_route() {
+        let router = Router::new()
+            .push(HeadMethod::new().attach(Router::with_path("hello").get(hello)))
+            .push(Router::with_path("other").head(explicit_head));
+        let service = Service::new(router);
+
+        let res = TestClient::head("http://127.0.0.1:5801/other").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get("x-head").unwrap(), "explicit");
+
+        let res = TestClient::head("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+    }
+}
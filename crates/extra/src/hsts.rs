@@ -0,0 +1,142 @@
+//! HTTP Strict Transport Security middleware.
+//!
+//! Read more: <https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Strict-Transport-Security>
+use std::sync::Once;
+use std::time::Duration;
+
+use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::header::{HeaderValue, STRICT_TRANSPORT_SECURITY};
+use salvo_core::http::uri::Scheme;
+use salvo_core::http::{Request, Response};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+/// Min `max-age` required by browser preload lists, one year.
+pub const PRELOAD_MIN_MAX_AGE: Duration = Duration::from_secs(31536000);
+
+/// Middleware adds `Strict-Transport-Security` header to responses of https requests.
+///
+/// The header is not added to plain http requests, because browsers ignore it.
+#[non_exhaustive]
+pub struct Hsts {
+    /// The time that the browser should remember that a site is only to be accessed using HTTPS.
+    pub max_age: Duration,
+    /// Whether this rule applies to all of the site's subdomains as well.
+    pub include_subdomains: bool,
+    /// Whether add `preload` directive.
+    pub preload: bool,
+    /// Skip to add header when skipper is returns `true`.
+    pub skipper: Box<dyn Skipper>,
+    preload_checked: Once,
+}
+impl Hsts {
+    /// Create new `Hsts` middleware.
+    #[inline]
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            include_subdomains: false,
+            preload: false,
+            skipper: Box::new(none_skipper),
+            preload_checked: Once::new(),
+        }
+    }
+    /// Sets whether add `includeSubDomains` directive and returns new `Hsts`.
+    #[inline]
+    pub fn include_subdomains(mut self, include_subdomains: bool) -> Self {
+        self.include_subdomains = include_subdomains;
+        self
+    }
+    /// Sets whether add `preload` directive and returns new `Hsts`.
+    ///
+    /// Preload lists require `includeSubDomains` and `max_age` at least one year ([`PRELOAD_MIN_MAX_AGE`]),
+    /// `preload` directive will be omitted and a warning is logged once if these requirements are not satisfied.
+    ///
+    /// **NOTE:** Registration in browser preload lists is effectively irreversible: removal takes months
+    /// to reach users, and during that time all subdomains must be served over HTTPS.
+    #[inline]
+    pub fn preload(mut self, preload: bool) -> Self {
+        self.preload = preload;
+        self
+    }
+    /// Sets skipper and returns new `Hsts`.
+    #[inline]
+    pub fn skipper(mut self, skipper: impl Skipper) -> Self {
+        self.skipper = Box::new(skipper);
+        self
+    }
+
+    fn is_preloadable(&self) -> bool {
+        self.include_subdomains && self.max_age >= PRELOAD_MIN_MAX_AGE
+    }
+
+    pub(crate) fn header_value(&self) -> HeaderValue {
+        let mut value = format!("max-age={}", self.max_age.as_secs());
+        if self.include_subdomains {
+            value.push_str("; includeSubDomains");
+        }
+        if self.preload {
+            if self.is_preloadable() {
+                value.push_str("; preload");
+            } else {
+                self.preload_checked.call_once(|| {
+                    tracing::warn!(
+                        max_age = self.max_age.as_secs(),
+                        include_subdomains = self.include_subdomains,
+                        "hsts `preload` requires `includeSubDomains` and `max-age` of at least one year, it is omitted"
+                    );
+                });
+            }
+        }
+        HeaderValue::from_str(&value).expect("hsts header value should be valid")
+    }
+}
+
+#[async_trait]
+impl Handler for Hsts {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if req.scheme() != &Scheme::HTTPS || self.skipper.skipped(req, depot) {
+            return;
+        }
+        ctrl.call_next(req, depot, res).await;
+        res.headers_mut().insert(STRICT_TRANSPORT_SECURITY, self.header_value());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::TestClient;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    #[tokio::test]
+    async fn test_hsts() {
+        let router = Router::with_hoop(Hsts::new(Duration::from_secs(600)).include_subdomains(true)).get(hello);
+        let service = Service::new(router);
+
+        let res = TestClient::get("https://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(
+            res.headers().get(STRICT_TRANSPORT_SECURITY).unwrap(),
+            "max-age=600; includeSubDomains"
+        );
+
+        let res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        assert!(res.headers().get(STRICT_TRANSPORT_SECURITY).is_none());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_hsts_preload() {
+        let hsts = Hsts::new(PRELOAD_MIN_MAX_AGE).include_subdomains(true).preload(true);
+        assert_eq!(hsts.header_value(), "max-age=31536000; includeSubDomains; preload");
+        let hsts = Hsts::new(Duration::from_secs(600)).include_subdomains(true).preload(true);
+        assert_eq!(hsts.header_value(), "max-age=600; includeSubDomains");
+        assert!(logs_contain("hsts `preload` requires"));
+    }
+}
@@ -0,0 +1,336 @@
+//! IP filter middleware.
+//!
+//! Allows or denies requests according to the client IP address, using CIDR ranges such as
+//! `192.168.1.0/24` or `::1/128`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use salvo_core::prelude::*;
+//! use salvo_extra::ip_filter::IpFilter;
+//!
+//! #[handler]
+//! async fn admin() -> &'static str {
+//!     "Hello admin"
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let filter = IpFilter::allow(["10.0.0.0/8", "127.0.0.1"]).unwrap();
+//!     let router = Router::with_path("admin").hoop(filter).get(admin);
+//!     let acceptor = TcpListener::new("0.0.0.0:5800").bind().await;
+//!     Server::new(acceptor).serve(router).await;
+//! }
+//! ```
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::header::HeaderName;
+use salvo_core::http::{Request, Response, StatusError};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+/// Error returned when a CIDR string can not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseIpNetError(String);
+impl Display for ParseIpNetError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "invalid CIDR: `{}`", self.0)
+    }
+}
+impl StdError for ParseIpNetError {}
+
+/// An IP network in CIDR notation, such as `192.168.1.0/24` or `::1/128`.
+///
+/// A bare IP address is parsed as a network containing only this address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+impl IpNet {
+    /// Create new `IpNet`, returns `None` if prefix length is too long for the address.
+    #[inline]
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<Self> {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    /// Get the network address.
+    #[inline]
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Get the prefix length.
+    #[inline]
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Returns `true` if the ip address is in this network.
+    ///
+    /// IPv4-mapped IPv6 addresses are matched as IPv4 addresses.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+impl FromStr for IpNet {
+    type Err = ParseIpNetError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseIpNetError(s.to_owned());
+        let (addr, prefix) = match s.trim().split_once('/') {
+            Some((addr, prefix)) => {
+                let addr = addr.parse::<IpAddr>().map_err(|_| err())?;
+                (addr, Some(prefix.parse::<u8>().map_err(|_| err())?))
+            }
+            None => (s.trim().parse::<IpAddr>().map_err(|_| err())?, None),
+        };
+        let prefix = prefix.unwrap_or(if addr.is_ipv4() { 32 } else { 128 });
+        Self::new(addr, prefix).ok_or_else(err)
+    }
+}
+impl Display for IpNet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+fn parse_nets<I, S>(cidrs: I) -> Result<Vec<IpNet>, ParseIpNetError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    cidrs.into_iter().map(|cidr| cidr.as_ref().parse()).collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterMode {
+    Allow,
+    Deny,
+}
+
+/// Middleware that allows or denies requests by client IP address.
+///
+/// By default, the client IP is the peer address of the connection. When running behind reverse
+/// proxies, use [`IpFilter::trusted_proxies`] to read the client IP from `X-Forwarded-For` header. The
+/// header is only used if the peer address is a trusted proxy, so clients can not spoof it.
+///
+/// Requests that are denied, or not matched by the allow list, get `403 Forbidden`.
+pub struct IpFilter {
+    mode: FilterMode,
+    nets: Vec<IpNet>,
+    trusted_proxies: Vec<IpNet>,
+    forwarded_header: HeaderName,
+    skipper: Box<dyn Skipper>,
+}
+impl IpFilter {
+    fn with_mode<I, S>(mode: FilterMode, cidrs: I) -> Result<Self, ParseIpNetError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Ok(Self {
+            mode,
+            nets: parse_nets(cidrs)?,
+            trusted_proxies: vec![],
+            forwarded_header: HeaderName::from_static("x-forwarded-for"),
+            skipper: Box::new(none_skipper),
+        })
+    }
+
+    /// Create new `IpFilter` which only allows requests from the given CIDR ranges.
+    #[inline]
+    pub fn allow<I, S>(cidrs: I) -> Result<Self, ParseIpNetError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::with_mode(FilterMode::Allow, cidrs)
+    }
+
+    /// Create new `IpFilter` which denies requests from the given CIDR ranges.
+    #[inline]
+    pub fn deny<I, S>(cidrs: I) -> Result<Self, ParseIpNetError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::with_mode(FilterMode::Deny, cidrs)
+    }
+
+    /// Sets trusted proxies and returns new `IpFilter`.
+    ///
+    /// If the peer address is in these CIDR ranges, the client IP is the right-most address in
+    /// `X-Forwarded-For` header which is not a trusted proxy.
+    #[inline]
+    pub fn trusted_proxies<I, S>(mut self, cidrs: I) -> Result<Self, ParseIpNetError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.trusted_proxies = parse_nets(cidrs)?;
+        Ok(self)
+    }
+
+    /// Sets the header name used to read forwarded addresses and returns new `IpFilter`.
+    ///
+    /// Default is `X-Forwarded-For`.
+    #[inline]
+    pub fn forwarded_header(mut self, name: HeaderName) -> Self {
+        self.forwarded_header = name;
+        self
+    }
+
+    /// Sets skipper and returns new `IpFilter`.
+    #[inline]
+    pub fn skipper(mut self, skipper: impl Skipper) -> Self {
+        self.skipper = Box::new(skipper);
+        self
+    }
+
+    fn is_trusted_proxy(&self, ip: &IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+
+    /// Get the client IP address of the request.
+    pub fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        let peer = req.remote_addr().clone().into_std()?.ip();
+        if !self.is_trusted_proxy(&peer) {
+            return Some(peer);
+        }
+        let mut client = peer;
+        let forwarded = req
+            .headers()
+            .get_all(&self.forwarded_header)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+        for addr in forwarded.into_iter().rev() {
+            match addr.trim().parse::<IpAddr>() {
+                Ok(ip) => {
+                    client = ip;
+                    if !self.is_trusted_proxy(&ip) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        Some(client)
+    }
+
+    /// Returns `true` if the request from the ip address is allowed.
+    pub fn is_allowed(&self, ip: Option<&IpAddr>) -> bool {
+        let matched = ip.map(|ip| self.nets.iter().any(|net| net.contains(ip)));
+        match self.mode {
+            FilterMode::Allow => matched.unwrap_or(false),
+            FilterMode::Deny => !matched.unwrap_or(false),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for IpFilter {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if self.skipper.skipped(req, depot) {
+            return;
+        }
+        let ip = self.client_ip(req);
+        if !self.is_allowed(ip.as_ref()) {
+            res.render(StatusError::forbidden());
+            ctrl.skip_rest();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use salvo_core::prelude::*;
+    use salvo_core::test::TestClient;
+
+    use super::*;
+
+    #[handler]
+    async fn set_peer(req: &mut Request) {
+        let peer = req.header::<SocketAddr>("test-peer").unwrap();
+        *req.remote_addr_mut() = peer.into();
+    }
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    async fn status(service: &Service, peer: &str, forwarded: Option<&str>) -> Option<StatusCode> {
+        let mut client = TestClient::get("http://127.0.0.1:5801/").add_header("test-peer", peer, true);
+        if let Some(forwarded) = forwarded {
+            client = client.add_header("x-forwarded-for", forwarded, true);
+        }
+        client.send(service).await.status_code
+    }
+
+    #[test]
+    fn test_ip_net() {
+        let net: IpNet = "192.168.1.0/24".parse().unwrap();
+        assert!(net.contains(&"192.168.1.42".parse().unwrap()));
+        assert!(net.contains(&"::ffff:192.168.1.42".parse().unwrap()));
+        assert!(!net.contains(&"192.168.2.1".parse().unwrap()));
+        assert!(!net.contains(&"::1".parse().unwrap()));
+
+        let net: IpNet = "::1".parse().unwrap();
+        assert_eq!(net.to_string(), "::1/128");
+        assert!(net.contains(&"::1".parse().unwrap()));
+        assert!("0.0.0.0/0".parse::<IpNet>().unwrap().contains(&"8.8.8.8".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+        assert!("10.0.0/8".parse::<IpNet>().is_err());
+        assert!(IpFilter::allow(["10.0.0.0/8", "bad"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ip_filter() {
+        let filter = IpFilter::allow(["10.0.0.0/8", "::1/128"]).unwrap();
+        let service = Service::new(Router::with_hoop(set_peer).hoop(filter).goal(hello));
+        assert_eq!(status(&service, "10.1.2.3:80", None).await, Some(StatusCode::OK));
+        assert_eq!(status(&service, "[::1]:80", None).await, Some(StatusCode::OK));
+        assert_eq!(status(&service, "11.1.2.3:80", None).await, Some(StatusCode::FORBIDDEN));
+
+        let filter = IpFilter::deny(["192.168.0.0/16"]).unwrap();
+        let service = Service::new(Router::with_hoop(set_peer).hoop(filter).goal(hello));
+        assert_eq!(status(&service, "192.168.1.1:80", None).await, Some(StatusCode::FORBIDDEN));
+        assert_eq!(status(&service, "10.1.2.3:80", None).await, Some(StatusCode::OK));
+    }
+
+    #[tokio::test]
+    async fn test_ip_filter_trusted_proxies() {
+        let filter = IpFilter::allow(["10.0.0.0/8"])
+            .unwrap()
+            .trusted_proxies(["172.16.0.0/12"])
+            .unwrap();
+        let service = Service::new(Router::with_hoop(set_peer).hoop(filter).goal(hello));
+
+        let forwarded = Some("10.1.2.3, 172.16.0.2");
+        assert_eq!(status(&service, "172.16.0.1:80", forwarded).await, Some(StatusCode::OK));
+        let forwarded = Some("10.1.2.3, 11.1.2.3");
+        assert_eq!(status(&service, "172.16.0.1:80", forwarded).await, Some(StatusCode::FORBIDDEN));
+        // Header from untrusted peer is ignored.
+        let forwarded = Some("10.1.2.3");
+        assert_eq!(status(&service, "11.1.2.3:80", forwarded).await, Some(StatusCode::FORBIDDEN));
+    }
+}
@@ -76,3 +76,39 @@ cfg_feature! {
     #![feature = "request-id"]
     pub mod request_id;
 }
+cfg_feature! {
+    #![feature = "normalize-path"]
+    pub mod normalize_path;
+}
+cfg_feature! {
+    #![feature = "body-debug"]
+    pub mod body_debug;
+}
+cfg_feature! {
+    #![feature = "head-method"]
+    pub mod head_method;
+}
+cfg_feature! {
+    #![feature = "start-time"]
+    pub mod start_time;
+}
+cfg_feature! {
+    #![feature = "request-size"]
+    pub mod request_size;
+}
+cfg_feature! {
+    #![feature = "hsts"]
+    pub mod hsts;
+}
+cfg_feature! {
+    #![feature = "www-redirect"]
+    pub mod www_redirect;
+}
+cfg_feature! {
+    #![feature = "security-headers"]
+    pub mod security_headers;
+}
+cfg_feature! {
+    #![feature = "ip-filter"]
+    pub mod ip_filter;
+}
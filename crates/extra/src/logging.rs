@@ -1,11 +1,18 @@
-//! Simple logging middleware.
+//! Logging middlewares.
+//!
+//! [`Logger`] is a simple logger, [`AccessLog`] writes structured access logs.
 //!
 //! Read more: <https://salvo.rs>
-use std::time::Instant;
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use time::OffsetDateTime;
 use tracing::{Instrument, Level};
 
-use salvo_core::http::{Request, ResBody, Response, StatusCode};
+use salvo_core::http::header::{REFERER, USER_AGENT};
+use salvo_core::http::{Method, Request, ResBody, Response, StatusCode, Version};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
 /// A simple logger middleware.
@@ -36,11 +43,7 @@ impl Handler for Logger {
             ctrl.call_next(req, depot, res).await;
             let duration = now.elapsed();
 
-            let status = res.status_code.unwrap_or(match &res.body {
-                ResBody::None => StatusCode::NOT_FOUND,
-                ResBody::Error(e) => e.code,
-                _ => StatusCode::OK,
-            });
+            let status = response_status(res);
             tracing::info!(
                 %status,
                 ?duration,
@@ -52,6 +55,193 @@ impl Handler for Logger {
     }
 }
 
+fn response_status(res: &Response) -> StatusCode {
+    res.status_code.unwrap_or(match &res.body {
+        ResBody::None => StatusCode::NOT_FOUND,
+        ResBody::Error(e) => e.code,
+        _ => StatusCode::OK,
+    })
+}
+
+/// A record of a handled request, used by [`AccessLog`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct LogRecord {
+    /// The time when the request is received.
+    pub time: OffsetDateTime,
+    /// The request method.
+    pub method: Method,
+    /// The request uri.
+    pub uri: String,
+    /// The request http version.
+    pub version: Version,
+    /// The response status code.
+    pub status: StatusCode,
+    /// The response body size, `None` if the body is streamed.
+    pub size: Option<u64>,
+    /// The time spent by the rest handlers.
+    pub latency: Duration,
+    /// The client ip address.
+    pub client_ip: Option<String>,
+    /// The `Referer` header.
+    pub referer: Option<String>,
+    /// The `User-Agent` header.
+    pub user_agent: Option<String>,
+    /// The request id generated by `RequestId` middleware.
+    pub request_id: Option<String>,
+}
+
+impl LogRecord {
+    /// Format the record in Apache combined log format.
+    ///
+    /// `"` and `\` in the quoted uri, referer and user agent are escaped with `\`, and control characters are
+    /// replaced with `\xHH`, so a client can not forge log lines or fields.
+    pub fn to_combined(&self) -> String {
+        let time = self.time;
+        format!(
+            r#"{} - - [{:02}/{}/{}:{:02}:{:02}:{:02} +0000] "{} {} {:?}" {} {} "{}" "{}""#,
+            self.client_ip.as_deref().unwrap_or("-"),
+            time.day(),
+            &time.month().to_string()[..3],
+            time.year(),
+            time.hour(),
+            time.minute(),
+            time.second(),
+            self.method,
+            escape(&self.uri),
+            self.version,
+            self.status.as_u16(),
+            self.size.map(|s| s.to_string()).unwrap_or_else(|| "-".into()),
+            escape(self.referer.as_deref().unwrap_or("-")),
+            escape(self.user_agent.as_deref().unwrap_or("-")),
+        )
+    }
+    /// Format the record as a JSON object.
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "time": self.time.unix_timestamp(),
+            "method": self.method.as_str(),
+            "uri": self.uri,
+            "status": self.status.as_u16(),
+            "size": self.size,
+            "latency_ms": self.latency.as_secs_f64() * 1000.0,
+            "client_ip": self.client_ip,
+            "referer": self.referer,
+            "user_agent": self.user_agent,
+            "request_id": self.request_id,
+        })
+        .to_string()
+    }
+}
+
+/// Escapes a value in quoted field of combined log format.
+fn escape(value: &str) -> Cow<'_, str> {
+    if !value.chars().any(|c| c == '"' || c == '\\' || c.is_control()) {
+        return Cow::Borrowed(value);
+    }
+    let mut escaped = String::with_capacity(value.len() + 8);
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() => {
+                for b in c.encode_utf8(&mut [0; 4]).bytes() {
+                    let _ = write!(escaped, "\\x{b:02x}");
+                }
+            }
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Format of [`AccessLog`].
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum LogFormat {
+    /// Apache combined log format.
+    #[default]
+    Combined,
+    /// JSON object per line.
+    Json,
+    /// Custom formatter.
+    Custom(Arc<dyn Fn(&LogRecord) -> String + Send + Sync>),
+}
+impl Debug for LogFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Combined => f.write_str("Combined"),
+            Self::Json => f.write_str("Json"),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+impl LogFormat {
+    fn format(&self, record: &LogRecord) -> String {
+        match self {
+            Self::Combined => record.to_combined(),
+            Self::Json => record.to_json(),
+            Self::Custom(formatter) => formatter(record),
+        }
+    }
+}
+
+/// Access log middleware, writes a line for every request with `tracing::info!`, so log level and
+/// output are controlled by the tracing subscriber.
+///
+/// Latency is measured from entering this middleware to the rest handlers finished, the time used
+/// to write response body to the connection is not included.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct AccessLog {
+    /// Log format.
+    pub format: LogFormat,
+}
+impl AccessLog {
+    /// Create new `AccessLog` middleware with combined format.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Sets log format and returns new `AccessLog`.
+    #[inline]
+    pub fn format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+#[async_trait]
+impl Handler for AccessLog {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let time = OffsetDateTime::now_utc();
+        let now = Instant::now();
+        ctrl.call_next(req, depot, res).await;
+        let latency = now.elapsed();
+
+        #[cfg(feature = "request-id")]
+        let request_id = crate::request_id::RequestId::get(depot).map(ToOwned::to_owned);
+        #[cfg(not(feature = "request-id"))]
+        let request_id = None;
+        let record = LogRecord {
+            time,
+            method: req.method().clone(),
+            uri: req.uri().to_string(),
+            version: req.version(),
+            status: response_status(res),
+            size: res.body.size(),
+            latency,
+            client_ip: req.remote_addr().clone().into_std().map(|addr| addr.ip().to_string()),
+            referer: req.header(REFERER),
+            user_agent: req.header(USER_AGENT),
+            request_id,
+        };
+        tracing::info!("{}", self.format.format(&record));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use salvo_core::prelude::*;
@@ -80,4 +270,72 @@ mod tests {
             .unwrap();
         assert!(logs_contain("duration"));
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_access_log_json() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let router = Router::new()
+            .hoop(AccessLog::new().format(LogFormat::Json))
+            .push(Router::with_path("hello").get(hello));
+        TestClient::get("http://127.0.0.1:5801/hello?a=1")
+            .add_header("user-agent", "test-agent", true)
+            .send(router)
+            .await;
+        assert!(logs_contain(r#""uri":"http://127.0.0.1:5801/hello?a=1""#));
+        assert!(logs_contain(r#""status":200"#));
+        assert!(logs_contain(r#""size":5"#));
+        assert!(logs_contain(r#""user_agent":"test-agent""#));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_access_log_custom() {
+        #[handler]
+        async fn forbidden(res: &mut Response) {
+            res.status_code(StatusCode::FORBIDDEN);
+        }
+
+        let router = Router::new()
+            .hoop(AccessLog::new().format(LogFormat::Custom(Arc::new(|record| {
+                format!("custom {} {}", record.method, record.status.as_u16())
+            }))))
+            .get(forbidden);
+        TestClient::get("http://127.0.0.1:5801/").send(router).await;
+        assert!(logs_contain("custom GET 403"));
+    }
+
+    #[test]
+    fn test_log_record_combined() {
+        let record = LogRecord {
+            time: OffsetDateTime::from_unix_timestamp(971211336).unwrap(),
+            method: Method::GET,
+            uri: "/apache_pb.gif".into(),
+            version: Version::HTTP_11,
+            status: StatusCode::OK,
+            size: Some(2326),
+            latency: Duration::from_millis(3),
+            client_ip: Some("127.0.0.1".into()),
+            referer: None,
+            user_agent: Some("curl/8.0".into()),
+            request_id: None,
+        };
+        assert_eq!(
+            record.to_combined(),
+            r#"127.0.0.1 - - [10/Oct/2000:20:55:36 +0000] "GET /apache_pb.gif HTTP/1.1" 200 2326 "-" "curl/8.0""#
+        );
+        let record = LogRecord {
+            referer: Some("http://a.com/\\path".into()),
+            user_agent: Some("evil\" 200 \"-\tagent\n".into()),
+            ..record
+        };
+        assert_eq!(
+            record.to_combined(),
+            r#"127.0.0.1 - - [10/Oct/2000:20:55:36 +0000] "GET /apache_pb.gif HTTP/1.1" 200 2326 "http://a.com/\\path" "evil\" 200 \"-\x09agent\x0a""#
+        );
+    }
 }
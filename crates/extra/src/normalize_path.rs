@@ -0,0 +1,214 @@
+//! Normalize path filter.
+//!
+//! Unlike [`TrailingSlash`](crate::trailing_slash::TrailingSlash), this filter rewrites request uri in place
+//! instead of redirecting, so the normalization is transparent to handlers.
+//!
+//! Route detecting happens before any middleware is called, so `NormalizePath` is a [`Filter`] which rewrites
+//! the uri before the routes are matched. Use [`NormalizePath::attach`] to add it to the root router:
+//!
+//! ```
+//! use salvo_core::prelude::*;
+//! use salvo_extra::normalize_path::NormalizePath;
+//!
+//! #[handler]
+//! async fn show_user() -> &'static str {
+//!     "user"
+//! }
+//! let router = NormalizePath::new().attach(Router::new().push(Router::with_path("users/<id>").get(show_user)));
+//! ```
+//!
+//! Read more: <https://salvo.rs>
+use std::borrow::Cow;
+use std::fmt::{self, Formatter};
+use std::str::FromStr;
+
+use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::uri::{PathAndQuery, Uri};
+use salvo_core::routing::{Filter, PathState};
+use salvo_core::{Depot, Request, Router};
+
+/// Filter for normalize request path.
+///
+/// By default, it collapses consecutive slashes, such as `//users//123` will be rewritten to `/users/123`.
+#[non_exhaustive]
+pub struct NormalizePath {
+    /// Collapse consecutive slashes to one slash.
+    pub collapse_slashes: bool,
+    /// Remove trailing slash, the root path `/` will not be changed.
+    pub trim_trailing_slash: bool,
+    /// Skip to normalize path when skipper is returns `true`.
+    ///
+    /// Filters have no access to the request's depot, so the skipper always gets an empty [`Depot`], only
+    /// skippers which check the [`Request`] are useful here.
+    pub skipper: Box<dyn Skipper>,
+}
+impl Default for NormalizePath {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl NormalizePath {
+    /// Create new `NormalizePath`.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            collapse_slashes: true,
+            trim_trailing_slash: false,
+            skipper: Box::new(none_skipper),
+        }
+    }
+    /// Sets whether collapse consecutive slashes and returns new `NormalizePath`.
+    #[inline]
+    pub fn collapse_slashes(mut self, collapse_slashes: bool) -> Self {
+        self.collapse_slashes = collapse_slashes;
+        self
+    }
+    /// Sets whether remove trailing slash and returns new `NormalizePath`.
+    #[inline]
+    pub fn trim_trailing_slash(mut self, trim_trailing_slash: bool) -> Self {
+        self.trim_trailing_slash = trim_trailing_slash;
+        self
+    }
+    /// Sets skipper and returns new `NormalizePath`.
+    ///
+    /// The skipper always gets an empty [`Depot`], because filters have no access to the request's depot.
+    #[inline]
+    pub fn skipper(mut self, skipper: impl Skipper) -> Self {
+        self.skipper = Box::new(skipper);
+        self
+    }
+
+    /// Add this filter to router as its first filter, and returns the router.
+    #[inline]
+    pub fn attach(self, mut router: Router) -> Router {
+        router.filters_mut().insert(0, Box::new(self));
+        router
+    }
+
+    fn normalize<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        let mut path = Cow::Borrowed(path);
+        if self.collapse_slashes && path.contains("//") {
+            let mut collapsed = String::with_capacity(path.len());
+            for c in path.chars() {
+                if c != '/' || !collapsed.ends_with('/') {
+                    collapsed.push(c);
+                }
+            }
+            path = Cow::Owned(collapsed);
+        }
+        if self.trim_trailing_slash && path.len() > 1 && path.ends_with('/') {
+            let trimmed = path.trim_end_matches('/');
+            path = Cow::Owned(if trimmed.is_empty() {
+                "/".to_owned()
+            } else {
+                trimmed.to_owned()
+            });
+        }
+        path
+    }
+}
+
+impl Filter for NormalizePath {
+    fn filter(&self, req: &mut Request, state: &mut PathState) -> bool {
+        // The depot is not available while detecting routes.
+        if self.skipper.skipped(req, &Depot::new()) {
+            return true;
+        }
+        let new_uri = match self.normalize(req.uri().path()) {
+            Cow::Borrowed(_) => None,
+            Cow::Owned(path) => replace_uri_path(req.uri(), &path),
+        };
+        if let Some(new_uri) = new_uri {
+            state.reset_path(new_uri.path());
+            *req.uri_mut() = new_uri;
+        }
+        true
+    }
+}
+impl fmt::Debug for NormalizePath {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("NormalizePath")
+            .field("collapse_slashes", &self.collapse_slashes)
+            .field("trim_trailing_slash", &self.trim_trailing_slash)
+            .finish()
+    }
+}
+
+fn replace_uri_path(original_uri: &Uri, new_path: &str) -> Option<Uri> {
+    let mut uri_parts = original_uri.clone().into_parts();
+    let path = match original_uri.query() {
+        Some(query) => Cow::from(format!("{new_path}?{query}")),
+        None => Cow::from(new_path),
+    };
+    uri_parts.path_and_query = Some(PathAndQuery::from_str(path.as_ref()).ok()?);
+    Uri::from_parts(uri_parts).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn echo(req: &mut Request) -> String {
+        req.uri().path_and_query().map(|p| p.to_string()).unwrap_or_default()
+    }
+
+    async fn access(service: &Service, path: &str) -> String {
+        TestClient::get(format!("http://127.0.0.1:5800{path}"))
+            .send(service)
+            .await
+            .take_string()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_collapse_slashes() {
+        let router = NormalizePath::new().attach(Router::new().push(Router::with_path("<**rest>").get(echo)));
+        let service = Service::new(router);
+        assert_eq!(access(&service, "//users//123").await, "/users/123");
+        assert_eq!(access(&service, "///api?a=1").await, "/api?a=1");
+        assert_eq!(access(&service, "/api/").await, "/api/");
+    }
+
+    #[tokio::test]
+    async fn test_trim_trailing_slash() {
+        let router = NormalizePath::new()
+            .trim_trailing_slash(true)
+            .attach(Router::new().push(Router::with_path("<**rest>").get(echo)));
+        let service = Service::new(router);
+        assert_eq!(access(&service, "/users//123//").await, "/users/123");
+        assert_eq!(access(&service, "/api/?a=1").await, "/api?a=1");
+    }
+
+    #[tokio::test]
+    async fn test_routing() {
+        #[handler]
+        async fn show_user(req: &mut Request) -> String {
+            format!("user {}", req.param::<String>("id").unwrap_or_default())
+        }
+        let router = NormalizePath::new().trim_trailing_slash(true).attach(
+            Router::with_path("users")
+                .push(Router::with_path("<id>").get(show_user))
+                .push(Router::with_path("<**rest>").get(echo)),
+        );
+        let service = Service::new(router);
+        assert_eq!(access(&service, "//users//123").await, "user 123");
+        assert_eq!(access(&service, "/users/123//").await, "user 123");
+        let res = TestClient::get("http://127.0.0.1:5800//other//").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let normalizer = NormalizePath::new().collapse_slashes(false).trim_trailing_slash(true);
+        assert_eq!(normalizer.normalize("//users/"), "//users");
+        assert_eq!(normalizer.normalize("/"), "/");
+        assert!(matches!(NormalizePath::new().normalize("/users/1"), Cow::Borrowed(_)));
+    }
+}
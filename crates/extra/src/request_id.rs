@@ -2,13 +2,16 @@
 //!
 //! Read more: <https://salvo.rs>
 use ulid::Ulid;
+use uuid::Uuid;
 
-use salvo_core::http::{header::HeaderName, Request, Response};
+use salvo_core::http::{header::HeaderName, Request, Response, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
-/// Key for incoming flash messages in depot.
+/// Key for request id in depot.
 pub const REQUST_ID_KEY: &str = "::salvo::request_id";
 
+type BoxedValidator = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
 /// A middleware for generate request id.
 #[non_exhaustive]
 pub struct RequestId {
@@ -18,18 +21,26 @@ pub struct RequestId {
     pub overwrite: bool,
     /// The generator for request id.
     pub generator: Box<dyn IdGenerator + Send + Sync>,
+    /// The validator for incoming request id, requests with invalid id are rejected with `400 Bad Request`.
+    pub validator: Option<BoxedValidator>,
 }
 
 impl RequestId {
-    /// Create new `CatchPanic` middleware.
+    /// Create new `RequestId` middleware.
     pub fn new() -> Self {
         Self {
             header_name: HeaderName::from_static("x-request-id"),
             overwrite: true,
             generator: Box::new(UlidGenerator::new()),
+            validator: None,
         }
     }
 
+    /// Get the request id stored in depot by this middleware.
+    pub fn get(depot: &Depot) -> Option<&str> {
+        depot.get::<String>(REQUST_ID_KEY).ok().map(|id| id.as_str())
+    }
+
     /// Set the header name for request id.
     pub fn header_name(mut self, name: HeaderName) -> Self {
         self.header_name = name;
@@ -37,6 +48,8 @@ impl RequestId {
     }
 
     /// Set whether overwrite exists request id. Default is `true`.
+    ///
+    /// If `overwrite` is `false`, the incoming request id is used if present and valid.
     pub fn overwrite(mut self, overwrite: bool) -> Self {
         self.overwrite = overwrite;
         self
@@ -47,6 +60,15 @@ impl RequestId {
         self.generator = Box::new(generator);
         self
     }
+
+    /// Set the validator for incoming request id.
+    ///
+    /// The validator is used only when `overwrite` is `false`, requests with invalid id
+    /// are rejected instead of generating a new id.
+    pub fn validator(mut self, validator: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
 }
 
 impl Default for RequestId {
@@ -85,14 +107,98 @@ impl IdGenerator for UlidGenerator {
     }
 }
 
+/// A generator for generate request id with uuid v4.
+#[derive(Default, Debug)]
+pub struct UuidGenerator {}
+impl UuidGenerator {
+    /// Create new `UuidGenerator`.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl IdGenerator for UuidGenerator {
+    fn generate(&self, _req: &mut Request, _depot: &mut Depot) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
 #[async_trait]
 impl Handler for RequestId {
-    async fn handle(&self, req: &mut Request, depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
-        if !self.overwrite && req.headers().contains_key(REQUST_ID_KEY) {
-            return;
-        }
-        let id = self.generator.generate(req, depot);
-        req.add_header(self.header_name.clone(), &id, true).ok();
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let incoming = if self.overwrite {
+            None
+        } else {
+            req.header::<String>(&self.header_name)
+        };
+        let id = match incoming {
+            Some(id) => {
+                if let Some(validator) = &self.validator {
+                    if !validator(&id) {
+                        res.render(StatusError::bad_request().brief("Invalid request id."));
+                        ctrl.skip_rest();
+                        return;
+                    }
+                }
+                id
+            }
+            None => {
+                let id = self.generator.generate(req, depot);
+                req.add_header(self.header_name.clone(), &id, true).ok();
+                id
+            }
+        };
+        res.add_header(self.header_name.clone(), &id, true).ok();
         depot.insert(REQUST_ID_KEY, id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn show_id(depot: &mut Depot) -> String {
+        RequestId::get(depot).unwrap_or_default().to_owned()
+    }
+
+    #[tokio::test]
+    async fn test_request_id() {
+        let service = Service::new(Router::with_hoop(RequestId::new().generator(UuidGenerator::new())).get(show_id));
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-request-id", "client-id", true)
+            .send(&service)
+            .await;
+        let header = res.headers().get("x-request-id").unwrap().to_str().unwrap().to_owned();
+        assert!(Uuid::parse_str(&header).is_ok());
+        assert_eq!(res.take_string().await.unwrap(), header);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_reuse_incoming() {
+        let request_id = RequestId::new()
+            .overwrite(false)
+            .validator(|id| id.len() <= 16 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        let service = Service::new(Router::with_hoop(request_id).get(show_id));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-request-id", "client-id", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get("x-request-id").unwrap(), "client-id");
+        assert_eq!(res.take_string().await.unwrap(), "client-id");
+
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-request-id", "bad id!", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        let header = res.headers().get("x-request-id").unwrap().to_str().unwrap().to_owned();
+        assert!(Ulid::from_string(&header).is_ok());
+        assert_eq!(res.take_string().await.unwrap(), header);
+    }
+}
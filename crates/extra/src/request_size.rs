@@ -0,0 +1,142 @@
+//! Request size limit middleware.
+//!
+//! Limits URI length and headers of request, it is purely defensive against malformed or malicious clients.
+//! Use [`max_size`](crate::size_limiter::max_size) to limit request body size.
+//!
+//! Read more: <https://salvo.rs>
+use salvo_core::http::{Request, Response, StatusError};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+/// Default max bytes of request uri.
+pub const DEFAULT_MAX_URI_BYTES: usize = 8192;
+/// Default max count of request headers.
+pub const DEFAULT_MAX_HEADER_COUNT: usize = 100;
+/// Default max bytes of each request header value.
+pub const DEFAULT_MAX_HEADER_VALUE_BYTES: usize = 8192;
+
+/// Middleware for limit request uri length and headers.
+///
+/// Returns `414 URI Too Long` if uri is too long, and `431 Request Header Fields Too Large`
+/// if there are too many headers or any header value is too large.
+///
+/// It should be added as service hoop, so it runs before any other middleware:
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::request_size::RequestSizeLimit;
+///
+/// let service = Service::new(Router::new()).hoop(RequestSizeLimit::new().max_uri_bytes(4096));
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct RequestSizeLimit {
+    /// Max bytes of request uri.
+    pub max_uri_bytes: usize,
+    /// Max count of request headers.
+    pub max_header_count: usize,
+    /// Max bytes of each request header value.
+    pub max_header_value_bytes: usize,
+}
+impl Default for RequestSizeLimit {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl RequestSizeLimit {
+    /// Create new `RequestSizeLimit` with default limits.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            max_uri_bytes: DEFAULT_MAX_URI_BYTES,
+            max_header_count: DEFAULT_MAX_HEADER_COUNT,
+            max_header_value_bytes: DEFAULT_MAX_HEADER_VALUE_BYTES,
+        }
+    }
+    /// Sets max bytes of request uri and returns new `RequestSizeLimit`.
+    #[inline]
+    pub fn max_uri_bytes(mut self, max_uri_bytes: usize) -> Self {
+        self.max_uri_bytes = max_uri_bytes;
+        self
+    }
+    /// Sets max count of request headers and returns new `RequestSizeLimit`.
+    #[inline]
+    pub fn max_header_count(mut self, max_header_count: usize) -> Self {
+        self.max_header_count = max_header_count;
+        self
+    }
+    /// Sets max bytes of each request header value and returns new `RequestSizeLimit`.
+    #[inline]
+    pub fn max_header_value_bytes(mut self, max_header_value_bytes: usize) -> Self {
+        self.max_header_value_bytes = max_header_value_bytes;
+        self
+    }
+
+    fn check(&self, req: &Request) -> Result<(), StatusError> {
+        let uri_len = req.uri().path_and_query().map(|p| p.as_str().len()).unwrap_or_default();
+        if uri_len > self.max_uri_bytes {
+            return Err(StatusError::uri_too_long());
+        }
+        let headers = req.headers();
+        if headers.len() > self.max_header_count {
+            return Err(StatusError::request_header_fields_toolarge().brief("Too many request headers."));
+        }
+        if headers.values().any(|v| v.len() > self.max_header_value_bytes) {
+            return Err(StatusError::request_header_fields_toolarge().brief("Request header value is too large."));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for RequestSizeLimit {
+    async fn handle(&self, req: &mut Request, _depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if let Err(e) = self.check(req) {
+            res.render(e);
+            ctrl.skip_rest();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::TestClient;
+
+    use super::*;
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    #[tokio::test]
+    async fn test_request_size_limit() {
+        let limit = RequestSizeLimit::new()
+            .max_uri_bytes(16)
+            .max_header_count(4)
+            .max_header_value_bytes(8);
+        let service = Service::new(Router::with_path("<**>").get(hello)).hoop(limit);
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello?name=salvo&a=b")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::URI_TOO_LONG));
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("x-value", "too long value", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE));
+
+        let mut client = TestClient::get("http://127.0.0.1:5801/hello");
+        for name in ["x-h0", "x-h1", "x-h2", "x-h3", "x-h4"] {
+            client = client.add_header(name, "v", true);
+        }
+        let res = client.send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE));
+    }
+}
@@ -0,0 +1,296 @@
+//! Security headers middleware.
+//!
+//! Sets common security related headers, such as `Strict-Transport-Security`, `X-Frame-Options`,
+//! `X-Content-Type-Options` and `Content-Security-Policy`.
+//!
+//! Read more: <https://owasp.org/www-project-secure-headers/>
+use std::fmt::Display;
+
+use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::header::{
+    HeaderName, HeaderValue, CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY,
+    X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS, X_XSS_PROTECTION,
+};
+use salvo_core::http::uri::Scheme;
+use salvo_core::http::{Request, Response};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+use crate::hsts::Hsts;
+
+/// Value of `X-Frame-Options` header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FrameOptions {
+    /// The page can not be displayed in a frame.
+    Deny,
+    /// The page can only be displayed in a frame on the same origin.
+    SameOrigin,
+}
+impl FrameOptions {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Deny => "DENY",
+            Self::SameOrigin => "SAMEORIGIN",
+        }
+    }
+}
+
+/// Value of `Referrer-Policy` header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReferrerPolicy {
+    /// `no-referrer`
+    NoReferrer,
+    /// `no-referrer-when-downgrade`
+    NoReferrerWhenDowngrade,
+    /// `origin`
+    Origin,
+    /// `origin-when-cross-origin`
+    OriginWhenCrossOrigin,
+    /// `same-origin`
+    SameOrigin,
+    /// `strict-origin`
+    StrictOrigin,
+    /// `strict-origin-when-cross-origin`
+    StrictOriginWhenCrossOrigin,
+    /// `unsafe-url`
+    UnsafeUrl,
+}
+impl ReferrerPolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoReferrer => "no-referrer",
+            Self::NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+            Self::Origin => "origin",
+            Self::OriginWhenCrossOrigin => "origin-when-cross-origin",
+            Self::SameOrigin => "same-origin",
+            Self::StrictOrigin => "strict-origin",
+            Self::StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+            Self::UnsafeUrl => "unsafe-url",
+        }
+    }
+}
+
+/// Middleware sets security headers on every response except redirects.
+///
+/// By default, `X-Content-Type-Options: nosniff` and `X-Frame-Options: DENY` are set. Headers already set
+/// by handlers are not overwritten. `Strict-Transport-Security` is only added to responses of https requests.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use salvo_core::http::CspDirectives;
+/// use salvo_core::prelude::*;
+/// use salvo_extra::hsts::Hsts;
+/// use salvo_extra::security_headers::{FrameOptions, SecurityHeaders};
+///
+/// let security_headers = SecurityHeaders::new()
+///     .hsts(Hsts::new(Duration::from_secs(31536000)).include_subdomains(true))
+///     .frame_options(FrameOptions::SameOrigin)
+///     .content_security_policy(CspDirectives::new().default_src(&["'self'"]));
+/// let router = Router::with_hoop(security_headers);
+/// ```
+#[non_exhaustive]
+pub struct SecurityHeaders {
+    /// Value of `Strict-Transport-Security` header.
+    pub hsts: Option<HeaderValue>,
+    /// Value of `X-Frame-Options` header.
+    pub frame_options: Option<FrameOptions>,
+    /// Whether add `X-Content-Type-Options: nosniff` header.
+    pub content_type_nosniff: bool,
+    /// Whether add `X-XSS-Protection: 1; mode=block` header.
+    pub xss_protection: bool,
+    /// Value of `Referrer-Policy` header.
+    pub referrer_policy: Option<ReferrerPolicy>,
+    /// Value of `Content-Security-Policy` header.
+    pub content_security_policy: Option<HeaderValue>,
+    /// Skip to add headers when skipper is returns `true`.
+    pub skipper: Box<dyn Skipper>,
+}
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl SecurityHeaders {
+    /// Create new `SecurityHeaders` middleware.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            hsts: None,
+            frame_options: Some(FrameOptions::Deny),
+            content_type_nosniff: true,
+            xss_protection: false,
+            referrer_policy: None,
+            content_security_policy: None,
+            skipper: Box::new(none_skipper),
+        }
+    }
+    /// Sets `Strict-Transport-Security` header built by [`Hsts`] and returns new `SecurityHeaders`.
+    ///
+    /// The `preload` directive is omitted if its requirements are not satisfied, see [`Hsts::preload`].
+    #[inline]
+    pub fn hsts(mut self, hsts: Hsts) -> Self {
+        self.hsts = Some(hsts.header_value());
+        self
+    }
+    /// Sets `X-Frame-Options` header and returns new `SecurityHeaders`.
+    #[inline]
+    pub fn frame_options(mut self, frame_options: FrameOptions) -> Self {
+        self.frame_options = Some(frame_options);
+        self
+    }
+    /// Sets whether add `X-Content-Type-Options: nosniff` header and returns new `SecurityHeaders`.
+    #[inline]
+    pub fn content_type_nosniff(mut self, content_type_nosniff: bool) -> Self {
+        self.content_type_nosniff = content_type_nosniff;
+        self
+    }
+    /// Sets whether add `X-XSS-Protection: 1; mode=block` header and returns new `SecurityHeaders`.
+    ///
+    /// This header is ignored by modern browsers, prefer `Content-Security-Policy`.
+    #[inline]
+    pub fn xss_protection(mut self, xss_protection: bool) -> Self {
+        self.xss_protection = xss_protection;
+        self
+    }
+    /// Sets `Referrer-Policy` header and returns new `SecurityHeaders`.
+    #[inline]
+    pub fn referrer_policy(mut self, referrer_policy: ReferrerPolicy) -> Self {
+        self.referrer_policy = Some(referrer_policy);
+        self
+    }
+    /// Sets `Content-Security-Policy` header and returns new `SecurityHeaders`.
+    ///
+    /// Accepts a [`CspDirectives`](salvo_core::http::CspDirectives) or a raw policy string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the policy is not a valid header value.
+    #[inline]
+    pub fn content_security_policy(mut self, policy: impl Display) -> Self {
+        self.content_security_policy =
+            Some(HeaderValue::from_str(&policy.to_string()).expect("content security policy should be valid"));
+        self
+    }
+    /// Sets skipper and returns new `SecurityHeaders`.
+    #[inline]
+    pub fn skipper(mut self, skipper: impl Skipper) -> Self {
+        self.skipper = Box::new(skipper);
+        self
+    }
+}
+
+fn insert_if_absent(res: &mut Response, name: HeaderName, value: HeaderValue) {
+    res.headers_mut().entry(name).or_insert(value);
+}
+
+#[async_trait]
+impl Handler for SecurityHeaders {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if self.skipper.skipped(req, depot) {
+            return;
+        }
+        ctrl.call_next(req, depot, res).await;
+        if res.status_code.map(|s| s.is_redirection()).unwrap_or(false) {
+            return;
+        }
+        if let Some(hsts) = &self.hsts {
+            if req.scheme() == &Scheme::HTTPS {
+                insert_if_absent(res, STRICT_TRANSPORT_SECURITY, hsts.clone());
+            }
+        }
+        if let Some(frame_options) = self.frame_options {
+            insert_if_absent(res, X_FRAME_OPTIONS, HeaderValue::from_static(frame_options.as_str()));
+        }
+        if self.content_type_nosniff {
+            insert_if_absent(res, X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+        }
+        if self.xss_protection {
+            insert_if_absent(res, X_XSS_PROTECTION, HeaderValue::from_static("1; mode=block"));
+        }
+        if let Some(referrer_policy) = self.referrer_policy {
+            insert_if_absent(res, REFERRER_POLICY, HeaderValue::from_static(referrer_policy.as_str()));
+        }
+        if let Some(csp) = &self.content_security_policy {
+            insert_if_absent(res, CONTENT_SECURITY_POLICY, csp.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use salvo_core::http::CspDirectives;
+    use salvo_core::prelude::*;
+    use salvo_core::test::TestClient;
+
+    use super::*;
+    use crate::hsts::PRELOAD_MIN_MAX_AGE;
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+    #[handler]
+    async fn redirect(res: &mut Response) {
+        res.render(Redirect::found("/"));
+    }
+
+    #[tokio::test]
+    async fn test_security_headers() {
+        let security_headers = SecurityHeaders::new()
+            .hsts(Hsts::new(Duration::from_secs(600)).include_subdomains(true).preload(true))
+            .frame_options(FrameOptions::SameOrigin)
+            .xss_protection(true)
+            .referrer_policy(ReferrerPolicy::NoReferrer)
+            .content_security_policy(
+                CspDirectives::new()
+                    .default_src(&["'self'"])
+                    .script_src(&["'self'", "https://cdn.example.com"])
+                    .upgrade_insecure_requests(),
+            );
+        let router = Router::with_hoop(security_headers)
+            .push(Router::with_path("hello").get(hello))
+            .push(Router::with_path("redirect").get(redirect));
+        let service = Service::new(router);
+
+        let res = TestClient::get("https://127.0.0.1:5801/hello").send(&service).await;
+        let headers = res.headers();
+        assert_eq!(
+            headers.get(STRICT_TRANSPORT_SECURITY).unwrap(),
+            "max-age=600; includeSubDomains"
+        );
+        assert_eq!(headers.get(X_FRAME_OPTIONS).unwrap(), "SAMEORIGIN");
+        assert_eq!(headers.get(X_CONTENT_TYPE_OPTIONS).unwrap(), "nosniff");
+        assert_eq!(headers.get(X_XSS_PROTECTION).unwrap(), "1; mode=block");
+        assert_eq!(headers.get(REFERRER_POLICY).unwrap(), "no-referrer");
+        assert_eq!(
+            headers.get(CONTENT_SECURITY_POLICY).unwrap(),
+            "default-src 'self'; script-src 'self' https://cdn.example.com; upgrade-insecure-requests"
+        );
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello").send(&service).await;
+        assert!(res.headers().get(STRICT_TRANSPORT_SECURITY).is_none());
+        assert_eq!(res.headers().get(X_FRAME_OPTIONS).unwrap(), "SAMEORIGIN");
+
+        let res = TestClient::get("https://127.0.0.1:5801/redirect").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::FOUND));
+        assert!(res.headers().get(X_FRAME_OPTIONS).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_security_headers_hsts_preload() {
+        let security_headers =
+            SecurityHeaders::new().hsts(Hsts::new(PRELOAD_MIN_MAX_AGE).include_subdomains(true).preload(true));
+        let service = Service::new(Router::with_hoop(security_headers).get(hello));
+        let res = TestClient::get("https://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(
+            res.headers().get(STRICT_TRANSPORT_SECURITY).unwrap(),
+            "max-age=31536000; includeSubDomains; preload"
+        );
+    }
+}
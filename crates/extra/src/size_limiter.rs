@@ -1,27 +1,36 @@
 //! size limiter middleware.
 //!
 //! Read more: <https://salvo.rs>
-use salvo_core::http::StatusError;
-use salvo_core::http::{Body, Request, Response};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use salvo_core::http::{mime, StatusError};
+use salvo_core::http::{Body, ReqBody, Request, Response};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
-/// MaxSize
+/// Middleware limits request body size, returns `413 Payload Too Large` if body size exceeds the limit.
+///
+/// If body size is unknown (chunked body), the body is still streamed to handler, reading fails once
+/// the limit is exceeded, and the response is replaced with `413 Payload Too Large`.
 pub struct MaxSize(pub u64);
+impl MaxSize {
+    /// Create a new `MaxSizePerContentType` which uses different limits for json, multipart
+    /// and urlencoded form bodies.
+    #[inline]
+    pub fn per_content_type(json: u64, multipart: u64, form: u64) -> MaxSizePerContentType {
+        MaxSizePerContentType {
+            json,
+            multipart,
+            form,
+            other: json.max(multipart).max(form),
+        }
+    }
+}
 #[async_trait]
 impl Handler for MaxSize {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let size_hint = req.body().size_hint().upper();
-        if let Some(upper) = size_hint {
-            if upper > self.0 {
-                res.render(StatusError::payload_too_large());
-                ctrl.skip_rest();
-            } else {
-                ctrl.call_next(req, depot, res).await;
-            }
-        } else {
-            res.render(StatusError::bad_request().brief("Request body size is unknown."));
-            ctrl.skip_rest();
-        }
+        limit_body(self.0, req, depot, res, ctrl).await;
     }
 }
 /// Create a new `MaxSize`.
@@ -30,8 +39,80 @@ pub fn max_size(size: u64) -> MaxSize {
     MaxSize(size)
 }
 
+/// Middleware limits request body size by content type, created by [`MaxSize::per_content_type`].
+#[non_exhaustive]
+pub struct MaxSizePerContentType {
+    /// Limit for `application/json` body.
+    pub json: u64,
+    /// Limit for `multipart/*` body.
+    pub multipart: u64,
+    /// Limit for `application/x-www-form-urlencoded` body.
+    pub form: u64,
+    /// Limit for other bodies, default is the largest one of other limits.
+    pub other: u64,
+}
+impl MaxSizePerContentType {
+    /// Sets limit for other bodies and returns new `MaxSizePerContentType`.
+    #[inline]
+    pub fn other(mut self, other: u64) -> Self {
+        self.other = other;
+        self
+    }
+
+    fn limit(&self, req: &Request) -> u64 {
+        let Some(ctype) = req.content_type() else {
+            return self.other;
+        };
+        if ctype.type_() == mime::MULTIPART {
+            self.multipart
+        } else if ctype.subtype() == mime::WWW_FORM_URLENCODED {
+            self.form
+        } else if ctype.subtype() == mime::JSON || ctype.suffix() == Some(mime::JSON) {
+            self.json
+        } else {
+            self.other
+        }
+    }
+}
+#[async_trait]
+impl Handler for MaxSizePerContentType {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        limit_body(self.limit(req), req, depot, res, ctrl).await;
+    }
+}
+
+// Rejects the request if its known size exceeds the limit, otherwise limits the body while it is read.
+async fn limit_body(limit: u64, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+    if let Some(upper) = req.body().size_hint().upper() {
+        if upper > limit {
+            res.render(StatusError::payload_too_large());
+            ctrl.skip_rest();
+        } else {
+            ctrl.call_next(req, depot, res).await;
+        }
+        return;
+    }
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let body = Limited::new(req.take_body(), limit as usize).map_err({
+        let exceeded = exceeded.clone();
+        move |e| {
+            if e.downcast_ref::<LengthLimitError>().is_some() {
+                exceeded.store(true, Ordering::Release);
+            }
+            e
+        }
+    });
+    req.replace_body(ReqBody::Boxed(Box::pin(body)));
+    ctrl.call_next(req, depot, res).await;
+    if exceeded.load(Ordering::Acquire) {
+        res.render(StatusError::payload_too_large());
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use salvo_core::http::body::Frame;
+    use salvo_core::hyper::body::Bytes;
     use salvo_core::prelude::*;
     use salvo_core::test::{ResponseExt, TestClient};
 
@@ -65,4 +146,55 @@ mod tests {
             .await;
         assert_eq!(res.status_code.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
     }
+
+    #[tokio::test]
+    async fn test_size_limiter_unknown_size() {
+        #[handler]
+        async fn echo(req: &mut Request) -> Result<String, StatusError> {
+            let payload = req.payload().await.map_err(|_| StatusError::bad_request())?;
+            Ok(String::from_utf8_lossy(payload).into_owned())
+        }
+        let router = Router::with_hoop(MaxSize(8)).post(echo);
+        let service = Service::new(router);
+
+        let chunks = |chunks: Vec<&'static str>| {
+            let stream = tokio_stream::iter(
+                chunks
+                    .into_iter()
+                    .map(|c| Ok::<_, std::io::Error>(Frame::data(Bytes::from(c)))),
+            );
+            ReqBody::Boxed(Box::pin(http_body_util::StreamBody::new(stream).map_err(Into::into)))
+        };
+        let mut req = TestClient::post("http://127.0.0.1:5801/").build();
+        req.replace_body(chunks(vec!["abc", "def"]));
+        let content = service.handle(req).await.take_string().await.unwrap();
+        assert_eq!(content, "abcdef");
+
+        let mut req = TestClient::post("http://127.0.0.1:5801/").build();
+        req.replace_body(chunks(vec!["abcdef", "ghijkl"]));
+        let res = service.handle(req).await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_size_limiter_per_content_type() {
+        let router = Router::with_hoop(MaxSize::per_content_type(4, 64, 8)).post(hello);
+        let service = Service::new(router);
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .json(&"abcdef")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .form(&[("a", "bc")])
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .text("abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyz")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
 }
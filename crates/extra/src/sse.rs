@@ -50,6 +50,7 @@
 
 use serde::Serialize;
 use std::borrow::Cow;
+use std::convert::Infallible;
 use std::error::Error as StdError;
 use std::fmt::{self, Display, Formatter, Write};
 use std::future::Future;
@@ -58,12 +59,13 @@ use std::task::{Context, Poll};
 use std::time::Duration;
 
 use futures_util::future;
-use futures_util::stream::{Stream, TryStream, TryStreamExt};
+use futures_util::stream::{Stream, StreamExt, TryStream, TryStreamExt};
 use pin_project::pin_project;
 use salvo_core::http::header::{HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
 use tokio::time::{self, Sleep};
 
 use salvo_core::http::Response;
+use salvo_core::writing::Scribe;
 
 /// Server-sent event data type
 #[derive(Clone, Debug)]
@@ -94,6 +96,18 @@ pub struct SseEvent {
 }
 
 impl SseEvent {
+    /// Create new empty `SseEvent`.
+    #[inline]
+    pub fn new() -> SseEvent {
+        Self::default()
+    }
+
+    /// Sets Server-sent event data, multi-line data will be split into multiple `data:` lines.
+    #[inline]
+    pub fn data<T: Display>(self, data: T) -> SseEvent {
+        self.text(data.to_string())
+    }
+
     /// Sets Server-sent event data.
     #[inline]
     pub fn text<T: Into<String>>(mut self, data: T) -> SseEvent {
@@ -122,6 +136,12 @@ impl SseEvent {
         self
     }
 
+    /// Sets Server-sent event event, it is same as [`SseEvent::name`].
+    #[inline]
+    pub fn event<T: Into<String>>(self, event: T) -> SseEvent {
+        self.name(event)
+    }
+
     /// Sets Server-sent event retry.
     #[inline]
     pub fn retry(mut self, duration: Duration) -> SseEvent {
@@ -135,6 +155,12 @@ impl SseEvent {
         self.id = Some(id.into());
         self
     }
+
+    /// Build the event to text in wire format.
+    #[inline]
+    pub fn build(&self) -> String {
+        self.to_string()
+    }
 }
 
 impl Display for SseEvent {
@@ -155,7 +181,7 @@ impl Display for SseEvent {
             Some(DataType::Text(ref data)) => {
                 for line in data.split('\n') {
                     "data:".fmt(f)?;
-                    line.fmt(f)?;
+                    line.trim_end_matches('\r').fmt(f)?;
                     f.write_char('\n')?;
                 }
             }
@@ -234,12 +260,19 @@ where
             alive_timer,
         }
     }
+    /// Create new `SseKeepAlive` which sends `:keep-alive` comment when no event is sent in `interval`.
+    #[inline]
+    pub fn wrap(event_stream: S, interval: Duration) -> SseKeepAlive<S> {
+        Self::new(event_stream).max_interval(interval).comment("keep-alive")
+    }
+
     /// Customize the interval between keep-alive messages.
     ///
     /// Default is 15 seconds.
     #[inline]
     pub fn max_interval(mut self, time: Duration) -> Self {
         self.max_interval = time;
+        self.alive_timer = time::sleep(time);
         self
     }
 
@@ -259,6 +292,48 @@ where
     }
 }
 
+/// A stream of [`SseEvent`] which can be returned from a handler directly.
+///
+/// It sets `Content-Type: text/event-stream` and `Cache-Control: no-cache` headers when rendered.
+///
+/// When the connection is lost, browsers reconnect automatically and send the id of the last
+/// received event in the `Last-Event-ID` header, so the stream can be resumed from there:
+///
+/// ```
+/// use futures_util::stream::{self, Stream};
+/// use salvo_core::prelude::*;
+/// use salvo_extra::sse::{EventStream, SseEvent};
+///
+/// #[handler]
+/// async fn events(req: &mut Request) -> EventStream<impl Stream<Item = SseEvent>> {
+///     let last_id = req.header::<u64>("last-event-id").unwrap_or(0);
+///     EventStream::new(stream::iter((last_id + 1..=last_id + 3).map(|id| {
+///         SseEvent::default().id(id.to_string()).text(format!("event {id}"))
+///     })))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EventStream<S>(S);
+impl<S> EventStream<S>
+where
+    S: Stream<Item = SseEvent> + Send + 'static,
+{
+    /// Create new `EventStream`.
+    #[inline]
+    pub fn new(event_stream: S) -> Self {
+        Self(event_stream)
+    }
+}
+impl<S> Scribe for EventStream<S>
+where
+    S: Stream<Item = SseEvent> + Send + 'static,
+{
+    #[inline]
+    fn render(self, res: &mut Response) {
+        stream(res, self.0.map(Ok::<_, Infallible>))
+    }
+}
+
 #[inline]
 fn write_response_headers(res: &mut Response) {
     res.headers_mut()
@@ -324,8 +399,9 @@ mod tests {
     use std::convert::Infallible;
     use std::time::Duration;
 
+    use futures_util::StreamExt;
     use salvo_core::prelude::*;
-    use salvo_core::test::ResponseExt;
+    use salvo_core::test::{ResponseExt, TestClient};
     use tokio_stream;
 
     use super::*;
@@ -342,6 +418,27 @@ mod tests {
         assert!(text.contains("data:1") && text.contains("data:2"));
     }
 
+    #[tokio::test]
+    async fn test_event_stream() {
+        #[handler]
+        async fn events(req: &mut Request) -> EventStream<impl Stream<Item = SseEvent>> {
+            let last_id = req.header::<u64>("last-event-id").unwrap_or(0);
+            EventStream::new(futures_util::stream::iter(
+                (last_id + 1..=last_id + 2).map(|id| SseEvent::default().id(id.to_string()).text("a\nb")),
+            ))
+        }
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("last-event-id", "3", true)
+            .send(Router::new().get(events))
+            .await;
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/event-stream");
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "no-cache");
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            "data:a\ndata:b\nid:4\n\ndata:a\ndata:b\nid:5\n\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_sse_keep_alive() {
         let event_stream = tokio_stream::iter(vec![Ok::<_, Infallible>(SseEvent::default().text("1"))]);
@@ -354,6 +451,27 @@ mod tests {
         assert!(text.contains("data:1"));
     }
 
+    #[tokio::test]
+    async fn test_sse_keep_alive_wrap() {
+        let event_stream = futures_util::stream::pending::<Result<SseEvent, Infallible>>();
+        let mut keep_alive = Box::pin(SseKeepAlive::wrap(event_stream, Duration::from_millis(10)));
+        let event = keep_alive.next().await.unwrap().unwrap();
+        assert_eq!(event.build(), ":keep-alive\n\n");
+    }
+
+    #[test]
+    fn test_sse_event_build() {
+        let event = SseEvent::new()
+            .event("message")
+            .data("line1\r\nline2")
+            .id("1")
+            .retry(Duration::from_secs(3));
+        assert_eq!(
+            event.build(),
+            "event:message\ndata:line1\ndata:line2\nid:1\nretry:3000\n\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_sse_json() {
         #[derive(Serialize, Debug)]
@@ -0,0 +1,73 @@
+//! Start time middleware.
+//!
+//! Read more: <https://salvo.rs>
+use std::time::{Duration, Instant};
+
+use salvo_core::http::{Request, Response};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+/// The instant when request processing is started, get it by `depot.obtain::<StartTime>()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StartTime(pub Instant);
+impl StartTime {
+    /// Create new `StartTime` from now.
+    #[inline]
+    pub fn now() -> Self {
+        StartTime(Instant::now())
+    }
+    /// Returns the amount of time elapsed since request is started.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// A middleware injects [`StartTime`] into depot.
+///
+/// It should be added as the first middleware, if a `StartTime` is already injected, it will not be replaced.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct StartTimer {}
+impl StartTimer {
+    /// Create new `StartTimer` middleware.
+    #[inline]
+    pub fn new() -> Self {
+        StartTimer {}
+    }
+}
+
+#[async_trait]
+impl Handler for StartTimer {
+    async fn handle(&self, _req: &mut Request, depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
+        if !depot.contains::<StartTime>() {
+            depot.inject(StartTime::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn hello(depot: &mut Depot) -> String {
+        let start = depot.obtain::<StartTime>().unwrap();
+        format!("{}", start.elapsed() < Duration::from_secs(10))
+    }
+
+    #[tokio::test]
+    async fn test_start_time() {
+        let router = Router::with_hoop(StartTimer::new())
+            .hoop(StartTimer::new())
+            .push(Router::with_path("hello").get(hello));
+        let content = TestClient::get("http://127.0.0.1:5801/hello")
+            .send(router)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "true");
+    }
+}
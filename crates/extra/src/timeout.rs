@@ -1,32 +1,101 @@
-//! basic auth middleware.
+//! Timeout middleware.
 //!
 //! Read more: <https://salvo.rs>
 use std::time::Duration;
 
+use salvo_core::http::header::{HeaderName, HeaderValue, RETRY_AFTER};
 use salvo_core::http::{Request, Response, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
-/// Timeout
+/// Alias of [`Timeout`].
+pub type RequestTimeout = Timeout;
+
+/// Middleware aborts the remaining handlers if they do not finish in time.
+///
+/// The clock starts when this middleware is entered. On timeout, the response is set to
+/// `503 Service Unavailable` with a `Retry-After` header and the rest handlers are skipped.
 pub struct Timeout {
-    value: Duration,
+    value: Option<Duration>,
+    header: Option<HeaderName>,
+    max: Option<Duration>,
+    retry_after: Duration,
 }
 impl Timeout {
     /// Create a new `Timeout`.
     #[inline]
     pub fn new(value: Duration) -> Self {
-        Timeout { value }
+        Timeout {
+            value: Some(value),
+            header: None,
+            max: None,
+            retry_after: Duration::from_secs(5),
+        }
+    }
+    /// Create a new `Timeout` which reads the timeout in milliseconds from request header, for cases
+    /// where the client signals its own deadline.
+    ///
+    /// If the header is missing or invalid, no timeout is applied unless a fallback is set by
+    /// [`Timeout::fallback`]. Use [`Timeout::max`] to limit the timeout a client can ask for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    #[inline]
+    pub fn from_header(name: &str) -> Self {
+        Timeout {
+            value: None,
+            header: Some(HeaderName::from_bytes(name.as_bytes()).expect("invalid header name")),
+            max: None,
+            retry_after: Duration::from_secs(5),
+        }
+    }
+    /// Sets the timeout used when the header is missing or invalid and returns new `Timeout`.
+    #[inline]
+    pub fn fallback(mut self, value: Duration) -> Self {
+        self.value = Some(value);
+        self
+    }
+    /// Sets the max timeout and returns new `Timeout`.
+    #[inline]
+    pub fn max(mut self, max: Duration) -> Self {
+        self.max = Some(max);
+        self
+    }
+    /// Sets the value of `Retry-After` header, default is 5 seconds, and returns new `Timeout`.
+    #[inline]
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    fn timeout(&self, req: &Request) -> Option<Duration> {
+        let value = self
+            .header
+            .as_ref()
+            .and_then(|name| req.header::<u64>(name))
+            .map(Duration::from_millis)
+            .or(self.value)?;
+        Some(match self.max {
+            Some(max) => value.min(max),
+            None => value,
+        })
     }
 }
 #[async_trait]
 impl Handler for Timeout {
     #[inline]
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        tokio::select! {
-            _ = ctrl.call_next(req, depot, res) => {},
-            _ = tokio::time::sleep(self.value) => {
-                res.render(StatusError::internal_server_error().brief("Server process the request timeout."));
-                ctrl.skip_rest();
-            }
+        let Some(timeout) = self.timeout(req) else {
+            return;
+        };
+        if tokio::time::timeout(timeout, ctrl.call_next(req, depot, res))
+            .await
+            .is_err()
+        {
+            res.render(StatusError::service_unavailable().brief("Server process the request timeout."));
+            res.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(self.retry_after.as_secs().max(1)));
+            ctrl.skip_rest();
         }
     }
 }
@@ -56,13 +125,10 @@ mod tests {
             .push(Router::with_path("fast").get(fast));
         let service = Service::new(router);
 
-        let content = TestClient::get("http://127.0.0.1:5801/slow")
-            .send(&service)
-            .await
-            .take_string()
-            .await
-            .unwrap();
-        assert!(content.contains("timeout"));
+        let mut res = TestClient::get("http://127.0.0.1:5801/slow").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "5");
+        assert!(res.take_string().await.unwrap().contains("timeout"));
 
         let content = TestClient::get("http://127.0.0.1:5801/fast")
             .send(&service)
@@ -72,4 +138,38 @@ mod tests {
             .unwrap();
         assert!(content.contains("hello"));
     }
+
+    #[tokio::test]
+    async fn test_timeout_from_header() {
+        #[handler]
+        async fn slow() -> &'static str {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            "hello"
+        }
+
+        let router = Router::new()
+            .hoop(
+                RequestTimeout::from_header("X-Request-Deadline")
+                    .max(Duration::from_millis(500))
+                    .retry_after(Duration::from_secs(10)),
+            )
+            .get(slow);
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-request-deadline", "50", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "10");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-request-deadline", "60000", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+    }
 }
@@ -11,6 +11,7 @@ use std::fmt::{self, Formatter};
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll, ready};
+use std::time::Duration;
 
 use futures_util::sink::{Sink, SinkExt};
 use futures_util::stream::{Stream, StreamExt};
@@ -21,6 +22,7 @@ use salvo_core::http::headers::{Connection, HeaderMapExt, SecWebsocketAccept, Se
 use salvo_core::http::{StatusCode, StatusError};
 use salvo_core::rt::tokio::TokioIo;
 use salvo_core::{Error, Request, Response};
+use tokio::time::{self, Interval, MissedTickBehavior};
 use tokio_tungstenite::{
     tungstenite::protocol::{self, WebSocketConfig},
     WebSocketStream,
@@ -42,6 +44,7 @@ use tokio_tungstenite::{
 #[allow(missing_debug_implementations)]
 pub struct WebSocketUpgrade {
     config: Option<WebSocketConfig>,
+    ping_interval: Option<Duration>,
 }
 
 impl Default for WebSocketUpgrade {
@@ -55,13 +58,19 @@ impl WebSocketUpgrade {
     /// Create new `WebSocketUpgrade`.
     #[inline]
     pub fn new() -> Self {
-        WebSocketUpgrade { config: None }
+        WebSocketUpgrade {
+            config: None,
+            ping_interval: None,
+        }
     }
 
     /// Create new `WebSocketUpgrade` with config.
     #[inline]
     pub fn with_config(config: WebSocketConfig) -> Self {
-        WebSocketUpgrade { config: Some(config) }
+        WebSocketUpgrade {
+            config: Some(config),
+            ping_interval: None,
+        }
     }
 
     /// The target minimum size of the write buffer to reach before writing the data
@@ -124,6 +133,16 @@ impl WebSocketUpgrade {
         self
     }
 
+    /// Sends a Ping message to the client every `interval` to keep the connection alive.
+    ///
+    /// Pings are sent while the [`WebSocket`] is polled for messages, for example while waiting
+    /// on [`WebSocket::recv`]. Pong replies from the client are returned as normal messages.
+    /// Default is `None`, no Ping message is sent automatically.
+    #[inline]
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
 
     /// Upgrade websocket request.
     pub async fn upgrade<F, Fut>(&self, req: &mut Request, res: &mut Response, callback: F) -> Result<(), StatusError>
@@ -173,6 +192,7 @@ impl WebSocketUpgrade {
 
         if let Some(on_upgrade) = req.extensions_mut().remove::<OnUpgrade>() {
             let config = self.config;
+            let ping_interval = self.ping_interval;
             tokio::spawn(async move {
                 let socket = on_upgrade
                     .and_then(move |upgraded| {
@@ -181,6 +201,10 @@ impl WebSocketUpgrade {
                     })
                     .await
                     .expect("connection upgrade failed");
+                let socket = match ping_interval {
+                    Some(interval) => socket.with_ping_interval(interval),
+                    None => socket,
+                };
                 callback(socket).await;
             });
             Ok(())
@@ -198,6 +222,8 @@ impl WebSocketUpgrade {
 /// `WebSocket`.
 pub struct WebSocket {
     inner: WebSocketStream<TokioIo<hyper::upgrade::Upgraded>>,
+    ping: Option<Interval>,
+    ping_pending: bool,
 }
 
 impl WebSocket {
@@ -208,10 +234,44 @@ impl WebSocket {
         config: Option<protocol::WebSocketConfig>,
     ) -> Self {
         WebSocketStream::from_raw_socket(TokioIo::new(upgraded), role, config)
-            .map(|inner| WebSocket { inner })
+            .map(|inner| WebSocket {
+                inner,
+                ping: None,
+                ping_pending: false,
+            })
             .await
     }
 
+    fn with_ping_interval(mut self, period: Duration) -> Self {
+        let mut interval = time::interval_at(time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        self.ping = Some(interval);
+        self
+    }
+
+    fn poll_ping(&mut self, cx: &mut Context) -> Result<(), Error> {
+        if let Some(interval) = &mut self.ping {
+            if interval.poll_tick(cx).is_ready() {
+                self.ping_pending = true;
+            }
+        }
+        if self.ping_pending {
+            let mut inner = Pin::new(&mut self.inner);
+            if let Poll::Ready(ready) = inner.as_mut().poll_ready(cx) {
+                ready.map_err(Error::other)?;
+                inner
+                    .as_mut()
+                    .start_send(protocol::Message::Ping(Vec::new()))
+                    .map_err(Error::other)?;
+                self.ping_pending = false;
+            }
+        }
+        if let Poll::Ready(Err(e)) = Pin::new(&mut self.inner).poll_flush(cx) {
+            return Err(Error::other(e));
+        }
+        Ok(())
+    }
+
     /// Receive another message.
     ///
     /// Returns `None` if the stream has closed.
@@ -236,6 +296,12 @@ impl Stream for WebSocket {
 
     #[inline]
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.ping.is_some() {
+            if let Err(e) = self.poll_ping(cx) {
+                tracing::debug!("websocket ping error: {}", e);
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
         match ready!(Pin::new(&mut self.inner).poll_next(cx)) {
             Some(Ok(item)) => Poll::Ready(Some(Ok(Message { inner: item }))),
             Some(Err(e)) => {
@@ -487,4 +553,45 @@ mod tests {
 
         assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
     }
+
+    #[tokio::test]
+    async fn test_websocket_ping_interval() {
+        #[handler]
+        async fn keepalive(req: &mut Request, res: &mut Response) -> Result<(), StatusError> {
+            WebSocketUpgrade::new()
+                .ping_interval(Duration::from_millis(50))
+                .upgrade(req, res, |mut ws| async move { while let Some(Ok(_)) = ws.recv().await {} })
+                .await
+        }
+        let router = Router::new().goal(keepalive);
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(async move {
+            Server::new(acceptor).serve(router).await;
+        });
+
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await.unwrap();
+        tokio::task::spawn(conn.with_upgrades());
+        let req = hyper::Request::builder()
+            .uri(format!("http://{}", addr))
+            .header(UPGRADE, "websocket")
+            .header(CONNECTION, "Upgrade")
+            .header(SEC_WEBSOCKET_KEY, "6D69KGBOr4Re+Nj6zx9aQA==")
+            .header(SEC_WEBSOCKET_VERSION, "13")
+            .body(http_body_util::Empty::<hyper::body::Bytes>::new())
+            .unwrap();
+        let res = sender.send_request(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(res.headers().get(UPGRADE).unwrap(), "websocket");
+
+        let upgraded = hyper::upgrade::on(res).await.unwrap();
+        let mut client = WebSocketStream::from_raw_socket(TokioIo::new(upgraded), protocol::Role::Client, None).await;
+        let msg = time::timeout(Duration::from_secs(2), client.next())
+            .await
+            .expect("no ping received")
+            .unwrap()
+            .unwrap();
+        assert!(msg.is_ping());
+    }
 }
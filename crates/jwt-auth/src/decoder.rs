@@ -42,6 +42,17 @@ impl ConstDecoder {
         }
     }
 
+    /// Sets the leeway in seconds applied to `exp` and `nbf` validation to tolerate clock skew,
+    /// default is 60 seconds.
+    pub fn leeway(mut self, leeway: u64) -> Self {
+        self.validation.leeway = leeway;
+        self
+    }
+    /// Get validation mutable reference.
+    pub fn validation_mut(&mut self) -> &mut Validation {
+        &mut self.validation
+    }
+
     /// If you're using HMAC, use this.
     pub fn from_secret(secret: &[u8]) -> Self {
         Self::with_validation(DecodingKey::from_secret(secret), Validation::default())
@@ -17,6 +17,7 @@ pub use jsonwebtoken::{decode, Algorithm, DecodingKey, TokenData, Validation};
 use serde::de::DeserializeOwned;
 use thiserror::Error;
 
+use salvo_core::http::header::{HeaderValue, WWW_AUTHENTICATE};
 use salvo_core::http::{Method, Request, Response, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
@@ -156,6 +157,9 @@ impl JwtAuthDepotExt for Depot {
 }
 
 /// JwtAuth, used as middleware.
+///
+/// If the token is missing or invalid, `401 Unauthorized` is returned with a `WWW-Authenticate: Bearer`
+/// header, unless [`force_passed`](JwtAuth::force_passed) is `true`.
 #[non_exhaustive]
 pub struct JwtAuth<C, D> {
     /// Only write auth state to depot when set to `true`.
@@ -239,7 +243,11 @@ where
                     depot.insert(JWT_AUTH_STATE_KEY, JwtAuthState::Forbidden);
                     depot.insert(JWT_AUTH_ERROR_KEY, e);
                     if !self.force_passed {
-                        res.render(StatusError::forbidden());
+                        res.headers_mut().insert(
+                            WWW_AUTHENTICATE,
+                            HeaderValue::from_static(r#"Bearer error="invalid_token""#),
+                        );
+                        res.render(StatusError::unauthorized().brief("The access token is invalid."));
                         ctrl.skip_rest();
                     }
                 }
@@ -247,6 +255,8 @@ where
         } else {
             depot.insert(JWT_AUTH_STATE_KEY, JwtAuthState::Unauthorized);
             if !self.force_passed {
+                res.headers_mut()
+                    .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
                 res.render(StatusError::unauthorized());
                 ctrl.skip_rest();
             }
@@ -335,6 +345,51 @@ mod tests {
         )
         .unwrap();
         let content = access(&service, &token).await;
-        assert!(content.contains("Forbidden"));
+        assert!(content.contains("Unauthorized"));
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("Authorization", format!("Bearer {}", token), true)
+            .add_header("Accept", "application/json", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            res.headers().get(WWW_AUTHENTICATE).unwrap(),
+            r#"Bearer error="invalid_token""#
+        );
+        assert_eq!(res.headers().get("content-type").unwrap(), "application/json");
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(res.headers().get(WWW_AUTHENTICATE).unwrap(), "Bearer");
+    }
+
+    #[tokio::test]
+    async fn test_jwt_auth_leeway() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        let claim = JwtClaims {
+            user: "root".into(),
+            exp: (OffsetDateTime::now_utc() - Duration::seconds(30)).unix_timestamp(),
+        };
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claim,
+            &EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .unwrap();
+
+        for (leeway, status) in [(60, StatusCode::OK), (0, StatusCode::UNAUTHORIZED)] {
+            let auth_handler: JwtAuth<serde_json::Value, ConstDecoder> =
+                JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF").leeway(leeway));
+            let router = Router::with_hoop(auth_handler).get(hello);
+            let res = TestClient::get("http://127.0.0.1:5801/")
+                .add_header("Authorization", format!("Bearer {}", token), true)
+                .send(router)
+                .await;
+            assert_eq!(res.status_code, Some(status));
+        }
     }
 }
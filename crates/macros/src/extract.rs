@@ -1,8 +1,9 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use quote::{quote, quote_spanned};
 use syn::parse::Parse;
 use syn::parse::ParseStream;
 use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::token::Comma;
 use syn::{DeriveInput, Error, Expr, ExprLit, Field, Generics, Lit, Meta, MetaNameValue, Token, Type};
 
@@ -19,6 +20,13 @@ struct FieldInfo {
     rename: Option<String>,
     serde_rename: Option<String>,
     flatten: bool,
+    secret: bool,
+    required: bool,
+    default: Option<Option<syn::Path>>,
+    serde_default: bool,
+    serde_skip: bool,
+    state: bool,
+    deserialize_with: Option<String>,
 }
 impl TryFrom<&Field> for FieldInfo {
     type Error = Error;
@@ -30,39 +38,83 @@ impl TryFrom<&Field> for FieldInfo {
         let mut aliases = Vec::with_capacity(field.attrs.len());
         let mut rename = None;
         let mut flatten = None;
+        let mut secret = false;
+        let mut required = false;
+        let mut default = None;
+        let mut deserialize_with = None;
         for attr in attrs {
             if attr.path().is_ident("salvo") {
                 if let Ok(Some(metas)) = attribute::find_nested_list(&attr, "extract") {
                     let info: ExtractFieldInfo = metas.parse_args()?;
                     sources.extend(info.sources);
                     aliases.extend(info.aliases);
-                    flatten = info.flatten;
                     if info.rename.is_some() {
                         rename = info.rename;
                     }
                     if info.flatten.is_some() {
                         flatten = info.flatten;
                     }
+                    secret = secret || info.secret;
+                    required = required || info.required;
+                    if info.default.is_some() {
+                        default = info.default;
+                    }
+                    if info.deserialize_with.is_some() {
+                        deserialize_with = info.deserialize_with;
+                    }
                 }
             }
         }
         sources.dedup();
         aliases.dedup();
+        let state = sources.iter().any(|s| s.from == "state");
+        if state {
+            if sources.len() > 1 {
+                return Err(Error::new_spanned(
+                    ident,
+                    "state field should not define other sources.",
+                ));
+            }
+            if !aliases.is_empty()
+                || rename.is_some()
+                || flatten.is_some()
+                || default.is_some()
+                || deserialize_with.is_some()
+            {
+                return Err(Error::new_spanned(
+                    ident,
+                    "state field should not define aliases, rename, flatten, default or deserialize_with.",
+                ));
+            }
+            sources.clear();
+        }
 
-        let (serde_rename, serde_flatten) =
-            if let Some(SerdeValue { rename, flatten, .. }) = serde_util::parse_value(&field.attrs) {
-                (rename, flatten)
-            } else {
-                (None, false)
-            };
+        let (serde_rename, serde_flatten, serde_default, serde_skip) = if let Some(SerdeValue {
+            rename,
+            flatten,
+            is_default,
+            skip,
+            ..
+        }) = serde_util::parse_value(&field.attrs)
+        {
+            (rename, flatten, is_default, skip)
+        } else {
+            (None, false, false, false)
+        };
         let flatten = flatten.unwrap_or(serde_flatten);
         if flatten {
-            if !sources.is_empty() {
-                return Err(Error::new_spanned(ident, "flatten field should not define souces."));
-            }
             if !aliases.is_empty() {
                 return Err(Error::new_spanned(ident, "flatten field should not define aliases."));
             }
+            if default.is_some() {
+                return Err(Error::new_spanned(ident, "flatten field should not define default."));
+            }
+        }
+        if default.is_some() && is_option_type(&field.ty) {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "`Option` field is `None` when missing, it should not define default.",
+            ));
         }
 
         Ok(Self {
@@ -73,6 +125,13 @@ impl TryFrom<&Field> for FieldInfo {
             rename,
             serde_rename,
             flatten,
+            secret,
+            required,
+            default,
+            serde_default,
+            serde_skip,
+            state,
+            deserialize_with,
         })
     }
 }
@@ -83,6 +142,10 @@ struct ExtractFieldInfo {
     aliases: Vec<String>,
     rename: Option<String>,
     flatten: Option<bool>,
+    secret: bool,
+    required: bool,
+    default: Option<Option<syn::Path>>,
+    deserialize_with: Option<String>,
 }
 impl Parse for ExtractFieldInfo {
     fn parse(input: ParseStream) -> syn::Result<Self> {
@@ -103,11 +166,44 @@ impl Parse for ExtractFieldInfo {
                 "alias" => {
                     input.parse::<Token![=]>()?;
                     let expr = input.parse::<Expr>()?;
-                    extract.aliases.push(expr_lit_value(&expr)?);
+                    if let Expr::Array(array) = &expr {
+                        for elem in &array.elems {
+                            extract.aliases.push(expr_lit_value(elem)?);
+                        }
+                    } else {
+                        extract.aliases.push(expr_lit_value(&expr)?);
+                    }
                 }
                 "flatten" => {
                     extract.flatten = Some(true);
                 }
+                "secret" => {
+                    extract.secret = true;
+                }
+                "default" => {
+                    if input.parse::<Token![=]>().is_ok() {
+                        let expr = input.parse::<Expr>()?;
+                        extract.default = Some(Some(syn::parse_str::<syn::Path>(&expr_lit_value(&expr)?)?));
+                    } else {
+                        extract.default = Some(None);
+                    }
+                }
+                "compile_check" => {
+                    input.parse::<Token![=]>()?;
+                    let expr = input.parse::<Expr>()?;
+                    if expr_lit_value(&expr)? != "required" {
+                        return Err(Error::new_spanned(expr, "compile_check value should be \"required\"."));
+                    }
+                    extract.required = true;
+                }
+                "deserialize_with" => {
+                    input.parse::<Token![=]>()?;
+                    let expr = input.parse::<Expr>()?;
+                    let path = expr_lit_value(&expr)?;
+                    syn::parse_str::<syn::Path>(&path)
+                        .map_err(|_| Error::new_spanned(&expr, "deserialize_with value should be a function path."))?;
+                    extract.deserialize_with = Some(path);
+                }
                 _ => {
                     return Err(input.error("unexpected attribute"));
                 }
@@ -134,31 +230,55 @@ impl Parse for SourceInfo {
         for field in fields {
             if field.path.is_ident("from") {
                 source.from = expr_lit_value(&field.value)?;
+                check_value(&field.value, &source.from, "source from", SOURCE_FROMS)?;
             } else if field.path.is_ident("parse") {
                 source.parser = expr_lit_value(&field.value)?;
+                if source.parser.is_empty() {
+                    source.parser = "smart".to_string();
+                }
+                check_value(&field.value, &source.parser, "source parser", SOURCE_PARSERS)?;
             } else {
                 return Err(input.error("unexpected attribute"));
             }
         }
-        if source.parser.is_empty() {
-            source.parser = "smart".to_string();
-        }
-        if !["param", "query", "header", "body"].contains(&source.from.as_str()) {
-            return Err(Error::new(
-                input.span(),
-                format!("source from is invalid: {}", source.from),
-            ));
-        }
-        if !["multimap", "json", "smart"].contains(&source.parser.as_str()) {
-            return Err(Error::new(
-                input.span(),
-                format!("source parser is invalid: {}", source.parser),
-            ));
-        }
         Ok(source)
     }
 }
 
+const SOURCE_FROMS: &[&str] = &["param", "query", "header", "cookie", "body", "state"];
+const SOURCE_PARSERS: &[&str] = &["multimap", "json", "smart", "qs"];
+
+fn check_value(expr: &Expr, value: &str, kind: &str, valid: &[&str]) -> syn::Result<()> {
+    if valid.contains(&value) {
+        return Ok(());
+    }
+    let mut msg = format!("{kind} is invalid: `{value}`, expected one of: {}", valid.join(", "));
+    if let Some(similar) = valid
+        .iter()
+        .map(|v| (edit_distance(value, v), v))
+        .filter(|(d, _)| *d <= 2)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, v)| v)
+    {
+        msg.push_str(&format!(", did you mean `{similar}`?"));
+    }
+    Err(Error::new_spanned(expr, msg))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
 struct ExtractibleArgs {
     ident: Ident,
     generics: Generics,
@@ -167,6 +287,10 @@ struct ExtractibleArgs {
     default_sources: Vec<SourceInfo>,
     rename_all: Option<RenameRule>,
     serde_rename_all: Option<RenameRule>,
+    serde_default: bool,
+    all_required: bool,
+    on_error: Option<syn::Path>,
+    debug: bool,
 }
 
 impl ExtractibleArgs {
@@ -189,12 +313,18 @@ impl ExtractibleArgs {
         }
         let mut default_sources = Vec::new();
         let mut rename_all = None;
+        let mut all_required = false;
+        let mut on_error = None;
+        let mut debug = false;
         for attr in &attrs {
             if attr.path().is_ident("salvo") {
                 if let Ok(Some(metas)) = attribute::find_nested_list(attr, "extract") {
                     let nested = metas.parse_args_with(Punctuated::<Meta, Comma>::parse_terminated)?;
                     for meta in nested {
                         match meta {
+                            Meta::Path(path) if path.is_ident("debug") => {
+                                debug = true;
+                            }
                             Meta::List(meta) => {
                                 if meta.path.is_ident("default_source") {
                                     default_sources.push(meta.parse_args()?);
@@ -203,6 +333,16 @@ impl ExtractibleArgs {
                             Meta::NameValue(meta) => {
                                 if meta.path.is_ident("rename_all") {
                                     rename_all = Some(expr_lit_value(&meta.value)?.parse::<RenameRule>()?);
+                                } else if meta.path.is_ident("compile_check") {
+                                    if expr_lit_value(&meta.value)? != "required" {
+                                        return Err(Error::new_spanned(
+                                            &meta.value,
+                                            "compile_check value should be \"required\".",
+                                        ));
+                                    }
+                                    all_required = true;
+                                } else if meta.path.is_ident("on_error") {
+                                    on_error = Some(syn::parse_str::<syn::Path>(&expr_lit_value(&meta.value)?)?);
                                 }
                             }
                             _ => {}
@@ -212,6 +352,7 @@ impl ExtractibleArgs {
             }
         }
         let serde_container = serde_util::parse_container(&attrs);
+        let serde_default = serde_container.as_ref().map(|c| c.is_default).unwrap_or_default();
         let serde_rename_all = serde_container.and_then(|c| c.rename_all);
         Ok(Self {
             ident,
@@ -220,13 +361,18 @@ impl ExtractibleArgs {
             default_sources,
             rename_all,
             serde_rename_all,
+            serde_default,
+            all_required,
+            on_error,
+            debug,
         })
     }
 }
 
 fn metadata_source(salvo: &Ident, source: &SourceInfo) -> TokenStream {
     let from = Ident::new(&RenameRule::PascalCase.apply_to_field(&source.from), Span::call_site());
-    let parser = if source.parser.to_lowercase() == "multimap" {
+    // `qs` parser deserializes the whole query string, it is described as multimap query in metadata.
+    let parser = if source.parser.to_lowercase() == "multimap" || source.parser == "qs" {
         Ident::new("MultiMap", Span::call_site())
     } else {
         Ident::new(
@@ -245,15 +391,75 @@ fn metadata_source(salvo: &Ident, source: &SourceInfo) -> TokenStream {
     }
 }
 
-pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
-    let mut args: ExtractibleArgs = ExtractibleArgs::from_derive_input(&args)?;
+pub(crate) fn generate(input: DeriveInput) -> Result<TokenStream, Error> {
+    let mut args: ExtractibleArgs = ExtractibleArgs::from_derive_input(&input)?;
     let salvo = salvo_crate();
-    let (_, ty_generics, where_clause) = args.generics.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = args.generics.split_for_impl();
 
     let name = &args.ident;
     let mut default_sources = Vec::new();
     let mut fields = Vec::new();
 
+    if args.debug {
+        if let Some(debug) = find_derived_debug(&input.attrs)? {
+            return Err(Error::new_spanned(
+                debug,
+                "`Debug` is implemented by `Extractible` to redact secret fields, remove it from derive.",
+            ));
+        }
+    } else if let Some(field) = args.fields.iter().find(|f| f.secret) {
+        // Without the generated `Debug`, a derived one would print the secret.
+        return Err(Error::new_spanned(
+            &field.ty,
+            "secret field is redacted by the `Debug` implemented by `Extractible`, add `#[salvo(extract(debug))]` to the struct and remove `Debug` from derive.",
+        ));
+    }
+
+    if !args.serde_default && !args.all_required {
+        for field in &args.fields {
+            if field.required
+                || field.state
+                || field.default.is_some()
+                || field.serde_default
+                || field.serde_skip
+                || field.flatten
+                || is_option_type(&field.ty)
+            {
+                continue;
+            }
+            return Err(Error::new_spanned(
+                &field.ty,
+                "field is required but may be missing in request, use `Option`, `#[serde(default)]` or confirm it with `#[salvo(extract(compile_check = \"required\"))]`.",
+            ));
+        }
+    }
+    let qs_query_only = args.default_sources.iter().any(|s| s.parser == "qs");
+    if qs_query_only {
+        if args.default_sources.len() != 1 || args.default_sources[0].from != "query" {
+            return Err(Error::new_spanned(
+                name,
+                "`qs` parser can only be used in the only default source from query.",
+            ));
+        }
+        if args.fields.iter().any(|f| !f.sources.is_empty() || f.default.is_some()) {
+            return Err(Error::new_spanned(
+                name,
+                "fields should not define sources or default when `qs` parser is used.",
+            ));
+        }
+    }
+    if args.fields.iter().any(|f| f.sources.iter().any(|s| s.parser == "qs")) {
+        return Err(Error::new_spanned(
+            name,
+            "`qs` parser can only be used in default source.",
+        ));
+    }
+    if args.default_sources.iter().any(|s| s.from == "state") {
+        return Err(Error::new_spanned(
+            name,
+            "state source can only be used in field source.",
+        ));
+    }
     for source in &args.default_sources {
         let source = metadata_source(&salvo, source);
         default_sources.push(quote! {
@@ -293,15 +499,50 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
         None
     };
 
-    for field in &mut args.fields {
-        let field_ident = field
-            .ident
-            .as_ref()
-            .ok_or_else(|| Error::new_spanned(name, "All fields must be named."))?
-            .to_string();
+    let is_tuple = args.fields.iter().any(|f| f.ident.is_none());
+    if is_tuple && qs_query_only {
+        return Err(Error::new_spanned(name, "`qs` parser can not be used in tuple struct."));
+    }
+    let has_state = args.fields.iter().any(|f| f.state);
+    let has_shadow = has_state || args.fields.iter().any(|f| f.deserialize_with.is_some());
+    if has_shadow && (is_tuple || !args.generics.params.is_empty()) {
+        return Err(Error::new_spanned(
+            name,
+            "state source and deserialize_with can not be used in tuple struct or generic struct.",
+        ));
+    }
+    for (index, field) in args.fields.iter_mut().enumerate() {
+        // State fields are obtained from depot, they are not described in metadata.
+        if field.state {
+            continue;
+        }
+        let field_ident = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => {
+                // Tuple struct fields are looked up by `rename`, the index is only used as declared name.
+                if field.rename.is_none() && !field.flatten {
+                    return Err(Error::new_spanned(
+                        &field.ty,
+                        "field of tuple struct should define name by `#[salvo(extract(rename = \"...\"))]`.",
+                    ));
+                }
+                index.to_string()
+            }
+        };
         let mut nested_metadata = None;
         let mut sources = Vec::with_capacity(field.sources.len());
-        if field.flatten {
+        if field.flatten && !field.sources.is_empty() {
+            // Flatten field with sources collects all rest key-value pairs of these sources into a map.
+            for source in &field.sources {
+                let source = metadata_source(&salvo, source);
+                sources.push(quote! {
+                    field = field.add_source(#source);
+                });
+            }
+            nested_metadata = Some(quote! {
+                field = field.flatten(true);
+            });
+        } else if field.flatten {
             if let Type::Path(ty) = &field.ty {
                 let ty = omit_type_path_lifetimes(ty);
                 nested_metadata = Some(quote! {
@@ -319,9 +560,6 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
                 });
             }
         }
-        if nested_metadata.is_some() && field.sources.len() > 1 {
-            return Err(Error::new_spanned(name, "Only one source can be from request."));
-        }
         let aliases = field.aliases.iter().map(|alias| {
             quote! {
                 field = field.add_alias(#alias);
@@ -337,6 +575,21 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
                 field = field.serde_rename(#serde_rename);
             }
         });
+        let default = field.default.as_ref().map(|default| {
+            let ty = &field.ty;
+            let value = match default {
+                Some(path) => quote! { #path() },
+                None => quote! { <#ty as ::std::default::Default>::default() },
+            };
+            // The default value is converted through `serde_json::Value`, the span of the field type is kept,
+            // so the error of unimplemented `Serialize` points to the field.
+            let to_value = quote_spanned! {ty.span()=>
+                #salvo::__private::serde_json::to_value::<#ty>
+            };
+            quote! {
+                field = field.default(|| #to_value(#value));
+            }
+        });
         fields.push(quote! {
             let mut field = #salvo::extract::metadata::Field::new(#field_ident);
             #nested_metadata
@@ -344,11 +597,82 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
             #(#aliases)*
             #rename
             #serde_rename
+            #default
             metadata = metadata.add_field(field);
         });
     }
 
     let mt = name.to_string();
+    let secret_fields = args
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.secret)
+        .map(|(index, f)| {
+            f.ident
+                .as_ref()
+                .map(|i| i.to_string())
+                .unwrap_or_else(|| index.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    // Warn once in debug builds, values of secret fields should not be logged even if they are redacted.
+    let secret_warning = quote! {
+        #[cfg(debug_assertions)]
+        {
+            static WARNED: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+            if !WARNED.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+                #salvo::__private::tracing::warn!(
+                    r#type = #mt,
+                    fields = #secret_fields,
+                    "value with secret fields is formatted, the secret fields are redacted"
+                );
+            }
+        }
+    };
+    let secret_warning = args.fields.iter().any(|f| f.secret).then_some(secret_warning);
+    let debug_impl = if !args.debug {
+        None
+    } else if is_tuple {
+        let debug_fields = args.fields.iter().enumerate().map(|(index, field)| {
+            let index = syn::Index::from(index);
+            if field.secret {
+                quote! { .field(&"***") }
+            } else {
+                quote! { .field(&self.#index) }
+            }
+        });
+        Some(quote! {
+            impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    #secret_warning
+                    f.debug_tuple(#mt)
+                        #(#debug_fields)*
+                        .finish()
+                }
+            }
+        })
+    } else {
+        let debug_fields = args.fields.iter().map(|field| {
+            let ident = field.ident.as_ref().expect("field ident should exist");
+            let field_name = ident.to_string();
+            if field.secret {
+                quote! { .field(#field_name, &"***") }
+            } else {
+                quote! { .field(#field_name, &self.#ident) }
+            }
+        });
+        Some(quote! {
+            impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    #secret_warning
+                    f.debug_struct(#mt)
+                        #(#debug_fields)*
+                        .finish()
+                }
+            }
+        })
+    };
     let metadata = quote! {
         fn metadata() ->  &'static #salvo::extract::Metadata {
             static METADATA: #salvo::__private::once_cell::sync::OnceCell<#salvo::extract::Metadata> = #salvo::__private::once_cell::sync::OnceCell::new();
@@ -366,6 +690,128 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
             })
         }
     };
+    // If all fields are read from json body, delegate to `Deserialize` directly, so all serde attributes are honored.
+    let json_body_only = args.default_sources.len() == 1
+        && args.default_sources[0].from == "body"
+        && args.default_sources[0].parser == "json"
+        && args.rename_all.is_none()
+        && args.fields.iter().all(|f| {
+            f.sources.is_empty() && f.aliases.is_empty() && f.rename.is_none() && !f.flatten && f.default.is_none()
+        });
+    let extract_body = if qs_query_only {
+        quote! {
+            req.parse_qs()
+        }
+    } else if json_body_only {
+        quote! {
+            req.parse_json().await
+        }
+    } else {
+        quote! {
+            #salvo::serde::from_request(req, Self::metadata()).await
+        }
+    };
+    // Fields from state source are obtained from depot, other fields are deserialized as a shadow struct
+    // which has the same serde attributes, so the type of state fields needs not to be `Deserialize`.
+    // Fields with `deserialize_with` are deserialized by the function in the shadow struct.
+    let (extract_body, extract_with_depot) = if has_shadow {
+        let serde_crate = format!("{salvo}::__private::serde");
+        let container_attrs = input.attrs.iter().filter(|a| a.path().is_ident("serde"));
+        let syn::Data::Struct(data) = &input.data else {
+            unreachable!("extractible is only derived for struct");
+        };
+        let mut shadow_fields = Vec::new();
+        let mut inits = Vec::new();
+        for (field, info) in data.fields.iter().zip(&args.fields) {
+            let ident = field.ident.as_ref().expect("field ident should exist");
+            let ty = &field.ty;
+            if info.state {
+                let ident_name = ident.to_string();
+                let obtain = if let Some(inner) = option_inner_type(ty) {
+                    quote! { depot.obtain::<#inner>().ok().cloned() }
+                } else {
+                    quote! {
+                        match depot.obtain::<#ty>() {
+                            Ok(value) => ::std::clone::Clone::clone(value),
+                            Err(_) => return Err(#salvo::http::ParseError::other(
+                                format!("state of field `{}` is not found in depot.", #ident_name)
+                            )),
+                        }
+                    }
+                };
+                inits.push(quote! { #ident: #obtain });
+            } else {
+                let attrs = field.attrs.iter().filter(|a| a.path().is_ident("serde"));
+                let deserialize_with = info.deserialize_with.as_ref().map(|path| {
+                    quote! { #[serde(deserialize_with = #path)] }
+                });
+                shadow_fields.push(quote! {
+                    #(#attrs)*
+                    #deserialize_with
+                    #ident: #ty
+                });
+                inits.push(quote! { #ident: shadow.#ident });
+            }
+        }
+        let mt = name.to_string();
+        let unsupported_body = quote! {
+            Err(#salvo::http::ParseError::other(
+                format!("`{}` has fields from depot state, it can only be extracted as handler argument.", #mt)
+            ))
+        };
+        let shadow_body = quote! {{
+            #[derive(#salvo::__private::serde::Deserialize)]
+            #[serde(crate = #serde_crate)]
+            #(#container_attrs)*
+            struct __MacroGenShadow {
+                #(#shadow_fields,)*
+            }
+            let result: Result<Self, #salvo::http::ParseError> = async {
+                let shadow: __MacroGenShadow = (#extract_body)?;
+                Ok(Self {
+                    #(#inits,)*
+                })
+            }
+            .await;
+            result
+        }};
+        if has_state {
+            (unsupported_body, Some(shadow_body))
+        } else {
+            (shadow_body, None)
+        }
+    } else {
+        (extract_body, None)
+    };
+    let (extract_body, extract_with_depot, extract_error) = if let Some(on_error) = &args.on_error {
+        (
+            quote! {
+                (#extract_body).map_err(#on_error)
+            },
+            extract_with_depot.map(|body| {
+                quote! {
+                    (#body).map_err(#on_error)
+                }
+            }),
+            quote! { #salvo::http::StatusError },
+        )
+    } else {
+        (extract_body, extract_with_depot, quote! { #salvo::http::ParseError })
+    };
+    let extract_with_depot = extract_with_depot.map(|body| {
+        quote! {
+            #[allow(refining_impl_trait)]
+            async fn extract_with_depot<'__macro_gen_depot>(
+                req: &'__macro_gen_ex mut #salvo::http::Request,
+                depot: &'__macro_gen_depot #salvo::Depot,
+                _arg: &str,
+            ) -> Result<Self, #extract_error>
+            where
+                Self: Sized {
+                #body
+            }
+        }
+    });
     let life_param = args.generics.lifetimes().next();
     let code = if let Some(life_param) = life_param {
         let ex_life_def = syn::parse_str(&format!("'__macro_gen_ex:{}", life_param.lifetime)).unwrap();
@@ -377,11 +823,13 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
                 #metadata
 
                 #[allow(refining_impl_trait)]
-                async fn extract(req: &'__macro_gen_ex mut #salvo::http::Request) -> Result<Self, #salvo::http::ParseError>
+                async fn extract(req: &'__macro_gen_ex mut #salvo::http::Request) -> Result<Self, #extract_error>
                 where
                     Self: Sized {
-                    #salvo::serde::from_request(req, Self::metadata()).await
+                    #extract_body
                 }
+
+                #extract_with_depot
             }
         }
     } else {
@@ -394,15 +842,64 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
                 #metadata
 
                 #[allow(refining_impl_trait)]
-                async fn extract(req: &'__macro_gen_ex mut #salvo::http::Request) -> Result<Self, #salvo::http::ParseError>
+                async fn extract(req: &'__macro_gen_ex mut #salvo::http::Request) -> Result<Self, #extract_error>
                 where
                     Self: Sized {
-                    #salvo::serde::from_request(req, Self::metadata()).await
+                    #extract_body
                 }
+
+                #extract_with_depot
+            }
+        }
+    };
+    Ok(quote! {
+        #code
+        #debug_impl
+    })
+}
+
+fn is_option_type(ty: &Type) -> bool {
+    if let Type::Path(ty) = ty {
+        ty.qself.is_none() && ty.path.segments.last().map(|s| s.ident == "Option").unwrap_or_default()
+    } else {
+        false
+    }
+}
+
+/// Finds `Debug` in the `derive` attributes which are visible to this derive macro.
+///
+/// Derives in the same attribute as `Extractible`, or in attributes before it, are not visible.
+fn find_derived_debug(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::Path>> {
+    for attr in attrs {
+        if attr.path().is_ident("derive") {
+            let paths = attr.parse_args_with(Punctuated::<syn::Path, Comma>::parse_terminated)?;
+            if let Some(path) = paths
+                .into_iter()
+                .find(|p| p.segments.last().map(|s| s.ident == "Debug").unwrap_or(false))
+            {
+                return Ok(Some(path));
             }
         }
+    }
+    Ok(None)
+}
+
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(ty) = ty else {
+        return None;
     };
-    Ok(code)
+    let segment = ty
+        .path
+        .segments
+        .last()
+        .filter(|s| ty.qself.is_none() && s.ident == "Option")?;
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 fn expr_lit_value(expr: &Expr) -> syn::Result<String> {
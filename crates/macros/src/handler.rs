@@ -1,43 +1,223 @@
-use proc_macro2::{Span, TokenStream};
-use quote::{quote, ToTokens};
-use syn::{Ident, ImplItem, Item, Pat, ReturnType, Signature, Type};
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::token::Comma;
+use syn::{
+    Attribute, GenericParam, Generics, Ident, ImplItem, Item, Meta, Pat, PathArguments, ReturnType, Signature, Token,
+    Type, WherePredicate,
+};
 
 use crate::shared::*;
 
-pub(crate) fn generate(input: Item) -> syn::Result<TokenStream> {
+/// Arguments of `#[handler(...)]` attribute.
+#[derive(Default, Debug)]
+pub(crate) struct HandlerArgs {
+    /// Always run synchronous function in `spawn_blocking`, it is an error if the function is `async` or takes
+    /// reference arguments.
+    pub(crate) blocking: bool,
+    /// Name of the generated struct.
+    pub(crate) name: Option<Ident>,
+}
+impl Parse for HandlerArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for meta in Punctuated::<Meta, Comma>::parse_terminated(input)? {
+            match meta {
+                Meta::Path(path) if path.is_ident("blocking") => {
+                    args.blocking = true;
+                }
+                Meta::NameValue(meta) if meta.path.is_ident("name") => {
+                    let syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(name),
+                        ..
+                    }) = &meta.value
+                    else {
+                        return Err(syn::Error::new_spanned(meta.value, "invalid handler name"));
+                    };
+                    args.name = Some(name.parse()?);
+                }
+                _ => return Err(syn::Error::new_spanned(meta, "unexpected handler attribute")),
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Returns attributes whose names are in `names`, attributes wrapped in `cfg_attr` are also kept, other
+/// attributes in `cfg_attr` are removed because they may not be valid on the generated items.
+fn forwarded_attrs(attrs: &[Attribute], names: &[&str]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter_map(|attr| {
+            if names.iter().any(|id| attr.path().is_ident(id)) {
+                Some(attr.clone())
+            } else if attr.path().is_ident("cfg_attr") {
+                let (predicate, metas) = attr
+                    .parse_args_with(|input: ParseStream| {
+                        let predicate: Meta = input.parse()?;
+                        input.parse::<Token![,]>()?;
+                        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+                        Ok((predicate, metas))
+                    })
+                    .ok()?;
+                let metas = metas
+                    .into_iter()
+                    .filter(|meta| names.iter().any(|id| meta.path().is_ident(id)))
+                    .collect::<Vec<_>>();
+                if metas.is_empty() {
+                    None
+                } else {
+                    Some(syn::parse_quote!(#[cfg_attr(#predicate, #(#metas),*)]))
+                }
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn generate(args: HandlerArgs, input: Item) -> syn::Result<TokenStream> {
     let salvo = salvo_crate();
     match input {
         Item::Fn(mut item_fn) => {
-            let attrs = &item_fn.attrs;
-            let vis = &item_fn.vis;
-            let sig = &mut item_fn.sig;
-            let body = &item_fn.block;
-            let name = &sig.ident;
-            let docs = item_fn
+            let is_deprecated = item_fn.attrs.iter().any(|attr| attr.path().is_ident("deprecated"));
+            // `#[deprecated]` is only forwarded to the struct, calling the function in generated code should not warn.
+            let attrs = item_fn
                 .attrs
                 .iter()
-                .filter(|attr| attr.path().is_ident("doc"))
+                .filter(|attr| !attr.path().is_ident("deprecated"))
                 .cloned()
                 .collect::<Vec<_>>();
+            let sattrs = forwarded_attrs(&item_fn.attrs, &["doc", "deprecated", "allow"]);
+            // `#[cfg]` must be added to all generated items.
+            let cfgs = item_fn
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("cfg"))
+                .cloned()
+                .collect::<Vec<_>>();
+            let allow_deprecated = is_deprecated.then(|| quote!(#[allow(deprecated)]));
+            let vis = &item_fn.vis;
+            let sig = &mut item_fn.sig;
+            let body = &item_fn.block;
+            let name = args.name.clone().unwrap_or_else(|| sig.ident.clone());
 
-            let sdef = quote! {
-                #(#docs)*
-                #[allow(non_camel_case_types)]
-                #[derive(Debug)]
-                #vis struct #name;
-                impl #name {
-                    #(#attrs)*
-                    #sig {
-                        #body
+            // Type and const parameters of function are moved to the generated struct. Lifetime parameters are
+            // kept on the function, they are inferred from the borrowed request when it is called, the struct can
+            // not hold them because `Handler` requires `'static`.
+            let generics =
+                if sig.generics.type_params().next().is_none() && sig.generics.const_params().next().is_none() {
+                    None
+                } else {
+                    Some(split_lifetimes(&mut sig.generics))
+                };
+            let sdef = if let Some(generics) = &generics {
+                let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+                let type_params = generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+                let name_str = name.to_string();
+                quote! {
+                    #(#cfgs)*
+                    #(#sattrs)*
+                    #[allow(non_camel_case_types)]
+                    #vis struct #name #impl_generics (::std::marker::PhantomData<fn() -> (#(#type_params,)*)>) #where_clause;
+                    #(#cfgs)*
+                    #allow_deprecated
+                    impl #impl_generics #name #ty_generics #where_clause {
+                        /// Create new handler.
+                        #[inline]
+                        #vis fn new() -> Self {
+                            Self(::std::marker::PhantomData)
+                        }
+                        #(#attrs)*
+                        #sig {
+                            #body
+                        }
+                    }
+                    #(#cfgs)*
+                    #allow_deprecated
+                    impl #impl_generics ::std::default::Default for #name #ty_generics #where_clause {
+                        fn default() -> Self {
+                            Self(::std::marker::PhantomData)
+                        }
+                    }
+                    #(#cfgs)*
+                    #allow_deprecated
+                    impl #impl_generics ::std::clone::Clone for #name #ty_generics #where_clause {
+                        fn clone(&self) -> Self {
+                            *self
+                        }
+                    }
+                    #(#cfgs)*
+                    #allow_deprecated
+                    impl #impl_generics ::std::marker::Copy for #name #ty_generics #where_clause {}
+                    #(#cfgs)*
+                    #allow_deprecated
+                    impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+                        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                            f.write_str(#name_str)
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    #(#cfgs)*
+                    #(#sattrs)*
+                    #[allow(non_camel_case_types)]
+                    #[derive(Clone, Copy, Debug)]
+                    #vis struct #name;
+                    #(#cfgs)*
+                    #allow_deprecated
+                    impl #name {
+                        #(#attrs)*
+                        #sig {
+                            #body
+                        }
                     }
                 }
             };
 
-            let hfn = handle_fn(&salvo, sig)?;
+            if args.blocking && sig.asyncness.is_some() {
+                return Err(syn::Error::new_spanned(
+                    sig.asyncness,
+                    "`blocking` handler must be a synchronous function",
+                ));
+            }
+            // Synchronous function which only takes extracted arguments is called in `spawn_blocking`, so it does
+            // not block the async runtime. Function takes references is called inline as before.
+            let blocking = args.blocking
+                || (sig.asyncness.is_none()
+                    && sig
+                        .inputs
+                        .iter()
+                        .all(|input| matches!(parse_input_type(input), InputType::NoReference(_))));
+            let hfn = handle_fn(&salvo, sig, blocking)?;
+            let himpl = if let Some(mut generics) = generics {
+                // `Handler` requires `'static`.
+                let type_params = generics
+                    .type_params()
+                    .map(|param| param.ident.clone())
+                    .collect::<Vec<_>>();
+                let where_clause = generics.make_where_clause();
+                for param in &type_params {
+                    where_clause.predicates.push(syn::parse_quote!(#param: 'static));
+                }
+                let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+                quote! {
+                    impl #impl_generics #salvo::Handler for #name #ty_generics #where_clause
+                }
+            } else {
+                quote! {
+                    impl #salvo::Handler for #name
+                }
+            };
             Ok(quote! {
                 #sdef
+                #(#cfgs)*
+                #allow_deprecated
                 #[#salvo::async_trait]
-                impl #salvo::Handler for #name {
+                #himpl {
                     #hfn
                 }
             })
@@ -55,12 +235,21 @@ pub(crate) fn generate(input: Item) -> syn::Result<TokenStream> {
                 return Err(syn::Error::new_spanned(item_impl.impl_token, "missing handle function"));
             }
             let hmtd = hmtd.unwrap();
-            let hfn = handle_fn(&salvo, &hmtd.sig)?;
+            if args.blocking || args.name.is_some() {
+                return Err(syn::Error::new_spanned(
+                    item_impl.impl_token,
+                    "`blocking` and `name` can only be used on `fn`",
+                ));
+            }
+            let hfn = handle_fn(&salvo, &hmtd.sig, false)?;
             let ty = &item_impl.self_ty;
             let (impl_generics, _, where_clause) = &item_impl.generics.split_for_impl();
+            // `#[cfg]` and lint attributes of the impl block are also applied to the generated `Handler` impl.
+            let hattrs = forwarded_attrs(&item_impl.attrs, &["cfg", "allow"]);
 
             Ok(quote! {
                 #item_impl
+                #(#hattrs)*
                 #[#salvo::async_trait]
                 impl #impl_generics #salvo::Handler for #ty #where_clause {
                     #hfn
@@ -74,8 +263,56 @@ pub(crate) fn generate(input: Item) -> syn::Result<TokenStream> {
     }
 }
 
-fn handle_fn(salvo: &Ident, sig: &Signature) -> syn::Result<TokenStream> {
+/// Moves type and const parameters out of `generics` and returns them, lifetime parameters and their
+/// predicates are left in `generics`.
+fn split_lifetimes(generics: &mut Generics) -> Generics {
+    let mut moved = Generics::default();
+    for param in std::mem::take(&mut generics.params) {
+        if matches!(param, GenericParam::Lifetime(_)) {
+            generics.params.push(param);
+        } else {
+            moved.params.push(param);
+        }
+    }
+    if let Some(where_clause) = generics.where_clause.take() {
+        let (lifetime_predicates, predicates): (Vec<_>, Vec<_>) = where_clause
+            .predicates
+            .into_iter()
+            .partition(|predicate| matches!(predicate, WherePredicate::Lifetime(_)));
+        if !lifetime_predicates.is_empty() {
+            generics.make_where_clause().predicates.extend(lifetime_predicates);
+        }
+        if !predicates.is_empty() {
+            moved.make_where_clause().predicates.extend(predicates);
+        }
+    }
+    if generics.params.is_empty() {
+        generics.lt_token = None;
+        generics.gt_token = None;
+    } else {
+        generics.lt_token = Some(Default::default());
+        generics.gt_token = Some(Default::default());
+    }
+    moved.lt_token = Some(Default::default());
+    moved.gt_token = Some(Default::default());
+    moved
+}
+
+fn handle_fn(salvo: &Ident, sig: &Signature, blocking: bool) -> syn::Result<TokenStream> {
     let name = &sig.ident;
+    if blocking {
+        if let Some(input) = sig
+            .inputs
+            .iter()
+            .find(|input| !matches!(parse_input_type(input), InputType::NoReference(_)))
+        {
+            return Err(syn::Error::new_spanned(
+                input,
+                "`blocking` handler can only take extracted arguments, they are moved into `spawn_blocking`, \
+                so the function must be `Fn + Send + 'static`",
+            ));
+        }
+    }
     let mut extract_ts = Vec::with_capacity(sig.inputs.len());
     let mut call_args: Vec<Ident> = Vec::with_capacity(sig.inputs.len());
     for input in &sig.inputs {
@@ -94,36 +331,58 @@ fn handle_fn(salvo: &Ident, sig: &Signature) -> syn::Result<TokenStream> {
             }
             InputType::Unknown => {
                 return Err(syn::Error::new_spanned(
-                    &sig.inputs,
-                    "the inputs parameters must be Request, Depot, Response or FlowCtrl",
+                    input,
+                    format!(
+                        "argument `{}` is not supported, reference argument must be `&mut Request`, `&mut Depot`, \
+                        `&mut Response` or `&mut FlowCtrl`",
+                        input.to_token_stream()
+                    ),
                 ))
             }
             InputType::NoReference(pat) => {
-                if let (Pat::Ident(ident), Type::Path(ty)) = (&*pat.pat, &*pat.ty) {
-                    call_args.push(ident.ident.clone());
-                    let ty = omit_type_path_lifetimes(ty);
-                    let idv = pat.pat.to_token_stream().to_string();
-                    let idv = idv.rsplit_once(' ').map(|(_, v)| v.to_owned()).unwrap_or(idv);
-                    let id = Ident::new(&idv, Span::call_site());
-                    let idv = idv.trim_start_matches('_');
+                let Type::Path(ty) = &*pat.ty else {
+                    return Err(syn::Error::new_spanned(
+                        pat,
+                        format!(
+                            "argument `{}` is not supported, argument must be a type implementing `Extractible`",
+                            pat.to_token_stream()
+                        ),
+                    ));
+                };
+                // Destructuring pattern like `Json(body): Json<T>` is bound in handler function itself,
+                // so the extracted value is passed by a generated ident, and the inner ident is used as the name.
+                let (id, idv) = match &*pat.pat {
+                    Pat::Ident(ident) => (ident.ident.clone(), ident.ident.to_string()),
+                    Pat::TupleStruct(ts) => match ts.elems.first() {
+                        Some(Pat::Ident(inner)) if ts.elems.len() == 1 => {
+                            let idv = inner.ident.to_string();
+                            (format_ident!("__macro_gen_arg_{}", idv), idv)
+                        }
+                        _ => return Err(syn::Error::new_spanned(pat, "invalid param definition")),
+                    },
+                    _ => return Err(syn::Error::new_spanned(pat, "invalid param definition")),
+                };
+                call_args.push(id.clone());
+                let idv = idv.trim_start_matches('_');
+                // Keep span of the argument type, so error of unimplemented `Extractible` points to the argument.
+                let span = pat.ty.span();
+                let ty = respan(omit_type_path_lifetimes(ty).into_token_stream(), span);
+                let extract = quote_spanned! {span=> <#ty as #salvo::Extractible>::extract_with_depot(__macro_gen_req, __macro_gen_depot, #idv).await };
 
-                    extract_ts.push(quote!{
-                        let #id: #ty = match <#ty as #salvo::Extractible>::extract_with_arg(__macro_gen_req, #idv).await {
-                            Ok(data) => data,
-                            Err(e) => {
-                                e.write(__macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
-                                // If status code is not set or is not error, set it to 400.
-                                let status_code = __macro_gen_res.status_code.unwrap_or_default();
-                                if !status_code.is_client_error() && !status_code.is_server_error() {
-                                    __macro_gen_res.status_code(#salvo::http::StatusCode::BAD_REQUEST);
-                                }
-                                return;
+                extract_ts.push(quote! {
+                    let #id: #ty = match #extract {
+                        Ok(data) => data,
+                        Err(e) => {
+                            e.write(__macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
+                            // If status code is not set or is not error, set it to 400.
+                            let status_code = __macro_gen_res.status_code.unwrap_or_default();
+                            if !status_code.is_client_error() && !status_code.is_server_error() {
+                                __macro_gen_res.status_code(#salvo::http::StatusCode::BAD_REQUEST);
                             }
-                        };
-                    });
-                } else {
-                    return Err(syn::Error::new_spanned(pat, "invalid param definition"));
-                }
+                            return;
+                        }
+                    };
+                });
             }
             InputType::Receiver(_) => {
                 call_args.push(Ident::new("self", Span::call_site()));
@@ -131,40 +390,68 @@ fn handle_fn(salvo: &Ident, sig: &Signature) -> syn::Result<TokenStream> {
         }
     }
 
-    match sig.output {
-        ReturnType::Default => {
-            if sig.asyncness.is_none() {
-                Ok(quote! {
-                    async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
-                        #(#extract_ts)*
-                        Self::#name(#(#call_args),*)
-                    }
-                })
-            } else {
-                Ok(quote! {
-                    async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
-                        #(#extract_ts)*
-                        Self::#name(#(#call_args),*).await
-                    }
-                })
+    let call = if blocking {
+        quote! {
+            match #salvo::__private::tokio::task::spawn_blocking(move || Self::#name(#(#call_args),*)).await {
+                Ok(data) => data,
+                Err(e) => {
+                    #salvo::__private::tracing::error!(error = ?e, "blocking handler failed");
+                    __macro_gen_res.render(#salvo::http::StatusError::internal_server_error().cause(e));
+                    return;
+                }
             }
         }
-        ReturnType::Type(_, _) => {
-            if sig.asyncness.is_none() {
-                Ok(quote! {
-                    async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
-                        #(#extract_ts)*
-                        #salvo::Writer::write(Self::#name(#(#call_args),*), __macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
-                    }
-                })
+    } else if sig.asyncness.is_none() {
+        quote! { Self::#name(#(#call_args),*) }
+    } else {
+        quote! { Self::#name(#(#call_args),*).await }
+    };
+    match &sig.output {
+        ReturnType::Default => Ok(quote! {
+            async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
+                #(#extract_ts)*
+                #call
+            }
+        }),
+        ReturnType::Type(_, ty) => {
+            // `Vec<T>` is rendered as json array, `T` must implement `Serialize`.
+            let call = if is_vec_type(ty) {
+                let json = quote_spanned! { ty.span()=> #salvo::writing::Json };
+                quote! { #json(#call) }
             } else {
-                Ok(quote! {
-                    async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
-                        #(#extract_ts)*
-                        #salvo::Writer::write(Self::#name(#(#call_args),*).await, __macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
-                    }
-                })
+                call
+            };
+            Ok(quote! {
+                async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
+                    #(#extract_ts)*
+                    #salvo::Writer::write(#call, __macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
+                }
+            })
+        }
+    }
+}
+
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut new_group = Group::new(group.delimiter(), respan(group.stream(), span));
+                new_group.set_span(span);
+                token = TokenTree::Group(new_group);
+            } else {
+                token.set_span(span);
             }
+            token
+        })
+        .collect()
+}
+
+fn is_vec_type(ty: &Type) -> bool {
+    if let Type::Path(ty) = ty {
+        if let Some(segment) = ty.path.segments.last() {
+            return segment.ident == "Vec" && matches!(segment.arguments, PathArguments::AngleBracketed(_));
         }
     }
+    false
 }
@@ -16,6 +16,7 @@ use syn::{parse_macro_input, DeriveInput, Item};
 mod attribute;
 mod extract;
 mod handler;
+mod multipart;
 mod shared;
 
 pub(crate) use salvo_serde_util as serde_util;
@@ -26,18 +27,34 @@ use shared::*;
 /// `Handler` is a trait, if `#[handler]` applied to `fn`,  `fn` will converted to a struct, and then implement `Handler`,
 /// after use `handler`, you don't need to care arguments' order, omit unused arguments.
 ///
+/// Synchronous function whose arguments are all extracted types is run in `tokio::task::spawn_blocking`,
+/// synchronous function takes references like `&mut Response` is called inline. Use `#[handler(blocking)]`
+/// to require the function to be run in `spawn_blocking`.
+///
+/// The arguments are moved into the blocking task, so they and the return type must be `Send + 'static`,
+/// otherwise the generated code fails to compile. Every call moves to tokio's blocking thread pool and back,
+/// which costs a thread hand-off per request and occupies a pool thread until the function returns, so cheap
+/// synchronous functions should be written as `async fn` instead.
+///
+/// Use `#[handler(name = "MyHandler")]` to specify the name of the generated struct, the function name is
+/// used by default.
+///
 /// View `salvo_core::handler` for more details.
 #[proc_macro_attribute]
-pub fn handler(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn handler(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as handler::HandlerArgs);
     let item = parse_macro_input!(input as Item);
-    match handler::generate(item) {
+    match handler::generate(args, item) {
         Ok(stream) => stream.into(),
         Err(e) => e.to_compile_error().into(),
     }
 }
 
 /// Generate code for extractible type.
-#[proc_macro_derive(Extractible, attributes(salvo))]
+///
+/// Fields with `#[salvo(extract(source(from = "state")))]` are obtained from `Depot`, so the type can only be
+/// extracted as handler argument, `Extractible::extract` returns an error at runtime for it.
+#[proc_macro_derive(Extractible, attributes(salvo, serde))]
 pub fn derive_extractible(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as DeriveInput);
     match extract::generate(args) {
@@ -46,6 +63,18 @@ pub fn derive_extractible(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Generate code for loading type from `multipart/form-data` request.
+///
+/// Use `#[salvo(multipart(rename = "name"))]` to specify the part name of a field.
+#[proc_macro_derive(FromMultipart, attributes(salvo))]
+pub fn derive_from_multipart(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as DeriveInput);
+    match multipart::generate(args) {
+        Ok(stream) => stream.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use quote::quote;
@@ -63,10 +92,10 @@ mod tests {
         };
         let item = parse2(input).unwrap();
         assert_eq!(
-            handler::generate(item).unwrap().to_string(),
+            handler::generate(Default::default(), item).unwrap().to_string(),
             quote! {
                 #[allow(non_camel_case_types)]
-                #[derive(Debug)]
+                #[derive(Clone, Copy, Debug)]
                 struct hello;
                 impl hello {
                     #[handler]
@@ -103,10 +132,10 @@ mod tests {
         };
         let item = parse2(input).unwrap();
         assert_eq!(
-            handler::generate(item).unwrap().to_string(),
+            handler::generate(Default::default(), item).unwrap().to_string(),
             quote!{
                 #[allow(non_camel_case_types)]
-                #[derive(Debug)]
+                #[derive(Clone, Copy, Debug)]
                 struct hello;
                 impl hello {
                     #[handler]
@@ -132,6 +161,233 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_handler_for_fn_forward_attrs() {
+        let input = quote! {
+            /// Say hello.
+            #[deprecated(note = "use hello2")]
+            #[allow(clippy::unused_async)]
+            async fn hello() -> &'static str {
+                "hello"
+            }
+        };
+        let item = parse2(input).unwrap();
+        assert_eq!(
+            handler::generate(Default::default(), item).unwrap().to_string(),
+            quote! {
+                #[doc = r" Say hello."]
+                #[deprecated(note = "use hello2")]
+                #[allow(clippy::unused_async)]
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy, Debug)]
+                struct hello;
+                #[allow(deprecated)]
+                impl hello {
+                    #[doc = r" Say hello."]
+                    #[allow(clippy::unused_async)]
+                    async fn hello() -> &'static str {
+                        {"hello"}
+                    }
+                }
+                #[allow(deprecated)]
+                #[salvo::async_trait]
+                impl salvo::Handler for hello {
+                    async fn handle(
+                        &self,
+                        __macro_gen_req: &mut salvo::Request,
+                        __macro_gen_depot: &mut salvo::Depot,
+                        __macro_gen_res: &mut salvo::Response,
+                        __macro_gen_ctrl: &mut salvo::FlowCtrl
+                    ) {
+                        salvo::Writer::write(Self::hello().await, __macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_handler_for_fn_forward_cfg() {
+        let input = quote! {
+            #[cfg(test)]
+            #[cfg_attr(test, allow(dead_code))]
+            async fn hello() {}
+        };
+        let item = parse2(input).unwrap();
+        assert_eq!(
+            handler::generate(Default::default(), item).unwrap().to_string(),
+            quote! {
+                #[cfg(test)]
+                #[cfg_attr(test, allow(dead_code))]
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy, Debug)]
+                struct hello;
+                #[cfg(test)]
+                impl hello {
+                    #[cfg(test)]
+                    #[cfg_attr(test, allow(dead_code))]
+                    async fn hello() {
+                        {}
+                    }
+                }
+                #[cfg(test)]
+                #[salvo::async_trait]
+                impl salvo::Handler for hello {
+                    async fn handle(
+                        &self,
+                        __macro_gen_req: &mut salvo::Request,
+                        __macro_gen_depot: &mut salvo::Depot,
+                        __macro_gen_res: &mut salvo::Response,
+                        __macro_gen_ctrl: &mut salvo::FlowCtrl
+                    ) {
+                        Self::hello().await
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_handler_for_fn_filter_cfg_attr() {
+        let input = quote! {
+            #[cfg(test)]
+            #[cfg_attr(test, inline, allow(dead_code))]
+            #[cfg_attr(test, must_use)]
+            async fn hello() {}
+        };
+        let item = parse2(input).unwrap();
+        assert_eq!(
+            handler::generate(Default::default(), item).unwrap().to_string(),
+            quote! {
+                #[cfg(test)]
+                #[cfg_attr(test, allow(dead_code))]
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy, Debug)]
+                struct hello;
+                #[cfg(test)]
+                impl hello {
+                    #[cfg(test)]
+                    #[cfg_attr(test, inline, allow(dead_code))]
+                    #[cfg_attr(test, must_use)]
+                    async fn hello() {
+                        {}
+                    }
+                }
+                #[cfg(test)]
+                #[salvo::async_trait]
+                impl salvo::Handler for hello {
+                    async fn handle(
+                        &self,
+                        __macro_gen_req: &mut salvo::Request,
+                        __macro_gen_depot: &mut salvo::Depot,
+                        __macro_gen_res: &mut salvo::Response,
+                        __macro_gen_ctrl: &mut salvo::FlowCtrl
+                    ) {
+                        Self::hello().await
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_handler_for_fn_destructure_arg() {
+        let input = quote! {
+            async fn show(PathParam(id): PathParam<u64>) -> String {
+                id.to_string()
+            }
+        };
+        let item = parse2(input).unwrap();
+        let code = handler::generate(Default::default(), item).unwrap().to_string();
+        assert!(code.contains(
+            &quote! {
+                let __macro_gen_arg_id: PathParam<u64> = match <PathParam<u64> as salvo::Extractible>::extract_with_depot(__macro_gen_req, __macro_gen_depot, "id").await
+            }
+            .to_string()
+        ));
+        assert!(code.contains(&quote! { Self::show(__macro_gen_arg_id).await }.to_string()));
+
+        let input = quote! {
+            async fn show(PathParam(id, name): PathParam<u64>) {}
+        };
+        assert!(handler::generate(Default::default(), parse2(input).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_handler_blocking() {
+        let args: handler::HandlerArgs = parse2(quote! { blocking }).unwrap();
+        let input = quote! {
+            fn hello(res: &mut Response) {}
+        };
+        assert!(handler::generate(args, parse2(input).unwrap()).is_err());
+
+        let args: handler::HandlerArgs = parse2(quote! { blocking }).unwrap();
+        let input = quote! {
+            fn hello(id: PathParam<i64>, res: &mut Response) {}
+        };
+        let err = handler::generate(args, parse2(input).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("`Fn + Send + 'static`"));
+
+        let input = quote! {
+            fn hello(id: PathParam<i64>, res: &mut Response) {}
+        };
+        let code = handler::generate(Default::default(), parse2(input).unwrap())
+            .unwrap()
+            .to_string();
+        assert!(!code.contains("spawn_blocking"));
+        assert!(code.contains(&quote! { Self::hello(id, __macro_gen_res) }.to_string()));
+
+        let input = quote! {
+            fn hello(id: PathParam<i64>) -> String {}
+        };
+        let code = handler::generate(Default::default(), parse2(input).unwrap())
+            .unwrap()
+            .to_string();
+        assert!(code.contains(&quote! { spawn_blocking(move || Self::hello(id)) }.to_string()));
+
+        let args: handler::HandlerArgs = parse2(quote! { blocking }).unwrap();
+        let input = quote! {
+            async fn hello() {}
+        };
+        assert!(handler::generate(args, parse2(input).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_handler_unknown_arg() {
+        let input = quote! {
+            async fn hello(bar: &mut Banana) {}
+        };
+        let err = handler::generate(Default::default(), parse2(input).unwrap()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("argument `bar : & mut Banana` is not supported"));
+    }
+
+    #[test]
+    fn test_handler_for_impl_forward_cfg() {
+        let input = quote! {
+            #[cfg(feature = "admin")]
+            #[allow(dead_code)]
+            impl Hello {
+                async fn handle(&self) {}
+            }
+        };
+        let item = parse2(input).unwrap();
+        let code = handler::generate(Default::default(), item).unwrap().to_string();
+        assert!(code.contains(
+            &quote! {
+                #[cfg(feature = "admin")]
+                #[allow(dead_code)]
+                #[salvo::async_trait]
+                impl salvo::Handler for Hello
+            }
+            .to_string()
+        ));
+    }
+
     #[test]
     fn test_handler_for_impl() {
         let input = quote! {
@@ -144,7 +400,7 @@ mod tests {
         };
         let item = parse2(input).unwrap();
         assert_eq!(
-            handler::generate(item).unwrap().to_string(),
+            handler::generate(Default::default(), item).unwrap().to_string(),
             quote! {
                 #[handler]
                 impl Hello {
@@ -172,7 +428,7 @@ mod tests {
     #[test]
     fn test_extract_simple() {
         let input = quote! {
-            #[salvo(extract(default_source(from = "body")))]
+            #[salvo(extract(default_source(from = "body"), compile_check = "required"))]
             struct BadMan<'a> {
                 #[salvo(extract(source(from = "query")))]
                 id: i64,
@@ -222,7 +478,8 @@ mod tests {
             #[salvo(extract(
                 default_source(from = "query"),
                 default_source(from = "param"),
-                default_source(from = "body")
+                default_source(from = "body"),
+                compile_check = "required"
             ))]
             struct BadMan<'a> {
                 id: i64,
@@ -278,4 +535,153 @@ mod tests {
             .to_string()
         );
     }
+
+    #[test]
+    fn test_extract_compile_check() {
+        let input = quote! {
+            #[salvo(extract(default_source(from = "query")))]
+            struct BadMan {
+                id: i64,
+                name: Option<String>,
+            }
+        };
+        let item = parse2(input).unwrap();
+        assert!(extract::generate(item).is_err());
+
+        let input = quote! {
+            #[salvo(extract(default_source(from = "query"), compile_check = "required"))]
+            struct BadMan {
+                id: i64,
+                name: Option<String>,
+            }
+        };
+        let item = parse2(input).unwrap();
+        assert!(extract::generate(item).is_ok());
+
+        let input = quote! {
+            #[salvo(extract(default_source(from = "query")))]
+            struct BadMan {
+                #[salvo(extract(compile_check = "required"))]
+                id: i64,
+                #[serde(default)]
+                age: u8,
+                name: Option<String>,
+            }
+        };
+        let item = parse2(input).unwrap();
+        assert!(extract::generate(item).is_ok());
+    }
+
+    #[test]
+    fn test_extract_secret_with_derived_debug() {
+        let input = quote! {
+            #[derive(Debug)]
+            #[salvo(extract(default_source(from = "body"), compile_check = "required", debug))]
+            struct Login {
+                username: String,
+                #[salvo(extract(secret))]
+                password: String,
+            }
+        };
+        let item = parse2(input).unwrap();
+        let err = extract::generate(item).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Debug` is implemented by `Extractible` to redact secret fields, remove it from derive."
+        );
+
+        let input = quote! {
+            #[salvo(extract(default_source(from = "body"), compile_check = "required"))]
+            struct Login {
+                username: String,
+                #[salvo(extract(secret))]
+                password: String,
+            }
+        };
+        let item = parse2(input).unwrap();
+        let err = extract::generate(item).unwrap_err();
+        assert!(err.to_string().contains("add `#[salvo(extract(debug))]` to the struct"));
+    }
+
+    #[test]
+    fn test_extract_invalid_source() {
+        let input = quote! {
+            struct BadMan {
+                #[salvo(extract(source(from = "bodey")))]
+                id: i64,
+            }
+        };
+        let item = parse2(input).unwrap();
+        let err = extract::generate(item).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "source from is invalid: `bodey`, expected one of: param, query, header, cookie, body, state, did you mean `body`?"
+        );
+
+        let input = quote! {
+            #[salvo(extract(default_source(from = "cookie", parse = "xml")))]
+            struct BadMan {
+                id: i64,
+            }
+        };
+        let item = parse2(input).unwrap();
+        let err = extract::generate(item).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "source parser is invalid: `xml`, expected one of: multimap, json, smart, qs"
+        );
+    }
+
+    #[test]
+    fn test_extract_default() {
+        let input = quote! {
+            #[salvo(extract(default_source(from = "query")))]
+            struct BadMan {
+                #[salvo(extract(default))]
+                page: u32,
+                #[salvo(extract(default = "default_size"))]
+                size: u32,
+            }
+        };
+        let item = parse2(input).unwrap();
+        assert!(extract::generate(item).is_ok());
+
+        let input = quote! {
+            #[salvo(extract(default_source(from = "query")))]
+            struct BadMan {
+                #[salvo(extract(default))]
+                page: Option<u32>,
+            }
+        };
+        let item = parse2(input).unwrap();
+        assert!(extract::generate(item).is_err());
+    }
+
+    #[test]
+    fn test_from_multipart() {
+        let input = quote! {
+            struct Upload {
+                title: String,
+                #[salvo(multipart(rename = "file"))]
+                avatar: Option<UploadedFile>,
+            }
+        };
+        let item = parse2(input).unwrap();
+        assert_eq!(
+            multipart::generate(item).unwrap().to_string(),
+            quote! {
+                impl salvo::extract::multipart::FromMultipart for Upload {
+                    async fn from_multipart(
+                        __macro_gen_form_data: &salvo::http::form::FormData,
+                    ) -> ::std::result::Result<Self, salvo::http::ParseError> {
+                        ::std::result::Result::Ok(Self {
+                            title: salvo::extract::multipart::required::<String>(__macro_gen_form_data, "title").await?,
+                            avatar: salvo::extract::multipart::required::<Option<UploadedFile> >(__macro_gen_form_data, "file").await?,
+                        })
+                    }
+                }
+            }
+            .to_string()
+        );
+    }
 }
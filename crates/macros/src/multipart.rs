@@ -0,0 +1,79 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{DeriveInput, Error, Expr, ExprLit, Fields, Lit, Meta};
+
+use crate::{attribute, salvo_crate};
+
+fn field_rename(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut rename = None;
+    for attr in &field.attrs {
+        if attr.path().is_ident("salvo") {
+            if let Some(metas) = attribute::find_nested_list(attr, "multipart")? {
+                let nested = metas.parse_args_with(Punctuated::<Meta, Comma>::parse_terminated)?;
+                for meta in nested {
+                    match meta {
+                        Meta::NameValue(meta) if meta.path.is_ident("rename") => {
+                            if let Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) = &meta.value {
+                                rename = Some(s.value());
+                            } else {
+                                return Err(Error::new_spanned(meta.value, "invalid rename expression"));
+                            }
+                        }
+                        _ => return Err(Error::new_spanned(meta, "unexpected attribute")),
+                    }
+                }
+            }
+        }
+    }
+    Ok(rename)
+}
+
+pub(crate) fn generate(input: DeriveInput) -> syn::Result<TokenStream> {
+    let salvo = salvo_crate();
+    let ident = &input.ident;
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(Error::new_spanned(
+                ident,
+                "from multipart can only be applied to an struct with named fields.",
+            ));
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "from multipart can not be applied to generic struct.",
+        ));
+    }
+
+    let mut values = Vec::with_capacity(fields.len());
+    for field in fields {
+        let field_ident = field.ident.as_ref().expect("named field should have ident");
+        let name = match field_rename(field)? {
+            Some(rename) => rename,
+            None => field_ident.to_string().trim_start_matches("r#").to_owned(),
+        };
+        let ty = &field.ty;
+        values.push(quote! {
+            #field_ident: #salvo::extract::multipart::required::<#ty>(__macro_gen_form_data, #name).await?
+        });
+    }
+
+    Ok(quote! {
+        impl #salvo::extract::multipart::FromMultipart for #ident {
+            async fn from_multipart(
+                __macro_gen_form_data: &#salvo::http::form::FormData,
+            ) -> ::std::result::Result<Self, #salvo::http::ParseError> {
+                ::std::result::Result::Ok(Self {
+                    #(#values,)*
+                })
+            }
+        }
+    })
+}
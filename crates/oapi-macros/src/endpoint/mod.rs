@@ -200,7 +200,7 @@ fn handle_fn(salvo: &Ident, oapi: &Ident, sig: &Signature) -> syn::Result<(Token
                     let id = Ident::new(&idv, Span::call_site());
                     let idv = idv.trim_start_matches('_');
                     extract_ts.push(quote!{
-                        let #id: #ty = match <#ty as #salvo::Extractible>::extract_with_arg(__macro_gen_req, #idv).await {
+                        let #id: #ty = match <#ty as #salvo::Extractible>::extract_with_depot(__macro_gen_req, __macro_gen_depot, #idv).await {
                             Ok(data) => {
                                 data
                             },
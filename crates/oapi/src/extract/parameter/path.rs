@@ -173,6 +173,25 @@ mod tests {
         assert_eq!(result.unwrap().0, "param");
     }
 
+    #[tokio::test]
+    async fn test_path_param_destructure_in_handler() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::ResponseExt;
+
+        #[handler]
+        async fn show(PathParam(id): PathParam<u64>) -> String {
+            format!("id: {id}")
+        }
+        let router = Router::with_path("users/<id>").get(show);
+        let content = TestClient::get("http://127.0.0.1:5801/users/42")
+            .send(router)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "id: 42");
+    }
+
     #[test]
     fn test_path_param_register() {
         let mut components = Components::new();
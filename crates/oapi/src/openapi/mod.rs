@@ -1086,6 +1086,39 @@ mod tests {
         assert_eq!(deserialize_result.unwrap(), Required::False);
     }
 
+    #[test]
+    fn test_merge_nested_router_endpoints() {
+        #[derive(serde::Serialize, ToSchema)]
+        struct User {
+            id: u64,
+            name: String,
+        }
+
+        /// Get user by ID.
+        #[salvo_oapi::endpoint(tags("users"), operation_id = "getUserById")]
+        async fn get_user(id: PathParam<u64>) -> Json<User> {
+            Json(User {
+                id: id.into_inner(),
+                name: "salvo".into(),
+            })
+        }
+
+        let router = Router::with_path("api").push(Router::with_path("users/{id}").get(get_user));
+        let doc = OpenApi::new("user api", "0.1.0").merge_router(&router);
+        let value = serde_json::to_value(&doc).unwrap();
+
+        let operation = &value["paths"]["/api/users/{id}"]["get"];
+        assert_eq!(operation["operationId"], "getUserById");
+        assert_eq!(operation["summary"], "Get user by ID.");
+        assert_eq!(operation["tags"], json!(["users"]));
+        assert_eq!(operation["parameters"][0]["name"], "id");
+        assert!(value["components"]["schemas"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .any(|name| name.ends_with("User")));
+    }
+
     #[tokio::test]
     async fn test_openapi_handle() {
         let doc = OpenApi::new("pet api", "0.1.0");
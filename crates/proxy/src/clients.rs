@@ -15,15 +15,7 @@ pub struct HyperClient {
 }
 impl Default for HyperClient {
     fn default() -> Self {
-        let https = HttpsConnectorBuilder::new()
-            .with_native_roots()
-            .expect("no native root CA certificates found")
-            .https_only()
-            .enable_http1()
-            .build();
-        Self {
-            inner: HyperUtilClient::builder(TokioExecutor::new()).build(https),
-        }
+        Self::build(false)
     }
 }
 impl HyperClient {
@@ -31,6 +23,28 @@ impl HyperClient {
     pub fn new(inner: HyperUtilClient<HttpsConnector<HttpConnector>, ReqBody>) -> Self {
         Self { inner }
     }
+
+    /// Create a new `HyperClient` which only connects to `https` upstreams.
+    ///
+    /// The default client connects to both `https` and plain `http` upstreams.
+    pub fn https_only() -> Self {
+        Self::build(true)
+    }
+
+    fn build(https_only: bool) -> Self {
+        let builder = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .expect("no native root CA certificates found");
+        let builder = if https_only {
+            builder.https_only()
+        } else {
+            builder.https_or_http()
+        };
+        let https = builder.enable_http1().build();
+        Self {
+            inner: HyperUtilClient::builder(TokioExecutor::new()).build(https),
+        }
+    }
 }
 
 impl super::Client for HyperClient {
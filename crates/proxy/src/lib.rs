@@ -16,9 +16,9 @@ use std::future::Future;
 
 use hyper::upgrade::OnUpgrade;
 use percent_encoding::{utf8_percent_encode, CONTROLS};
-use salvo_core::http::header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, HOST, UPGRADE};
+use salvo_core::http::header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, HOST, UPGRADE, VIA};
 use salvo_core::http::uri::Uri;
-use salvo_core::http::{ReqBody, ResBody, StatusCode};
+use salvo_core::http::{ReqBody, ResBody, StatusError, Version};
 use salvo_core::{async_trait, BoxedError, Depot, Error, FlowCtrl, Handler, Request, Response};
 
 mod clients;
@@ -126,6 +126,8 @@ where
     pub url_path_getter: UrlPartGetter,
     /// Url query getter.
     pub url_query_getter: UrlPartGetter,
+    /// Whether to keep the forwarding headers of incoming requests.
+    pub trust_forwarded_headers: bool,
 }
 impl<U> Proxy<U, HyperClient>
 where
@@ -151,9 +153,21 @@ where
             client,
             url_path_getter: Box::new(default_url_path_getter),
             url_query_getter: Box::new(default_url_query_getter),
+            trust_forwarded_headers: false,
         }
     }
 
+    /// Sets whether to keep the forwarding headers of incoming requests and returns new `Proxy`.
+    ///
+    /// By default `X-Forwarded-For`, `X-Forwarded-Proto` and `X-Forwarded-Host` sent by clients are
+    /// replaced. Only enable this when the requests come from trusted proxies, then the client ip is
+    /// appended to `X-Forwarded-For` and the other headers set by them are kept.
+    #[inline]
+    pub fn trust_forwarded_headers(mut self, trusted: bool) -> Self {
+        self.trust_forwarded_headers = trusted;
+        self
+    }
+
     /// Set url path getter.
     #[inline]
     pub fn url_path_getter<G>(mut self, url_path_getter: G) -> Self
@@ -233,24 +247,9 @@ where
         if let Some(host) = forward_url.host().and_then(|host| HeaderValue::from_str(host).ok()) {
             build = build.header(HeaderName::from_static("host"), host);
         }
-        // let x_forwarded_for_header_name = "x-forwarded-for";
-        // // Add forwarding information in the headers
-        // match request.headers_mut().entry(x_forwarded_for_header_name) {
-        //     Ok(header_entry) => {
-        //         match header_entry {
-        //             hyper::header::Entry::Vacant(entry) => {
-        //                 let addr = format!("{}", client_ip);
-        //                 entry.insert(addr.parse().unwrap());
-        //             },
-        //             hyper::header::Entry::Occupied(mut entry) => {
-        //                 let addr = format!("{}, {}", entry.get().to_str().unwrap(), client_ip);
-        //                 entry.insert(addr.parse().unwrap());
-        //             }
-        //         }
-        //     }
-        //     // shouldn't happen...
-        //     Err(_) => panic!("Invalid header name: {}", x_forwarded_for_header_name),
-        // }
+        if let Some(headers) = build.headers_mut() {
+            add_forwarded_headers(req, headers, self.trust_forwarded_headers);
+        }
         build.body(req.take_body()).map_err(Error::other)
     }
 }
@@ -287,12 +286,13 @@ where
                     }
                     Err(e) => {
                         tracing::error!( error = ?e, uri = ?req.uri(), "get response data failed: {}", e);
-                        res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
+                        res.render(StatusError::bad_gateway());
                     }
                 }
             }
             Err(e) => {
                 tracing::error!(error = ?e, "build proxied request failed");
+                res.render(StatusError::internal_server_error());
             }
         }
         if ctrl.has_next() {
@@ -301,6 +301,57 @@ where
         }
     }
 }
+/// Adds `X-Forwarded-For`, `X-Forwarded-Proto`, `X-Forwarded-Host` and `Via` headers to the proxied request.
+///
+/// Unless `trusted`, the forwarding headers sent by the client are replaced. Otherwise the client ip is
+/// appended to an existing `X-Forwarded-For`, while the existing `X-Forwarded-Proto` and `X-Forwarded-Host`
+/// set by the proxies in front are kept.
+fn add_forwarded_headers(req: &Request, headers: &mut HeaderMap, trusted: bool) {
+    let x_forwarded_for = HeaderName::from_static("x-forwarded-for");
+    let x_forwarded_proto = HeaderName::from_static("x-forwarded-proto");
+    let x_forwarded_host = HeaderName::from_static("x-forwarded-host");
+    if !trusted {
+        headers.remove(&x_forwarded_for);
+        headers.remove(&x_forwarded_proto);
+        headers.remove(&x_forwarded_host);
+    }
+    if let Some(addr) = req.remote_addr().clone().into_std() {
+        let mut forwarded_for = headers
+            .get_all(&x_forwarded_for)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !forwarded_for.is_empty() {
+            forwarded_for.push_str(", ");
+        }
+        forwarded_for.push_str(&addr.ip().to_string());
+        if let Ok(value) = HeaderValue::from_str(&forwarded_for) {
+            headers.insert(x_forwarded_for, value);
+        }
+    }
+    if !headers.contains_key(&x_forwarded_proto) {
+        if let Ok(value) = HeaderValue::from_str(req.scheme().as_str()) {
+            headers.insert(x_forwarded_proto, value);
+        }
+    }
+    if !headers.contains_key(&x_forwarded_host) {
+        if let Some(host) = req.headers().get(HOST) {
+            headers.insert(x_forwarded_host, host.clone());
+        }
+    }
+    let version = match req.version() {
+        Version::HTTP_09 => "0.9",
+        Version::HTTP_10 => "1.0",
+        Version::HTTP_2 => "2",
+        Version::HTTP_3 => "3",
+        _ => "1.1",
+    };
+    if let Ok(value) = HeaderValue::from_str(&format!("{version} salvo")) {
+        headers.append(VIA, value);
+    }
+}
+
 #[inline]
 fn get_upgrade_type(headers: &HeaderMap) -> Option<&str> {
     if headers
@@ -363,6 +414,66 @@ mod tests {
             .unwrap();
         assert!(content.contains("Install Rust"));
     }
+    #[tokio::test]
+    async fn test_proxy_forwarded_headers() {
+        #[handler]
+        async fn echo(req: &mut Request) -> String {
+            let header = |name: &str| {
+                req.headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_owned()
+            };
+            format!(
+                "{}|{}|{}|{}|{}",
+                req.uri().path(),
+                header("x-forwarded-for"),
+                header("x-forwarded-proto"),
+                header("x-forwarded-host"),
+                header("via")
+            )
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let server = Server::new(acceptor);
+        let addr = server.holdings()[0].local_addr.clone().into_std().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::with_path("<**rest>").get(echo)));
+
+        #[handler]
+        async fn set_peer(req: &mut Request) {
+            *req.remote_addr_mut() = std::net::SocketAddr::from(([192, 168, 1, 2], 8000)).into();
+        }
+        let request = || {
+            TestClient::get("http://127.0.0.1:5801/api/users")
+                .add_header("host", "salvo.rs", true)
+                .add_header("x-forwarded-for", "10.0.0.1", true)
+                .add_header("x-forwarded-proto", "https", true)
+                .add_header("x-forwarded-host", "evil.com", true)
+        };
+        let upstream = format!("http://{addr}");
+        let router = Router::with_hoop(set_peer)
+            .path("api/<**rest>")
+            .goal(Proxy::default_hyper_client(upstream.clone()));
+        let content = request().send(router).await.take_string().await.unwrap();
+        assert_eq!(content, "/users|192.168.1.2|http|salvo.rs|1.1 salvo");
+
+        let router = Router::with_hoop(set_peer)
+            .path("api/<**rest>")
+            .goal(Proxy::default_hyper_client(upstream).trust_forwarded_headers(true));
+        let content = request().send(router).await.take_string().await.unwrap();
+        assert_eq!(content, "/users|10.0.0.1, 192.168.1.2|https|evil.com|1.1 salvo");
+        handle.stop_forcible();
+    }
+
+    #[tokio::test]
+    async fn test_proxy_bad_gateway() {
+        let router = Router::with_path("<**rest>").goal(Proxy::default_hyper_client("http://127.0.0.1:1"));
+        let res = TestClient::get("http://127.0.0.1:5801/").send(router).await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_GATEWAY));
+    }
+
     #[test]
     fn test_others() {
         let mut handler = Proxy::default_hyper_client(["https://www.bing.com"]);
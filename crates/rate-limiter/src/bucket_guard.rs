@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use super::{BucketQuota, RateGuard};
+
+/// Token bucket implement.
+///
+/// Every request takes a token from the bucket, and tokens are refilled continuously
+/// according to the quota, so bursts up to the bucket capacity are allowed.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BucketGuard {
+    tokens: f64,
+    refilled_at: OffsetDateTime,
+    quota: Option<BucketQuota>,
+}
+
+impl Default for BucketGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BucketGuard {
+    /// Create a new `BucketGuard`.
+    pub fn new() -> Self {
+        Self {
+            tokens: 0.0,
+            refilled_at: OffsetDateTime::now_utc(),
+            quota: None,
+        }
+    }
+
+    fn tokens_per_second(quota: &BucketQuota) -> f64 {
+        let period = quota.period.as_seconds_f64();
+        if period <= 0.0 {
+            f64::INFINITY
+        } else {
+            quota.refill_rate as f64 / period
+        }
+    }
+
+    fn refill(&mut self, quota: &BucketQuota) {
+        let now = OffsetDateTime::now_utc();
+        let elapsed = (now - self.refilled_at).as_seconds_f64().max(0.0);
+        self.tokens = (self.tokens + elapsed * Self::tokens_per_second(quota)).min(quota.capacity as f64);
+        self.refilled_at = now;
+    }
+}
+
+impl RateGuard for BucketGuard {
+    type Quota = BucketQuota;
+    async fn verify(&mut self, quota: &Self::Quota) -> bool {
+        if self.quota.as_ref() != Some(quota) {
+            self.tokens = quota.capacity as f64;
+            self.refilled_at = OffsetDateTime::now_utc();
+            self.quota = Some(quota.clone());
+        } else {
+            self.refill(quota);
+        }
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    async fn remaining(&self, _quota: &Self::Quota) -> usize {
+        self.tokens.floor() as usize
+    }
+
+    /// Returns the time when next token is available.
+    async fn reset(&self, quota: &Self::Quota) -> i64 {
+        let tokens_per_second = Self::tokens_per_second(quota);
+        let wait = if tokens_per_second > 0.0 {
+            Duration::seconds_f64((1.0 - self.tokens).max(0.0) / tokens_per_second)
+        } else {
+            quota.period
+        };
+        (self.refilled_at + wait).unix_timestamp()
+    }
+
+    async fn limit(&self, quota: &Self::Quota) -> usize {
+        quota.capacity
+    }
+}
@@ -8,7 +8,8 @@
 //!
 //! [`QuotaGetter`] is used to get quota for every key.
 //!
-//! [`RateGuard`] is strategy to verify is the request exceeded quota.
+//! [`RateGuard`] is strategy to verify is the request exceeded quota, [`FixedGuard`], [`SlidingGuard`]
+//! and [`BucketGuard`] (token bucket) are provided.
 //!
 //! Read more: <https://salvo.rs>
 #![doc(html_favicon_url = "https://salvo.rs/favicon-32x32.png")]
@@ -27,11 +28,13 @@ use std::hash::Hash;
 
 use salvo_core::conn::SocketAddr;
 use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::header::{HeaderName, RETRY_AFTER};
 use salvo_core::http::{HeaderValue, Request, Response, StatusCode, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+use time::OffsetDateTime;
 
 mod quota;
-pub use quota::{BasicQuota, CelledQuota, QuotaGetter};
+pub use quota::{BasicQuota, BucketQuota, CelledQuota, QuotaGetter};
 #[macro_use]
 mod cfg;
 
@@ -56,6 +59,13 @@ cfg_feature! {
     pub use sliding_guard::SlidingGuard;
 }
 
+cfg_feature! {
+    #![feature = "bucket-guard"]
+
+    mod bucket_guard;
+    pub use bucket_guard::BucketGuard;
+}
+
 /// Issuer is used to identify every request.
 pub trait RateIssuer: Send + Sync + 'static {
     /// The key is used to identify the rate limit.
@@ -87,6 +97,27 @@ impl RateIssuer for RemoteIpIssuer {
     }
 }
 
+/// Identify user by the value of a request header, such as `Authorization`.
+pub struct HeaderIssuer {
+    name: HeaderName,
+}
+impl HeaderIssuer {
+    /// Create a new `HeaderIssuer`.
+    #[inline]
+    pub fn new(name: HeaderName) -> Self {
+        Self { name }
+    }
+}
+impl RateIssuer for HeaderIssuer {
+    type Key = String;
+    async fn issue(&self, req: &mut Request, _depot: &Depot) -> Option<Self::Key> {
+        req.headers()
+            .get(&self.name)
+            .and_then(|v| v.to_str().ok())
+            .map(ToOwned::to_owned)
+    }
+}
+
 /// `RateGuard` is strategy to verify is the request exceeded quota
 pub trait RateGuard: Clone + Send + Sync + 'static {
     /// The quota for the rate limit.
@@ -220,6 +251,9 @@ where
             );
         }
         if !verified {
+            let retry_after = guard.reset(&quota).await - OffsetDateTime::now_utc().unix_timestamp();
+            res.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after.max(1)));
             res.status_code(StatusCode::TOO_MANY_REQUESTS);
             ctrl.skip_rest();
         }
@@ -412,4 +446,49 @@ mod tests {
         assert_eq!(respone.status_code, Some(StatusCode::OK));
         assert_eq!(respone.take_string().await.unwrap(), "Limited page");
     }
+
+    #[tokio::test]
+    async fn test_bucket_guard() {
+        let limiter = RateLimiter::new(
+            BucketGuard::default(),
+            MokaStore::default(),
+            HeaderIssuer::new(salvo_core::http::header::AUTHORIZATION),
+            BucketQuota::per_second(2, 1),
+        )
+        .add_headers(true);
+        let router = Router::new().push(Router::with_path("limited").hoop(limiter).get(limited));
+        let service = Service::new(router);
+
+        for _ in 0..2 {
+            let respone = TestClient::get("http://127.0.0.1:5800/limited")
+                .add_header("authorization", "user1", true)
+                .send(&service)
+                .await;
+            assert_eq!(respone.status_code, Some(StatusCode::OK));
+        }
+        let respone = TestClient::get("http://127.0.0.1:5800/limited")
+            .add_header("authorization", "user1", true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.status_code, Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(respone.headers().get(RETRY_AFTER).unwrap(), "1");
+        assert_eq!(respone.headers().get("x-ratelimit-remaining").unwrap(), "0");
+
+        let respone = TestClient::get("http://127.0.0.1:5800/limited")
+            .add_header("authorization", "user2", true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.status_code, Some(StatusCode::OK));
+
+        let respone = TestClient::get("http://127.0.0.1:5800/limited").send(&service).await;
+        assert_eq!(respone.status_code, Some(StatusCode::BAD_REQUEST));
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+
+        let respone = TestClient::get("http://127.0.0.1:5800/limited")
+            .add_header("authorization", "user1", true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.status_code, Some(StatusCode::OK));
+    }
 }
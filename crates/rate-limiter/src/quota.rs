@@ -109,6 +109,42 @@ impl CelledQuota {
     }
 }
 
+/// A quota for token bucket, the bucket holds at most `capacity` tokens and is refilled
+/// with `refill_rate` tokens every `period`.
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct BucketQuota {
+    /// The max tokens the bucket can hold, it is also the max burst of requests.
+    pub capacity: usize,
+    /// The tokens refilled every period.
+    pub refill_rate: usize,
+    /// The period of refilling.
+    pub period: Duration,
+}
+impl BucketQuota {
+    /// Create new `BucketQuota`.
+    pub const fn new(capacity: usize, refill_rate: usize, period: Duration) -> Self {
+        Self {
+            capacity,
+            refill_rate,
+            period,
+        }
+    }
+
+    /// Sets the capacity and the tokens refilled per second.
+    pub const fn per_second(capacity: usize, refill_rate: usize) -> Self {
+        Self::new(capacity, refill_rate, Duration::seconds(1))
+    }
+    /// Sets the capacity and the tokens refilled per minute.
+    pub const fn per_minute(capacity: usize, refill_rate: usize) -> Self {
+        Self::new(capacity, refill_rate, Duration::seconds(60))
+    }
+    /// Sets the capacity and the tokens refilled per hour.
+    pub const fn per_hour(capacity: usize, refill_rate: usize) -> Self {
+        Self::new(capacity, refill_rate, Duration::seconds(3600))
+    }
+}
+
 impl<Key, T> QuotaGetter<Key> for T
 where
     Key: Hash + Eq + Send + Sync + 'static,
@@ -189,4 +225,18 @@ mod tests {
         assert_eq!(quota.cells, 6);
         assert_eq!(quota.period, Duration::seconds(7200));
     }
+
+    #[test]
+    fn test_bucket_quota() {
+        let quota = BucketQuota::per_second(10, 2);
+        assert_eq!(quota.capacity, 10);
+        assert_eq!(quota.refill_rate, 2);
+        assert_eq!(quota.period, Duration::seconds(1));
+
+        let quota = BucketQuota::per_minute(5, 1);
+        assert_eq!(quota.period, Duration::seconds(60));
+
+        let quota = BucketQuota::per_hour(5, 1);
+        assert_eq!(quota.period, Duration::seconds(3600));
+    }
 }
@@ -90,10 +90,55 @@ cfg_feature! {
     pub use salvo_extra::websocket;
 }
 cfg_feature! {
-    #![feature ="request_id"]
+    #![feature ="request-id"]
     #[doc(no_inline)]
     pub use salvo_extra::request_id;
 }
+cfg_feature! {
+    #![feature ="normalize-path"]
+    #[doc(no_inline)]
+    pub use salvo_extra::normalize_path;
+}
+cfg_feature! {
+    #![feature ="body-debug"]
+    #[doc(no_inline)]
+    pub use salvo_extra::body_debug;
+}
+cfg_feature! {
+    #![feature ="head-method"]
+    #[doc(no_inline)]
+    pub use salvo_extra::head_method;
+}
+cfg_feature! {
+    #![feature ="start-time"]
+    #[doc(no_inline)]
+    pub use salvo_extra::start_time;
+}
+cfg_feature! {
+    #![feature ="request-size"]
+    #[doc(no_inline)]
+    pub use salvo_extra::request_size;
+}
+cfg_feature! {
+    #![feature ="hsts"]
+    #[doc(no_inline)]
+    pub use salvo_extra::hsts;
+}
+cfg_feature! {
+    #![feature ="www-redirect"]
+    #[doc(no_inline)]
+    pub use salvo_extra::www_redirect;
+}
+cfg_feature! {
+    #![feature ="security-headers"]
+    #[doc(no_inline)]
+    pub use salvo_extra::security_headers;
+}
+cfg_feature! {
+    #![feature ="ip-filter"]
+    #[doc(no_inline)]
+    pub use salvo_extra::ip_filter;
+}
 cfg_feature! {
     #![feature ="cache"]
     #[doc(no_inline)]
@@ -166,7 +211,7 @@ pub mod prelude {
     }
     cfg_feature! {
         #![feature ="compression"]
-        pub use salvo_compression::{Compression, CompressionAlgo, CompressionLevel};
+        pub use salvo_compression::{Compression, CompressionAlgo, CompressionLevel, Decompression};
     }
     cfg_feature! {
         #![feature ="csrf"]
@@ -182,7 +227,7 @@ pub mod prelude {
     }
     cfg_feature! {
         #![feature ="logging"]
-        pub use salvo_extra::logging::Logger;
+        pub use salvo_extra::logging::{AccessLog, LogFormat, Logger};
     }
     cfg_feature! {
         #![feature ="proxy"]
@@ -210,7 +255,7 @@ pub mod prelude {
     }
     cfg_feature! {
         #![feature ="timeout"]
-        pub use salvo_extra::timeout::Timeout;
+        pub use salvo_extra::timeout::{RequestTimeout, Timeout};
     }
     cfg_feature! {
         #![feature ="websocket"]
@@ -220,6 +265,42 @@ pub mod prelude {
         #![feature ="request-id"]
         pub use salvo_extra::request_id::RequestId;
     }
+    cfg_feature! {
+        #![feature ="normalize-path"]
+        pub use salvo_extra::normalize_path::NormalizePath;
+    }
+    cfg_feature! {
+        #![feature ="body-debug"]
+        pub use salvo_extra::body_debug::BodyDebugLogger;
+    }
+    cfg_feature! {
+        #![feature ="head-method"]
+        pub use salvo_extra::head_method::HeadMethod;
+    }
+    cfg_feature! {
+        #![feature ="start-time"]
+        pub use salvo_extra::start_time::{StartTime, StartTimer};
+    }
+    cfg_feature! {
+        #![feature ="request-size"]
+        pub use salvo_extra::request_size::RequestSizeLimit;
+    }
+    cfg_feature! {
+        #![feature ="hsts"]
+        pub use salvo_extra::hsts::Hsts;
+    }
+    cfg_feature! {
+        #![feature ="www-redirect"]
+        pub use salvo_extra::www_redirect::{WwwAction, WwwRedirect};
+    }
+    cfg_feature! {
+        #![feature ="security-headers"]
+        pub use salvo_extra::security_headers::{FrameOptions, ReferrerPolicy, SecurityHeaders};
+    }
+    cfg_feature! {
+        #![feature ="ip-filter"]
+        pub use salvo_extra::ip_filter::{IpFilter, IpNet};
+    }
     cfg_feature! {
         #![feature ="serve-static"]
         pub use salvo_serve_static::{StaticFile, StaticDir};
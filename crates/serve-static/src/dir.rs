@@ -1,15 +1,15 @@
 //! serve static dir
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fmt::{self, Display, Write};
 use std::fs::Metadata;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use salvo_core::fs::NamedFile;
-use salvo_core::http::header::ACCEPT_ENCODING;
+use salvo_core::http::header::{ACCEPT_ENCODING, VARY};
 use salvo_core::http::{self, HeaderValue, Request, Response, StatusCode, StatusError};
 use salvo_core::writing::Text;
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, IntoVecString};
@@ -17,7 +17,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use time::{macros::format_description, OffsetDateTime};
 
-use super::{decode_url_path_safely, encode_url_path, format_url_path_safely, join_path, redirect_to_dir_url};
+use super::{
+    decode_url_path_safely, encode_url_path, format_url_path_safely, is_path_escaped, join_path, redirect_to_dir_url,
+    set_cache_max_age,
+};
 
 /// CompressionAlgo
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Hash)]
@@ -47,6 +50,18 @@ impl FromStr for CompressionAlgo {
     }
 }
 
+impl CompressionAlgo {
+    #[inline]
+    fn preference(self) -> u8 {
+        match self {
+            Self::Brotli => 0,
+            Self::Zstd => 1,
+            Self::Gzip => 2,
+            Self::Deflate => 3,
+        }
+    }
+}
+
 impl Display for CompressionAlgo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -119,6 +134,23 @@ where
     }
 }
 
+/// Format of directory listing.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub enum DirListing {
+    /// Choose the format by the request's `Accept` header, HTML is used if nothing matches.
+    #[default]
+    Auto,
+    /// HTML page.
+    Html,
+    /// JSON object with `path`, `files` and `dirs`.
+    Json,
+    /// XML document.
+    Xml,
+    /// Plain text.
+    Text,
+}
+
 /// Handler that serves a directory.
 #[non_exhaustive]
 pub struct StaticDir {
@@ -137,6 +169,8 @@ pub struct StaticDir {
     exclude_filters: Vec<Box<dyn Fn(&str) -> bool + Send + Sync>>,
     /// Auto list the directory if default file not found.
     pub auto_list: bool,
+    /// Format of directory listing, only used when `auto_list` is `true`.
+    pub listing: DirListing,
     /// Compressed variations.
     ///
     /// The key is the compression algorithm, and the value is the file extension.
@@ -146,6 +180,8 @@ pub struct StaticDir {
     pub defaults: Vec<String>,
     /// Fallback file name. This is used when the requested file is not found.
     pub fallback: Option<String>,
+    /// Max age of served files, `Cache-Control: public, max-age=...` header is set if it is not `None`.
+    pub max_age: Option<Duration>,
 }
 impl StaticDir {
     /// Create new `StaticDir`.
@@ -163,9 +199,11 @@ impl StaticDir {
             include_dot_files: false,
             exclude_filters: vec![],
             auto_list: false,
+            listing: DirListing::Auto,
             compressed_variations,
             defaults: vec![],
             fallback: None,
+            max_age: None,
         }
     }
 
@@ -195,6 +233,14 @@ impl StaticDir {
         self
     }
 
+    /// Enables directory listing with the given format and returns a new `StaticDir`.
+    #[inline]
+    pub fn listing(mut self, listing: DirListing) -> Self {
+        self.auto_list = true;
+        self.listing = listing;
+        self
+    }
+
     /// Sets compressed_variations and returns a new `StaticDirOptions`.
     #[inline]
     pub fn compressed_variation<A>(mut self, algo: A, exts: &str) -> Self
@@ -231,6 +277,20 @@ impl StaticDir {
         self
     }
 
+    /// Sets max age of served files and returns a new `StaticDir`.
+    ///
+    /// The `Cache-Control: public, max-age=...` header is added to responses of files.
+    #[inline]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    #[inline]
+    fn is_excluded(&self, path: &str) -> bool {
+        self.exclude_filters.iter().any(|filter| filter(path))
+    }
+
     #[inline]
     fn is_compressed_ext(&self, ext: &str) -> bool {
         for exts in self.compressed_variations.values() {
@@ -294,6 +354,10 @@ impl Handler for StaticDir {
         } else {
             decode_url_path_safely(req_path)
         };
+        if is_path_escaped(&rel_path) {
+            res.render(StatusError::forbidden());
+            return;
+        }
         let rel_path = format_url_path_safely(&rel_path);
         let mut files: HashMap<String, Metadata> = HashMap::new();
         let mut dirs: HashMap<String, Metadata> = HashMap::new();
@@ -306,10 +370,8 @@ impl Handler for StaticDir {
         if self.include_dot_files || !is_dot_file {
             for root in &self.roots {
                 let raw_path = join_path!(root, &rel_path);
-                for filter in &self.exclude_filters {
-                    if filter(&raw_path) {
-                        continue;
-                    }
+                if self.is_excluded(&raw_path) {
+                    continue;
                 }
                 let path = Path::new(&raw_path);
                 if path.is_dir() {
@@ -341,10 +403,8 @@ impl Handler for StaticDir {
         if abs_path.is_none() && !fallback.is_empty() {
             for root in &self.roots {
                 let raw_path = join_path!(root, fallback);
-                for filter in &self.exclude_filters {
-                    if filter(&raw_path) {
-                        continue;
-                    }
+                if self.is_excluded(&raw_path) {
+                    continue;
                 }
                 let path = Path::new(&raw_path);
                 if path.is_file() {
@@ -368,35 +428,45 @@ impl Handler for StaticDir {
             let mut content_encoding = None;
             let named_path = if !is_compressed_ext {
                 if !self.compressed_variations.is_empty() {
-                    let mut new_abs_path = None;
                     let header = req
                         .headers()
                         .get(ACCEPT_ENCODING)
                         .and_then(|v| v.to_str().ok())
                         .unwrap_or_default();
-                    let accept_algos = http::parse_accept_encoding(header)
+                    let mut accept_algos = http::parse_accept_encoding(header)
                         .into_iter()
-                        .filter_map(|(algo, _level)| {
-                            if let Ok(algo) = algo.parse::<CompressionAlgo>() {
-                                Some(algo)
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<HashSet<_>>();
+                        .filter(|(_, level)| *level > 0)
+                        .filter_map(|(algo, level)| algo.parse::<CompressionAlgo>().ok().map(|algo| (algo, level)))
+                        .collect::<Vec<_>>();
+                    // Algorithms with the same weight are preferred by their compression ratio.
+                    accept_algos.sort_by(|(a, a_level), (b, b_level)| {
+                        b_level.cmp(a_level).then_with(|| a.preference().cmp(&b.preference()))
+                    });
+                    let mut variations = Vec::new();
                     for (algo, exts) in &self.compressed_variations {
-                        if accept_algos.contains(algo) {
-                            for zip_ext in exts {
-                                let mut path = abs_path.clone();
-                                path.as_mut_os_string().push(&*format!(".{}", zip_ext));
-                                if path.is_file() {
-                                    new_abs_path = Some(path);
-                                    content_encoding = Some(algo.to_string());
-                                    break;
-                                }
+                        for zip_ext in exts {
+                            let mut path = abs_path.clone();
+                            path.as_mut_os_string().push(&*format!(".{}", zip_ext));
+                            if path.is_file() {
+                                variations.push((*algo, path));
+                                break;
                             }
                         }
                     }
+                    if !variations.is_empty() {
+                        res.headers_mut()
+                            .append(VARY, HeaderValue::from_static("accept-encoding"));
+                    }
+                    let new_abs_path = accept_algos.iter().find_map(|(algo, _)| {
+                        variations
+                            .iter()
+                            .position(|(variation, _)| variation == algo)
+                            .map(|index| variations.swap_remove(index))
+                    });
+                    let new_abs_path = new_abs_path.map(|(algo, path)| {
+                        content_encoding = Some(algo.to_string());
+                        path
+                    });
                     new_abs_path.unwrap_or(abs_path)
                 } else {
                     abs_path
@@ -417,6 +487,9 @@ impl Handler for StaticDir {
                 builder
             };
             if let Ok(named_file) = builder.build().await {
+                if let Some(max_age) = self.max_age {
+                    set_cache_max_age(res, max_age);
+                }
                 let headers = req.headers();
                 named_file.send(headers, res).await;
             } else {
@@ -429,10 +502,8 @@ impl Handler for StaticDir {
                     let file_name = entry.file_name().to_string_lossy().to_string();
                     if self.include_dot_files || !file_name.starts_with('.') {
                         let raw_path = join_path!(&abs_path, &file_name);
-                        for filter in &self.exclude_filters {
-                            if filter(&raw_path) {
-                                continue;
-                            }
+                        if self.is_excluded(&raw_path) {
+                            continue;
                         }
                         if let Ok(metadata) = entry.metadata().await {
                             if metadata.is_dir() {
@@ -458,10 +529,19 @@ impl Handler for StaticDir {
             dirs.sort_by(|a, b| a.name.cmp(&b.name));
             let root = CurrentInfo::new(decode_url_path_safely(req_path), files, dirs);
             res.status_code(StatusCode::OK);
-            match format.subtype().as_ref() {
-                "plain" => res.render(Text::Plain(list_text(&root))),
-                "json" => res.render(Text::Json(list_json(&root))),
-                "xml" => res.render(Text::Xml(list_xml(&root))),
+            let listing = match self.listing {
+                DirListing::Auto => match format.subtype().as_ref() {
+                    "plain" => DirListing::Text,
+                    "json" => DirListing::Json,
+                    "xml" => DirListing::Xml,
+                    _ => DirListing::Html,
+                },
+                listing => listing,
+            };
+            match listing {
+                DirListing::Text => res.render(Text::Plain(list_text(&root))),
+                DirListing::Json => res.render(Text::Json(list_json(&root))),
+                DirListing::Xml => res.render(Text::Xml(list_xml(&root))),
                 _ => res.render(Text::Html(list_html(&root))),
             };
         }
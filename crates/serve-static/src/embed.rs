@@ -1,14 +1,33 @@
 use std::borrow::Cow;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use rust_embed::{EmbeddedFile, Metadata, RustEmbed};
 use salvo_core::http::header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH};
-use salvo_core::http::{Mime, Request, Response, StatusCode};
+use salvo_core::http::{HeaderValue, Mime, Request, Response, StatusCode};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, IntoVecString};
 
-use super::{decode_url_path_safely, format_url_path_safely, redirect_to_dir_url, join_path};
+use super::{decode_url_path_safely, format_url_path_safely, redirect_to_dir_url, join_path, set_cache_max_age};
 
 /// Handler that serves embed file.
+///
+/// The `ETag` header is derived from the SHA256 hash of the file provided by `rust-embed`, and
+/// `304 Not Modified` is returned if it matches the request's `If-None-Match` header.
+///
+/// # Example
+///
+/// ```
+/// use rust_embed::RustEmbed;
+/// use salvo_core::prelude::*;
+/// use salvo_serve_static::static_embed;
+///
+/// #[derive(RustEmbed)]
+/// #[folder = "test"]
+/// struct Assets;
+///
+/// // `static/index.html` in assets is served at `/index.html`.
+/// let router = Router::with_path("<*path>").get(static_embed::<Assets>().prefix("static").defaults("index.html"));
+/// ```
 #[non_exhaustive]
 #[derive(Default)]
 pub struct StaticEmbed<T> {
@@ -17,6 +36,10 @@ pub struct StaticEmbed<T> {
     pub defaults: Vec<String>,
     /// Fallback file name. This is used when the requested file is not found.
     pub fallback: Option<String>,
+    /// Path prefix of files in the assets, it is prepended to the request path when looking up files.
+    pub prefix: Option<String>,
+    /// Max age of served files, `Cache-Control: public, max-age=...` header is set if it is not `None`.
+    pub max_age: Option<Duration>,
 }
 
 /// Create a new `StaticEmbed` middleware.
@@ -26,6 +49,8 @@ pub fn static_embed<T: RustEmbed>() -> StaticEmbed<T> {
         _assets: PhantomData,
         defaults: vec![],
         fallback: None,
+        prefix: None,
+        max_age: None,
     }
 }
 
@@ -46,21 +71,22 @@ fn render_embedded_data(
     let mime = mime.unwrap_or_else(|| mime_infer::from_path(req.uri().path()).first_or_octet_stream());
     res.headers_mut().insert(CONTENT_TYPE, mime.as_ref().parse().unwrap());
 
-    let hash = hex::encode(metadata.sha256_hash());
+    let etag = format!("\"{}\"", hex::encode(metadata.sha256_hash()));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        res.headers_mut().insert(ETAG, value);
+    }
     // if etag is matched, return 304
     if req
         .headers()
         .get(IF_NONE_MATCH)
-        .map(|etag| etag.to_str().unwrap_or("000000").eq(&hash))
+        .and_then(|value| value.to_str().ok())
+        .map(|value| etag_matches(value, &etag))
         .unwrap_or(false)
     {
         res.status_code(StatusCode::NOT_MODIFIED);
         return;
     }
 
-    // otherwise, return 200 with etag hash
-    res.headers_mut().insert(ETAG, hash.parse().unwrap());
-
     match data {
         Cow::Borrowed(data) => {
             res.write_body(data).ok();
@@ -71,6 +97,15 @@ fn render_embedded_data(
     }
 }
 
+/// Returns `true` if the `If-None-Match` header value matches `etag`, weak comparison is used.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_matches('"');
+    if_none_match.split(',').any(|tag| {
+        let tag = tag.trim();
+        tag == "*" || tag.strip_prefix("W/").unwrap_or(tag).trim_matches('"') == etag
+    })
+}
+
 impl<T> StaticEmbed<T>
 where
     T: RustEmbed + Send + Sync + 'static,
@@ -78,11 +113,7 @@ where
     /// Create a new `StaticEmbed`.
     #[inline]
     pub fn new() -> Self {
-        Self {
-            _assets: PhantomData,
-            defaults: vec![],
-            fallback: None,
-        }
+        static_embed()
     }
 
     /// Create a new `StaticEmbed` with defaults.
@@ -98,6 +129,33 @@ where
         self.fallback = Some(fallback.into());
         self
     }
+
+    /// Sets path prefix of files in the assets and returns a new `StaticEmbed`.
+    ///
+    /// For example, with prefix `dist`, the asset `dist/index.html` is served at `/index.html`. The prefix
+    /// also applies to defaults and fallback.
+    #[inline]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        let prefix = format_url_path_safely(&prefix.into());
+        self.prefix = if prefix.is_empty() { None } else { Some(prefix) };
+        self
+    }
+
+    /// Sets max age of served files and returns a new `StaticEmbed`.
+    #[inline]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    #[inline]
+    fn asset_path<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        match &self.prefix {
+            Some(prefix) if path.is_empty() => Cow::Owned(prefix.clone()),
+            Some(prefix) => Cow::Owned(format!("{prefix}/{path}")),
+            None => Cow::Borrowed(path),
+        }
+    }
 }
 #[async_trait]
 impl<T> Handler for StaticEmbed<T>
@@ -112,11 +170,12 @@ where
             decode_url_path_safely(req.uri().path())
         };
         let req_path = format_url_path_safely(&req_path);
-        let mut key_path = Cow::Borrowed(&*req_path);
-        let mut embedded_file = T::get(req_path.as_str());
+        let asset_path = self.asset_path(&req_path);
+        let mut key_path = Cow::Borrowed(&*asset_path);
+        let mut embedded_file = T::get(&asset_path);
         if embedded_file.is_none() {
             for ifile in &self.defaults {
-                let ipath = join_path!(&req_path, ifile);
+                let ipath = join_path!(&*asset_path, ifile);
                 if let Some(file) = T::get(&ipath) {
                     embedded_file = Some(file);
                     key_path = Cow::from(ipath);
@@ -131,9 +190,10 @@ where
         if embedded_file.is_none() {
             let fallback = self.fallback.as_deref().unwrap_or_default();
             if !fallback.is_empty() {
-                if let Some(file) = T::get(fallback) {
+                let fallback = self.asset_path(fallback);
+                if let Some(file) = T::get(&fallback) {
                     embedded_file = Some(file);
-                    key_path = Cow::from(fallback);
+                    key_path = Cow::Owned(fallback.into_owned());
                 }
             }
         }
@@ -141,6 +201,9 @@ where
         match embedded_file {
             Some(file) => {
                 let mime = mime_infer::from_path(&*key_path).first_or_octet_stream();
+                if let Some(max_age) = self.max_age {
+                    set_cache_max_age(res, max_age);
+                }
                 render_embedded_file(file, req, res, Some(mime));
             }
             None => {
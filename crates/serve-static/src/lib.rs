@@ -13,12 +13,16 @@
 pub mod dir;
 mod file;
 
+use std::time::Duration;
+
 use percent_encoding::{utf8_percent_encode, CONTROLS};
+use salvo_core::http::header::CACHE_CONTROL;
 use salvo_core::http::uri::{Parts as UriParts, Uri};
+use salvo_core::http::HeaderValue;
 use salvo_core::writing::Redirect;
 use salvo_core::Response;
 
-pub use dir::StaticDir;
+pub use dir::{DirListing, StaticDir};
 pub use file::StaticFile;
 
 #[macro_use]
@@ -59,6 +63,25 @@ pub(crate) fn decode_url_path_safely(path: &str) -> String {
         .to_string()
 }
 
+/// Returns `true` if the `..` segments in `path` go above the root.
+#[inline]
+pub(crate) fn is_path_escaped(path: &str) -> bool {
+    let mut depth = 0usize;
+    for part in path.split(['/', '\\']) {
+        if part.is_empty() || part == "." {
+            continue;
+        } else if part == ".." {
+            if depth == 0 {
+                return true;
+            }
+            depth -= 1;
+        } else {
+            depth += 1;
+        }
+    }
+    false
+}
+
 #[inline]
 pub(crate) fn format_url_path_safely(path: &str) -> String {
     let mut used_parts = Vec::with_capacity(8);
@@ -74,6 +97,13 @@ pub(crate) fn format_url_path_safely(path: &str) -> String {
     used_parts.join("/")
 }
 
+/// Sets `Cache-Control: public, max-age=...` header of served files.
+pub(crate) fn set_cache_max_age(res: &mut Response, max_age: Duration) {
+    if let Ok(value) = HeaderValue::try_from(format!("public, max-age={}", max_age.as_secs())) {
+        res.headers_mut().insert(CACHE_CONTROL, value);
+    }
+}
+
 pub(crate) fn redirect_to_dir_url(req_uri: &Uri, res: &mut Response) {
     let UriParts {
         scheme,
@@ -101,6 +131,8 @@ pub(crate) fn redirect_to_dir_url(req_uri: &Uri, res: &mut Response) {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use salvo_core::prelude::*;
     use salvo_core::test::{ResponseExt, TestClient};
 
@@ -168,6 +200,109 @@ mod tests {
         assert!(content == "copy3");
     }
 
+    #[tokio::test]
+    async fn test_serve_static_dir_conditional() {
+        use salvo_core::http::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+
+        let router = Router::with_path("<*path>").get(StaticDir::new("test/static").max_age(Duration::from_secs(3600)));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/test1.txt").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "public, max-age=3600");
+        let etag = res.headers().get(ETAG).unwrap().clone();
+
+        let res = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header(IF_NONE_MATCH, etag, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_MODIFIED));
+
+        let res = TestClient::get("http://127.0.0.1:5801/dir1/..%2F..%2Ftest1.txt")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+
+        let res = TestClient::get("http://127.0.0.1:5801/notexist.txt")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn test_serve_static_dir_compressed() {
+        use salvo_core::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE, VARY};
+
+        let router = Router::with_path("<*path>").get(StaticDir::new("test/static"));
+        let service = Service::new(router);
+
+        async fn access(service: &Service, accept_encoding: &str) -> Response {
+            TestClient::get("http://127.0.0.1:5801/compressed/app.js")
+                .add_header(ACCEPT_ENCODING, accept_encoding, true)
+                .send(service)
+                .await
+        }
+
+        let mut res = access(&service, "deflate, gzip").await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(res.headers().get(VARY).unwrap(), "accept-encoding");
+        assert!(res
+            .headers()
+            .get(CONTENT_TYPE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .contains("javascript"));
+        assert_eq!(
+            res.take_bytes(None).await.unwrap(),
+            std::fs::read("test/static/compressed/app.js.gz").unwrap()
+        );
+
+        let res = access(&service, "gzip;q=0.5, deflate").await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "deflate");
+
+        let mut res = access(&service, "br, gzip;q=0").await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.headers().get(VARY).unwrap(), "accept-encoding");
+        assert_eq!(res.take_string().await.unwrap(), "console.log(\"hello salvo\");\n");
+    }
+
+    #[tokio::test]
+    async fn test_serve_static_dir_listing() {
+        let router = Router::new()
+            .push(Router::with_path("json/<*path>").get(StaticDir::new("test/static").listing(DirListing::Json)))
+            .push(
+                Router::with_path("excluded/<*path>").get(
+                    StaticDir::new("test/static")
+                        .listing(DirListing::Text)
+                        .exclude(|path| path.ends_with(".gz")),
+                ),
+            )
+            .push(Router::with_path("off/<*path>").get(StaticDir::new("test/static")));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/json/dir1/")
+            .add_header("accept", "text/html", true)
+            .send(&service)
+            .await;
+        let content = res.take_string().await.unwrap();
+        assert!(content.starts_with('{') && content.contains("test3.txt") && content.contains("dir2"));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/json/dir1/test3.txt")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "copy3");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/excluded/compressed/")
+            .send(&service)
+            .await;
+        let content = res.take_string().await.unwrap();
+        assert!(content.contains("app.js.deflate") && !content.contains("app.js.gz"));
+
+        let res = TestClient::get("http://127.0.0.1:5801/off/dir1/").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
+
     #[tokio::test]
     async fn test_serve_static_file() {
         let router = Router::new()
@@ -247,4 +382,45 @@ mod tests {
             .await;
         assert_eq!(response.status_code.unwrap(), StatusCode::NOT_FOUND);
     }
+
+    #[cfg(feature = "embed")]
+    #[tokio::test]
+    async fn test_serve_embed_files_prefix_and_etag() {
+        use salvo_core::http::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+
+        #[derive(rust_embed::RustEmbed)]
+        #[folder = "test"]
+        struct Assets;
+
+        let router = Router::with_path("<*path>").get(
+            static_embed::<Assets>()
+                .prefix("static/dir1")
+                .max_age(Duration::from_secs(60)),
+        );
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/test3.txt").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "public, max-age=60");
+        let etag = res.headers().get(ETAG).unwrap().clone();
+        assert!(etag.to_str().unwrap().starts_with('"'));
+        assert_eq!(res.take_string().await.unwrap(), "copy3");
+
+        let res = TestClient::get("http://127.0.0.1:5801/test3.txt")
+            .add_header(IF_NONE_MATCH, format!("W/{}", etag.to_str().unwrap()), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_MODIFIED));
+        assert_eq!(res.headers().get(ETAG).unwrap(), &etag);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/dir2/test3.txt")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "dir2 test3");
+
+        let res = TestClient::get("http://127.0.0.1:5801/static/dir1/test3.txt")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
 }
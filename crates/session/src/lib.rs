@@ -55,6 +55,16 @@ If there are any failures in the above session retrieval process, a
 new empty session is generated for the request, which proceeds through
 the application as normal.
 
+### Regeneration
+
+To prevent session fixation, call [`Session::regenerate`] or replace the session in depot after
+privilege escalation, such as login. The session is stored again with a new cookie value and the
+old session is removed from the store.
+
+## Flash messages
+
+Flash messages are provided by the `salvo-flash` crate, which can store them in session.
+
 ## Stale/expired session cleanup
 
 Any session store other than the cookie store will accumulate stale
@@ -317,7 +327,8 @@ where
         let cookie = req.cookies().get(&self.cookie_name);
         let cookie_value = cookie.and_then(|cookie| self.verify_signature(cookie.value()).ok());
 
-        let mut session = self.load_or_create(cookie_value).await;
+        let loaded = self.load(cookie_value).await;
+        let mut session = loaded.clone().unwrap_or_default();
 
         if let Some(ttl) = self.session_ttl {
             session.expire_in(ttl);
@@ -331,12 +342,23 @@ where
         }
 
         let session = depot.take_session().expect("session should exist in depot");
+        // The session is regenerated or replaced, e.g. after login, remove the old one from store,
+        // so the old cookie can not be used any more.
+        let regenerated = match loaded {
+            Some(loaded) if loaded.id() != session.id() => {
+                if let Err(e) = self.store.destroy_session(loaded).await {
+                    tracing::error!(error = ?e, "unable to destroy regenerated session");
+                }
+                true
+            }
+            _ => false,
+        };
         if session.is_destroyed() {
             if let Err(e) = self.store.destroy_session(session).await {
                 tracing::error!(error = ?e, "unable to destroy session");
             }
             res.remove_cookie(&self.cookie_name);
-        } else if self.save_unchanged || session.data_changed() {
+        } else if self.save_unchanged || regenerated || session.data_changed() {
             match self.store.store_session(session).await {
                 Ok(cookie_value) => {
                     if let Some(cookie_value) = cookie_value {
@@ -362,13 +384,13 @@ where
         HandlerBuilder::new(store, secret)
     }
     #[inline]
-    async fn load_or_create(&self, cookie_value: Option<String>) -> Session {
+    async fn load(&self, cookie_value: Option<String>) -> Option<Session> {
         let session = match cookie_value {
             Some(cookie_value) => self.store.load_session(cookie_value).await.ok().flatten(),
             None => None,
         };
 
-        session.and_then(|session| session.validate()).unwrap_or_default()
+        session.and_then(|session| session.validate())
     }
     // the following is reused verbatim from
     // https://github.com/SergioBenitez/cookie-rs/blob/master/src/secure/signed.rs#L51-L66
@@ -533,4 +555,56 @@ mod tests {
         let mut respone = TestClient::get("http://127.0.0.1:5800/").send(&service).await;
         assert_eq!(respone.take_string().await.unwrap(), "home");
     }
+
+    #[tokio::test]
+    async fn test_session_regenerate() {
+        #[handler]
+        pub async fn login(depot: &mut Depot) {
+            depot.session_mut().unwrap().insert("username", "salvo").unwrap();
+        }
+        #[handler]
+        pub async fn escalate(depot: &mut Depot) {
+            depot.session_mut().unwrap().regenerate();
+        }
+        #[handler]
+        pub async fn home(depot: &mut Depot) -> String {
+            depot
+                .session()
+                .and_then(|session| session.get::<String>("username"))
+                .unwrap_or_else(|| "home".into())
+        }
+
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .get(home)
+            .push(Router::with_path("login").get(login))
+            .push(Router::with_path("escalate").get(escalate));
+        let service = Service::new(router);
+
+        let respone = TestClient::get("http://127.0.0.1:5800/login").send(&service).await;
+        let old_cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        let respone = TestClient::get("http://127.0.0.1:5800/escalate")
+            .add_header(COOKIE, &old_cookie, true)
+            .send(&service)
+            .await;
+        let new_cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        assert_ne!(old_cookie, new_cookie);
+
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, &new_cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "salvo");
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, &old_cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "home");
+    }
 }
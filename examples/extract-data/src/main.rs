@@ -41,7 +41,8 @@ async fn edit(req: &mut Request) -> String {
 #[salvo(extract(
     default_source(from = "query"),
     default_source(from = "param"),
-    default_source(from = "body")
+    default_source(from = "body"),
+    compile_check = "required"
 ))]
 struct BadMan<'a> {
     id: i64,
@@ -55,7 +56,8 @@ struct BadMan<'a> {
     default_source(from = "query"),
     default_source(from = "param"),
     default_source(from = "body"),
-    rename_all = "camelCase"
+    rename_all = "camelCase",
+    compile_check = "required"
 ))]
 struct GoodMan<'a> {
     id: i64,
@@ -35,11 +35,12 @@ async fn edit<'a>(bad_man: BadMan<'a>) -> String {
 }
 
 #[derive(Serialize, Deserialize, Extractible, Debug)]
-#[salvo(
+#[salvo(extract(
     default_source(from = "query"),
     default_source(from = "param"),
-    default_source(from = "body")
-)]
+    default_source(from = "body"),
+    compile_check = "required"
+))]
 struct BadMan<'a> {
     id: i64,
     username: String,
@@ -48,17 +49,18 @@ struct BadMan<'a> {
     lovers: Vec<String>,
 }
 #[derive(Serialize, Deserialize, Extractible, Debug)]
-#[salvo(
+#[salvo(extract(
     default_source(from = "query"),
     default_source(from = "param"),
-    default_source(from = "body")
-)]
+    default_source(from = "body"),
+    compile_check = "required"
+))]
 struct GoodMan<'a> {
     id: i64,
     username: &'a str,
     first_name: String,
     last_name: String,
-    #[salvo(alias = "lovers")]
+    #[salvo(extract(alias = "lovers"))]
     lover: String,
 }
 
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use salvo::prelude::*;
 use salvo::server::ServerHandle;
 use tokio::signal;
@@ -43,6 +45,6 @@ async fn listen_shutdown_signal(handle: ServerHandle) {
         _ = terminate => println!("terminate signal received"),
     };
 
-    // Graceful Shutdown Server
-    handle.stop_graceful(None);
+    // Graceful Shutdown Server, wait at most 60 seconds for in-flight requests
+    handle.graceful_stop(Duration::from_secs(60)).await;
 }